@@ -1,12 +1,18 @@
 use core::{char::CharTryFromError, fmt};
 
+#[cfg(feature = "uuid")]
+use crate::combinator::Map;
 use crate::decoder::{Decoder, Error as DError, Result as DResult};
-use crate::endian::{ByteOrder, Endian, NativeEndian};
-use crate::Size;
+use crate::encoder::{Encoder, Error as EError, Result as EResult};
+use crate::endian::{BigEndian, ByteOrder, Endian, NativeEndian};
+#[cfg(feature = "bitflags")]
+use crate::frame::Layout;
+use crate::{Size, SizeHint};
 
 /// A binary [`Decoder`] that is capable of reading a [`prim@bool`]
 /// in a specified byte order.
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Bool<B: ByteOrder = NativeEndian> {
     /// The byte order for this decoder.
     pub byte_order: B,
@@ -37,6 +43,11 @@ impl<B: ByteOrder> Decoder for Bool<B> {
         Size::new(1)
     }
 
+    #[inline]
+    fn size_hint(&self) -> SizeHint {
+        SizeHint::exact(self.hint())
+    }
+
     #[inline]
     fn decode<'s>(&mut self, src: &mut &'s [u8]) -> DResult<'s, Self> {
         let mut _src = *src;
@@ -61,6 +72,7 @@ impl<B: ByteOrder> Decoder for Bool<B> {
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct BoolError(());
 
 impl fmt::Display for BoolError {
@@ -69,12 +81,12 @@ impl fmt::Display for BoolError {
     }
 }
 
-#[cfg(feature = "std")]
-impl std::error::Error for BoolError {}
+impl core::error::Error for BoolError {}
 
 /// A binary [`Decoder`] that is capable of reading a [`prim@char`]
 /// in a specified byte order.
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Char<B: ByteOrder = NativeEndian> {
     /// The byte order for this decoder.
     pub byte_order: B,
@@ -105,6 +117,11 @@ impl<B: ByteOrder> Decoder for Char<B> {
         Size::new(4)
     }
 
+    #[inline]
+    fn size_hint(&self) -> SizeHint {
+        SizeHint::exact(self.hint())
+    }
+
     #[inline]
     fn decode<'s>(&mut self, src: &mut &'s [u8]) -> DResult<'s, Self> {
         let mut _src = *src;
@@ -135,6 +152,7 @@ macro_rules! define {
             )]
             $(#[$attr])*
             #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+            #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
             $vis struct $name<B: ByteOrder = NativeEndian> {
                 /// The byte order for this decoder.
                 pub byte_order: B,
@@ -174,6 +192,11 @@ macro_rules! define {
                     Size::new(Self::SIZE)
                 }
 
+                #[inline]
+                fn size_hint(&self) -> SizeHint {
+                    SizeHint::exact(self.hint())
+                }
+
                 #[inline]
                 fn decode<'s>(&mut self, src: &mut &'s [u8]) -> DResult<'s, Self> {
                     let Some((bytes, rest)) = src.split_at_checked(Self::SIZE) else {
@@ -213,3 +236,484 @@ define! {
     pub struct F32<f32> {}
     pub struct F64<f64> {}
 }
+
+/// A [`Decoder`] that reads nothing and yields a fixed length.
+///
+/// This is useful as the length of a [`Bytes`] decoder for fixed-size
+/// regions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Fixed(pub usize);
+
+impl Decoder for Fixed {
+    type Item<'src> = usize;
+    type Error = ::core::convert::Infallible;
+
+    #[inline]
+    fn hint(&self) -> Size {
+        Size::Zero
+    }
+
+    #[inline]
+    fn size_hint(&self) -> SizeHint {
+        SizeHint::exact(Size::Zero)
+    }
+
+    #[inline]
+    fn decode<'s>(&mut self, _: &mut &'s [u8]) -> DResult<'s, Self> {
+        Ok(self.0)
+    }
+}
+
+/// A binary [`Decoder`] that is capable of reading a region of bytes
+/// whose length is read by another decoder.
+///
+/// It is also an [`Encoder`] when the length can be encoded as a
+/// [`prim@u64`].
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Bytes<L> {
+    /// The decoder for the length of the region.
+    pub len: L,
+    /// The maximum length of the region.
+    ///
+    /// Longer regions are a fatal error rather than incomplete.
+    pub max_len: usize,
+}
+
+impl<L> Bytes<L> {
+    /// Create a new decoder for a region of bytes.
+    #[inline]
+    #[must_use]
+    pub const fn new(len: L) -> Bytes<L> {
+        Bytes {
+            len,
+            max_len: usize::MAX,
+        }
+    }
+
+    /// Set the maximum length of the region.
+    #[inline]
+    #[must_use]
+    pub const fn with_max_len(mut self, max_len: usize) -> Bytes<L> {
+        self.max_len = max_len;
+        self
+    }
+}
+
+impl Bytes<Fixed> {
+    /// Create a new decoder for a region of exactly `len` bytes.
+    #[inline]
+    #[must_use]
+    pub const fn fixed(len: usize) -> Bytes<Fixed> {
+        Bytes::new(Fixed(len))
+    }
+}
+
+impl<L: Default> Default for Bytes<L> {
+    #[inline]
+    fn default() -> Self {
+        Bytes::new(L::default())
+    }
+}
+
+impl<L> Decoder for Bytes<L>
+where
+    L: Decoder,
+    for<'a> L::Item<'a>: TryInto<usize>,
+{
+    type Item<'src> = &'src [u8];
+    type Error = BytesError<L::Error>;
+
+    #[inline]
+    fn hint(&self) -> Size {
+        self.len.hint()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> SizeHint {
+        let len = self.len.size_hint();
+
+        SizeHint::new(len.min, len.max + Size::new(self.max_len))
+    }
+
+    #[inline]
+    fn decode<'s>(&mut self, src: &mut &'s [u8]) -> DResult<'s, Self> {
+        let mut rest = *src;
+
+        let len = self
+            .len
+            .decode(&mut rest)
+            .map_err(|e| e.map(BytesError::Length))?;
+        let len = match len.try_into() {
+            Ok(len) if len <= self.max_len => len,
+            _ => {
+                return Err(DError::Fatal {
+                    error: BytesError::TooLong,
+                })
+            }
+        };
+
+        let Some((bytes, rest)) = rest.split_at_checked(len) else {
+            return Err(DError::Incomplete {
+                needed: Size::new(len - rest.len()),
+            });
+        };
+
+        *src = rest;
+
+        Ok(bytes)
+    }
+}
+
+impl<L: Encoder<u64>> Encoder<[u8]> for Bytes<L> {
+    type Error = BytesError<L::Error>;
+
+    #[inline]
+    fn encode_hint(&self, item: &[u8]) -> Size {
+        self.len.encode_hint(&(item.len() as u64)) + Size::new(item.len())
+    }
+
+    fn encode(&mut self, item: &[u8], dst: &mut &mut [u8]) -> EResult<Self::Error> {
+        if item.len() > self.max_len {
+            return Err(EError::Fatal {
+                error: BytesError::TooLong,
+            });
+        }
+
+        let capacity = dst.len();
+        let mut rest = &mut **dst;
+
+        self.len
+            .encode(&(item.len() as u64), &mut rest)
+            .map_err(|error| match error {
+                EError::Full { needed } => EError::Full {
+                    needed: needed + Size::new(item.len()),
+                },
+                error => error.map(BytesError::Length),
+            })?;
+
+        if rest.len() < item.len() {
+            return Err(EError::Full {
+                needed: Size::new(item.len() - rest.len()),
+            });
+        }
+
+        let len = capacity - rest.len() + item.len();
+        let (bytes, rest) = core::mem::take(dst).split_at_mut(len);
+
+        bytes[len - item.len()..].copy_from_slice(item);
+
+        *dst = rest;
+
+        Ok(())
+    }
+}
+
+/// Type for errors that may occur while reading a region of bytes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum BytesError<E> {
+    /// The length could not be read.
+    Length(E),
+    /// The length is too large.
+    TooLong,
+}
+
+impl<E: fmt::Display> fmt::Display for BytesError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Length(error) => write!(f, "invalid length: {error}"),
+            Self::TooLong => f.write_str("length too large"),
+        }
+    }
+}
+
+impl<E: fmt::Display + fmt::Debug> core::error::Error for BytesError<E> {}
+
+/// A binary [`Decoder`] and [`Encoder`] for a 16 byte UUID.
+///
+/// UUIDs are yielded in the big endian layout of RFC 4122. With a little
+/// endian byte order, the source is in the mixed-endian layout of
+/// Microsoft's GUIDs instead, where the first three fields are little
+/// endian.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Uuid<B: ByteOrder = BigEndian> {
+    /// The byte order of the first three fields.
+    pub byte_order: B,
+}
+
+impl<B: ByteOrder> Uuid<B> {
+    /// Create a new binary decoder for a UUID.
+    #[inline]
+    #[must_use]
+    pub const fn new(byte_order: B) -> Uuid<B> {
+        Uuid { byte_order }
+    }
+
+    /// Returns a decoder yielding [`uuid::Uuid`]s.
+    #[cfg(feature = "uuid")]
+    #[inline]
+    #[must_use]
+    pub fn typed(self) -> TypedUuid<B> {
+        Map::new(self, ::uuid::Uuid::from_bytes)
+    }
+
+    /// Swap between the RFC 4122 and GUID layouts, if needed.
+    #[inline]
+    fn swap(&self, bytes: &mut [u8; 16]) {
+        if self.byte_order.endian() == Endian::Little {
+            bytes[..4].reverse();
+            bytes[4..6].reverse();
+            bytes[6..8].reverse();
+        }
+    }
+}
+
+/// A [`Decoder`] for a [`uuid::Uuid`], see [`Uuid::typed`].
+#[cfg(feature = "uuid")]
+pub type TypedUuid<B = BigEndian> = Map<Uuid<B>, fn([u8; 16]) -> ::uuid::Uuid>;
+
+impl<B: ByteOrder + Default> Default for Uuid<B> {
+    #[inline]
+    fn default() -> Self {
+        Self::new(B::default())
+    }
+}
+
+impl<B: ByteOrder> Decoder for Uuid<B> {
+    type Item<'src> = [u8; 16];
+    type Error = ::core::convert::Infallible;
+
+    #[inline]
+    fn hint(&self) -> Size {
+        Size::new(16)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> SizeHint {
+        SizeHint::exact(self.hint())
+    }
+
+    #[inline]
+    fn decode<'s>(&mut self, src: &mut &'s [u8]) -> DResult<'s, Self> {
+        let Some((bytes, rest)) = src.split_first_chunk::<16>() else {
+            return Err(DError::Incomplete {
+                needed: Size::new(16 - src.len()),
+            });
+        };
+
+        let mut bytes = *bytes;
+
+        self.swap(&mut bytes);
+
+        *src = rest;
+
+        Ok(bytes)
+    }
+}
+
+impl<B: ByteOrder> Encoder<[u8; 16]> for Uuid<B> {
+    type Error = ::core::convert::Infallible;
+
+    #[inline]
+    fn encode_hint(&self, _: &[u8; 16]) -> Size {
+        Size::new(16)
+    }
+
+    #[inline]
+    fn encode(&mut self, item: &[u8; 16], dst: &mut &mut [u8]) -> EResult<Self::Error> {
+        if dst.len() < 16 {
+            return Err(EError::Full {
+                needed: Size::new(16 - dst.len()),
+            });
+        }
+
+        let (bytes, rest) = core::mem::take(dst).split_at_mut(16);
+        let bytes: &mut [u8; 16] = bytes.try_into().unwrap();
+
+        *bytes = *item;
+
+        self.swap(bytes);
+
+        *dst = rest;
+
+        Ok(())
+    }
+}
+
+#[cfg(feature = "uuid")]
+impl<B: ByteOrder> Encoder<::uuid::Uuid> for Uuid<B> {
+    type Error = ::core::convert::Infallible;
+
+    #[inline]
+    fn encode_hint(&self, _: &::uuid::Uuid) -> Size {
+        Size::new(16)
+    }
+
+    #[inline]
+    fn encode(&mut self, item: &::uuid::Uuid, dst: &mut &mut [u8]) -> EResult<Self::Error> {
+        self.encode(item.as_bytes(), dst)
+    }
+}
+
+/// How to handle bits that do not correspond to any defined flag.
+#[cfg(feature = "bitflags")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Unknown {
+    /// Reject them.
+    #[default]
+    Reject,
+    /// Drop them.
+    Truncate,
+}
+
+/// A binary [`Decoder`] and [`Encoder`] for a type generated by the
+/// `bitflags` crate, as its underlying integer in a specified byte order.
+#[cfg(feature = "bitflags")]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Flags<F, B: ByteOrder = NativeEndian> {
+    /// The byte order for this decoder.
+    pub byte_order: B,
+    /// How to handle unknown bits, both when decoding and encoding.
+    pub unknown: Unknown,
+    _marker: core::marker::PhantomData<fn() -> F>,
+}
+
+#[cfg(feature = "bitflags")]
+impl<F, B: ByteOrder> Flags<F, B>
+where
+    F: ::bitflags::Flags,
+    F::Bits: Layout,
+{
+    /// Create a new binary decoder for `F`.
+    #[inline]
+    #[must_use]
+    pub const fn new(byte_order: B) -> Flags<F, B> {
+        Flags {
+            byte_order,
+            unknown: Unknown::Reject,
+            _marker: core::marker::PhantomData,
+        }
+    }
+
+    /// Set how to handle unknown bits.
+    #[inline]
+    #[must_use]
+    pub const fn with_unknown(mut self, unknown: Unknown) -> Flags<F, B> {
+        self.unknown = unknown;
+        self
+    }
+
+    #[inline]
+    fn flags(&self, bits: F::Bits) -> Result<F, FlagsError> {
+        match self.unknown {
+            Unknown::Reject => F::from_bits(bits).ok_or(FlagsError(())),
+            Unknown::Truncate => Ok(F::from_bits_truncate(bits)),
+        }
+    }
+}
+
+#[cfg(feature = "bitflags")]
+impl<F, B: ByteOrder + Default> Default for Flags<F, B>
+where
+    F: ::bitflags::Flags,
+    F::Bits: Layout,
+{
+    #[inline]
+    fn default() -> Self {
+        Flags::new(B::default())
+    }
+}
+
+#[cfg(feature = "bitflags")]
+impl<F, B: ByteOrder> Decoder for Flags<F, B>
+where
+    F: ::bitflags::Flags,
+    F::Bits: Layout,
+{
+    type Item<'src> = F;
+    type Error = FlagsError;
+
+    #[inline]
+    fn hint(&self) -> Size {
+        Size::new(F::Bits::SIZE)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> SizeHint {
+        SizeHint::exact(self.hint())
+    }
+
+    #[inline]
+    fn decode<'s>(&mut self, src: &mut &'s [u8]) -> DResult<'s, Self> {
+        let Some((bytes, rest)) = src.split_at_checked(F::Bits::SIZE) else {
+            return Err(DError::Incomplete {
+                needed: Size::new(F::Bits::SIZE - src.len()),
+            });
+        };
+
+        let bits = F::Bits::read(bytes, self.byte_order.endian()).map_err(|_| DError::Fatal {
+            error: FlagsError(()),
+        })?;
+
+        let flags = self.flags(bits).map_err(|error| DError::Fatal { error })?;
+
+        *src = rest;
+
+        Ok(flags)
+    }
+}
+
+#[cfg(feature = "bitflags")]
+impl<F, B: ByteOrder> Encoder<F> for Flags<F, B>
+where
+    F: ::bitflags::Flags,
+    F::Bits: Layout,
+{
+    type Error = FlagsError;
+
+    #[inline]
+    fn encode_hint(&self, _: &F) -> Size {
+        Size::new(F::Bits::SIZE)
+    }
+
+    #[inline]
+    fn encode(&mut self, item: &F, dst: &mut &mut [u8]) -> EResult<Self::Error> {
+        let flags = self
+            .flags(item.bits())
+            .map_err(|error| EError::Fatal { error })?;
+
+        if dst.len() < F::Bits::SIZE {
+            return Err(EError::Full {
+                needed: Size::new(F::Bits::SIZE - dst.len()),
+            });
+        }
+
+        let (bytes, rest) = core::mem::take(dst).split_at_mut(F::Bits::SIZE);
+
+        flags.bits().write(bytes, self.byte_order.endian());
+
+        *dst = rest;
+
+        Ok(())
+    }
+}
+
+#[cfg(feature = "bitflags")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct FlagsError(());
+
+#[cfg(feature = "bitflags")]
+impl fmt::Display for FlagsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("unknown bits in flags")
+    }
+}
+
+#[cfg(feature = "bitflags")]
+impl core::error::Error for FlagsError {}
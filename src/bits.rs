@@ -0,0 +1,573 @@
+use core::convert::Infallible;
+
+use crate::decoder::{Decoder, Error as DError, Incomplete, Result as DResult};
+use crate::Size;
+
+/// The order in which bits are read from each byte.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum BitOrder {
+    /// From the most significant bit, with fields spanning bytes starting
+    /// with their most significant bits, as in MPEG-TS.
+    #[default]
+    MsbFirst,
+    /// From the least significant bit, with fields spanning bytes starting
+    /// with their least significant bits, as in DEFLATE.
+    LsbFirst,
+}
+
+/// A cursor over the bits of a byte slice, for writing [`BitDecoder`] and
+/// [`Decoder`] bodies.
+///
+/// Like [`Reader`](crate::reader::Reader), reading never touches the
+/// source; call [`BitReader::commit`] once the whole frame has been read.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct BitReader<'s> {
+    start: &'s [u8],
+    rest: &'s [u8],
+    /// The amount of bits read from the first byte of `rest`.
+    bit: u32,
+    order: BitOrder,
+}
+
+impl<'s> BitReader<'s> {
+    /// Create a new [`BitReader`] positioned at the start of `src`, reading
+    /// bits in `order`.
+    #[inline]
+    #[must_use]
+    pub const fn new(src: &'s [u8], order: BitOrder) -> BitReader<'s> {
+        BitReader {
+            start: src,
+            rest: src,
+            bit: 0,
+            order,
+        }
+    }
+
+    /// Advance `src` past every byte read from so far, including the
+    /// rest of a partially read byte.
+    #[inline]
+    pub fn commit(mut self, src: &mut &'s [u8]) {
+        self.align();
+
+        *src = self.rest;
+    }
+
+    /// Returns the order in which bits are read.
+    #[inline]
+    #[must_use]
+    pub const fn order(&self) -> BitOrder {
+        self.order
+    }
+
+    /// Returns the amount of bits read so far.
+    #[inline]
+    #[must_use]
+    pub const fn position(&self) -> usize {
+        (self.start.len() - self.rest.len()) * 8 + self.bit as usize
+    }
+
+    /// Returns the amount of bits left to read.
+    #[inline]
+    #[must_use]
+    pub const fn remaining(&self) -> usize {
+        self.rest.len() * 8 - self.bit as usize
+    }
+
+    /// Returns whether the reader is at a byte boundary.
+    #[inline]
+    #[must_use]
+    pub const fn is_aligned(&self) -> bool {
+        self.bit == 0
+    }
+
+    /// Skip the rest of a partially read byte, if any.
+    #[inline]
+    pub fn align(&mut self) {
+        if self.bit > 0 {
+            self.rest = &self.rest[1..];
+            self.bit = 0;
+        }
+    }
+
+    #[inline]
+    fn incomplete(&self, bits: usize) -> Incomplete {
+        Incomplete::new(Size::new((bits - self.remaining()).div_ceil(8)))
+    }
+
+    /// Read the next `n` bits as an unsigned integer.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is more than 64.
+    pub fn bits(&mut self, n: u32) -> Result<u64, Incomplete> {
+        assert!(n <= 64, "cannot read more than 64 bits at once");
+
+        if n as usize > self.remaining() {
+            return Err(self.incomplete(n as usize));
+        }
+
+        let mut value = 0u64;
+        let mut filled = 0;
+
+        while filled < n {
+            let byte = u64::from(self.rest[0]);
+            let take = (8 - self.bit).min(n - filled);
+            let mask = (1 << take) - 1;
+
+            match self.order {
+                BitOrder::MsbFirst => {
+                    let chunk = (byte >> (8 - self.bit - take)) & mask;
+
+                    value = value << take | chunk;
+                }
+                BitOrder::LsbFirst => {
+                    let chunk = (byte >> self.bit) & mask;
+
+                    value |= chunk << filled;
+                }
+            }
+
+            filled += take;
+            self.bit += take;
+
+            if self.bit == 8 {
+                self.rest = &self.rest[1..];
+                self.bit = 0;
+            }
+        }
+
+        Ok(value)
+    }
+
+    /// Read the next `n` bits as a signed two's complement integer.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is more than 64.
+    #[inline]
+    pub fn signed(&mut self, n: u32) -> Result<i64, Incomplete> {
+        let value = self.bits(n)?;
+        let shift = 64 - n;
+
+        Ok(match n {
+            0 => 0,
+            _ => ((value << shift) as i64) >> shift,
+        })
+    }
+
+    /// Read the next bit.
+    #[inline]
+    pub fn bit(&mut self) -> Result<bool, Incomplete> {
+        self.bits(1).map(|bit| bit == 1)
+    }
+
+    /// Skip the next `n` bits.
+    #[inline]
+    pub fn skip(&mut self, n: usize) -> Result<(), Incomplete> {
+        if n > self.remaining() {
+            return Err(self.incomplete(n));
+        }
+
+        let bits = self.bit as usize + n;
+
+        self.rest = &self.rest[bits / 8..];
+        self.bit = (bits % 8) as u32;
+
+        Ok(())
+    }
+
+    /// Read a frame using `decoder`.
+    #[inline]
+    pub fn decode<B: BitDecoder>(&mut self, decoder: &mut B) -> DResult<'s, Bitwise<B>> {
+        decoder.decode(self)
+    }
+
+    /// Read a frame using the byte-level `decoder`, after skipping the rest
+    /// of a partially read byte.
+    #[inline]
+    pub fn read<D: Decoder>(&mut self, decoder: &mut D) -> DResult<'s, D> {
+        self.align();
+
+        decoder.decode(&mut self.rest)
+    }
+}
+
+/// Trait for decoders of fields that are not whole bytes, reading from a
+/// [`BitReader`].
+///
+/// These can be used as a byte-level [`Decoder`] with [`Bitwise`].
+pub trait BitDecoder {
+    type Item<'src>;
+    type Error: core::fmt::Display + core::fmt::Debug;
+
+    /// Try to decode a single frame from the bits of `reader`.
+    fn decode<'s>(
+        &mut self,
+        reader: &mut BitReader<'s>,
+    ) -> Result<Self::Item<'s>, DError<Self::Error>>;
+}
+
+impl<B: BitDecoder + ?Sized> BitDecoder for &mut B {
+    type Item<'src> = B::Item<'src>;
+    type Error = B::Error;
+
+    #[inline]
+    fn decode<'s>(
+        &mut self,
+        reader: &mut BitReader<'s>,
+    ) -> Result<Self::Item<'s>, DError<Self::Error>> {
+        (**self).decode(reader)
+    }
+}
+
+/// A [`BitDecoder`] for unsigned integers of [`width`](Unsigned::width)
+/// bits, at most 64.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Unsigned {
+    /// The amount of bits in the integer.
+    pub width: u32,
+}
+
+impl Unsigned {
+    /// Create a new decoder for unsigned integers of `width` bits.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `width` is more than 64.
+    #[inline]
+    #[must_use]
+    pub const fn new(width: u32) -> Unsigned {
+        assert!(width <= 64, "width must be at most 64 bits");
+
+        Unsigned { width }
+    }
+}
+
+impl BitDecoder for Unsigned {
+    type Item<'src> = u64;
+    type Error = Infallible;
+
+    #[inline]
+    fn decode<'s>(&mut self, reader: &mut BitReader<'s>) -> Result<u64, DError<Infallible>> {
+        reader.bits(self.width).map_err(Into::into)
+    }
+}
+
+/// A [`BitDecoder`] for signed two's complement integers of
+/// [`width`](Signed::width) bits, at most 64.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Signed {
+    /// The amount of bits in the integer.
+    pub width: u32,
+}
+
+impl Signed {
+    /// Create a new decoder for signed integers of `width` bits.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `width` is more than 64.
+    #[inline]
+    #[must_use]
+    pub const fn new(width: u32) -> Signed {
+        assert!(width <= 64, "width must be at most 64 bits");
+
+        Signed { width }
+    }
+}
+
+impl BitDecoder for Signed {
+    type Item<'src> = i64;
+    type Error = Infallible;
+
+    #[inline]
+    fn decode<'s>(&mut self, reader: &mut BitReader<'s>) -> Result<i64, DError<Infallible>> {
+        reader.signed(self.width).map_err(Into::into)
+    }
+}
+
+/// A [`BitDecoder`] for a single bit, as a [`prim@bool`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Flag;
+
+impl BitDecoder for Flag {
+    type Item<'src> = bool;
+    type Error = Infallible;
+
+    #[inline]
+    fn decode<'s>(&mut self, reader: &mut BitReader<'s>) -> Result<bool, DError<Infallible>> {
+        reader.bit().map_err(Into::into)
+    }
+}
+
+/// A [`BitDecoder`] for frames of a byte-level [`Decoder`], starting at the
+/// next byte boundary.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Aligned<D> {
+    /// The inner decoder.
+    pub decoder: D,
+}
+
+impl<D> Aligned<D> {
+    /// Create a new decoder for frames of `decoder` at byte boundaries.
+    #[inline]
+    #[must_use]
+    pub const fn new(decoder: D) -> Aligned<D> {
+        Aligned { decoder }
+    }
+}
+
+impl<D: Decoder> BitDecoder for Aligned<D> {
+    type Item<'src> = D::Item<'src>;
+    type Error = D::Error;
+
+    #[inline]
+    fn decode<'s>(&mut self, reader: &mut BitReader<'s>) -> DResult<'s, D> {
+        reader.read(&mut self.decoder)
+    }
+}
+
+/// A [`Decoder`] for frames of a [`BitDecoder`], reading bits in
+/// [`order`](Bitwise::order).
+///
+/// Frames always end at a byte boundary, skipping the rest of a partially
+/// read byte.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Bitwise<B> {
+    /// The inner decoder.
+    pub decoder: B,
+    /// The order in which bits are read.
+    pub order: BitOrder,
+}
+
+impl<B> Bitwise<B> {
+    /// Create a new decoder for frames of `decoder`, reading bits in
+    /// `order`.
+    #[inline]
+    #[must_use]
+    pub const fn new(decoder: B, order: BitOrder) -> Bitwise<B> {
+        Bitwise { decoder, order }
+    }
+}
+
+impl<B: BitDecoder> Decoder for Bitwise<B> {
+    type Item<'src> = B::Item<'src>;
+    type Error = B::Error;
+
+    #[inline]
+    fn decode<'s>(&mut self, src: &mut &'s [u8]) -> DResult<'s, Self> {
+        let mut reader = BitReader::new(src, self.order);
+        let item = self.decoder.decode(&mut reader)?;
+
+        reader.commit(src);
+
+        Ok(item)
+    }
+}
+
+/// Trait for the types of fields in a [`bitfield!`](crate::bitfield!)
+/// struct, and of the words containing them.
+///
+/// This is implemented for [`prim@bool`] and the integers up to 64 bits.
+/// Signed integers are sign extended from the width of their field.
+pub trait Field: Sized {
+    /// The amount of bits in the type.
+    const BITS: u32;
+
+    /// Convert the low `width` bits of `bits` into a value.
+    fn from_bits(bits: u64, width: u32) -> Self;
+
+    /// Convert a value into bits, of which the field keeps as many as fit.
+    fn to_bits(&self) -> u64;
+}
+
+impl Field for bool {
+    const BITS: u32 = 1;
+
+    #[inline]
+    fn from_bits(bits: u64, _: u32) -> Self {
+        bits & 1 == 1
+    }
+
+    #[inline]
+    fn to_bits(&self) -> u64 {
+        u64::from(*self)
+    }
+}
+
+macro_rules! define {
+    ($($unsigned:ident $signed:ident)*) => {
+        $(
+            impl Field for ::core::primitive::$unsigned {
+                const BITS: u32 = ::core::primitive::$unsigned::BITS;
+
+                #[inline]
+                fn from_bits(bits: u64, _: u32) -> Self {
+                    bits as ::core::primitive::$unsigned
+                }
+
+                #[inline]
+                fn to_bits(&self) -> u64 {
+                    u64::from(*self)
+                }
+            }
+
+            impl Field for ::core::primitive::$signed {
+                const BITS: u32 = ::core::primitive::$signed::BITS;
+
+                #[inline]
+                fn from_bits(bits: u64, width: u32) -> Self {
+                    let shift = 64 - width.clamp(1, 64);
+
+                    (((bits << shift) as i64) >> shift) as ::core::primitive::$signed
+                }
+
+                #[inline]
+                fn to_bits(&self) -> u64 {
+                    i64::from(*self) as u64
+                }
+            }
+        )*
+    };
+}
+
+define! {
+    u8 i8
+    u16 i16
+    u32 i32
+    u64 i64
+}
+
+/// Define structs packed into the bits of a single word, such as a
+/// hardware register.
+///
+/// The word is an unsigned integer, read in the byte order of the
+/// [`Frame`](crate::frame::Frame) decoder. Each field is followed by its
+/// width in bits, and fields are packed from the most significant bit, so
+/// the widths must add up to the size of the word. Field types must
+/// implement [`Field`], which includes [`prim@bool`] and the integers up
+/// to 64 bits. Bits of a value that do not fit its field are dropped when
+/// writing.
+///
+/// This implements [`Layout`](crate::frame::Layout), so bitfield structs
+/// can be decoded and encoded with [`Frame`](crate::frame::Frame), or
+/// nested in [`frame!`](crate::frame!) structs.
+///
+/// ```
+/// kodek::bitfield! {
+///     #[derive(Debug, Clone, Copy, PartialEq)]
+///     pub struct Instruction: u16 {
+///         pub wide: bool: 1,
+///         pub opcode: u8: 5,
+///         pub offset: i16: 10,
+///     }
+/// }
+///
+/// use kodek::{frame::Frame, Decoder, Encoder, endian::BE};
+///
+/// let mut src = &[0b1_00101_11, 0b1111_1110][..];
+/// let instruction = Frame::<Instruction, _>::new(BE).decode(&mut src).unwrap();
+///
+/// assert_eq!(instruction, Instruction { wide: true, opcode: 5, offset: -2 });
+///
+/// let mut buf = [0; 2];
+/// Frame::new(BE).encode(&instruction, &mut &mut buf[..]).unwrap();
+///
+/// assert_eq!(buf, [0b1_00101_11, 0b1111_1110]);
+/// ```
+#[macro_export]
+macro_rules! bitfield {
+    ($(
+        $(#[$attr:meta])*
+        $vis:vis struct $name:ident : $word:ty {
+            $(
+                $(#[$field_attr:meta])*
+                $field_vis:vis $field:ident : $ty:ty : $width:expr
+            ),* $(,)?
+        }
+    )*) => {
+        $(
+            $(#[$attr])*
+            $vis struct $name {
+                $(
+                    $(#[$field_attr])*
+                    $field_vis $field: $ty,
+                )*
+            }
+
+            const _: () = {
+                $(
+                    ::core::assert!(
+                        $width > 0 && $width <= <$ty as $crate::bits::Field>::BITS,
+                        ::core::concat!(
+                            "invalid width for field `",
+                            ::core::stringify!($field),
+                            "`",
+                        ),
+                    );
+                )*
+
+                ::core::assert!(
+                    0 $(+ $width)* == <$word as $crate::bits::Field>::BITS,
+                    ::core::concat!(
+                        "the widths of `",
+                        ::core::stringify!($name),
+                        "` do not add up to its word",
+                    ),
+                );
+            };
+
+            impl $crate::frame::Layout for $name {
+                const SIZE: usize = <$word as $crate::frame::Layout>::SIZE;
+
+                #[inline]
+                fn read(
+                    bytes: &[u8],
+                    endian: $crate::endian::Endian,
+                ) -> ::core::result::Result<Self, $crate::frame::LayoutError> {
+                    let word = <$word as $crate::frame::Layout>::read(bytes, endian)?;
+                    let word = <$word as $crate::bits::Field>::to_bits(&word);
+                    let mut shift = <$word as $crate::bits::Field>::BITS;
+
+                    $(
+                        shift -= $width;
+
+                        let $field = <$ty as $crate::bits::Field>::from_bits(
+                            (word >> shift) & (u64::MAX >> (64 - $width)),
+                            $width,
+                        );
+                    )*
+
+                    ::core::result::Result::Ok($name { $($field),* })
+                }
+
+                #[inline]
+                fn write(&self, bytes: &mut [u8], endian: $crate::endian::Endian) {
+                    let mut word = 0u64;
+                    let mut shift = <$word as $crate::bits::Field>::BITS;
+
+                    $(
+                        shift -= $width;
+                        word |= (<$ty as $crate::bits::Field>::to_bits(&self.$field)
+                            & (u64::MAX >> (64 - $width)))
+                            << shift;
+                    )*
+
+                    let word = <$word as $crate::bits::Field>::from_bits(
+                        word,
+                        <$word as $crate::bits::Field>::BITS,
+                    );
+
+                    <$word as $crate::frame::Layout>::write(&word, bytes, endian);
+                }
+            }
+        )*
+    };
+}
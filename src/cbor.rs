@@ -0,0 +1,194 @@
+use core::fmt;
+
+use crate::decoder::{Decoder, Error as DError, Result as DResult};
+use crate::{Size, SizeHint};
+
+/// The header of a CBOR data item, which is its initial byte and the
+/// argument following it.
+///
+/// Strings are followed by their payload, arrays and maps by their
+/// elements, and tags by the tagged item. Indefinite lengths are `None`,
+/// and are followed by their chunks or elements up to a
+/// [`Break`](Header::Break).
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Header {
+    /// An unsigned integer, of major type 0.
+    Unsigned(u64),
+    /// A negative integer of `-1 - n`, of major type 1.
+    Negative(u64),
+    /// A byte string of major type 2, followed by this many bytes.
+    Bytes(Option<u64>),
+    /// A text string of major type 3, followed by this many bytes of
+    /// UTF-8.
+    Text(Option<u64>),
+    /// An array of major type 4, followed by this many items.
+    Array(Option<u64>),
+    /// A map of major type 5, followed by this many pairs of items.
+    Map(Option<u64>),
+    /// A tag of major type 6, followed by the tagged item.
+    Tag(u64),
+    /// A simple value of major type 7, such as `false` (20), `true` (21),
+    /// `null` (22) and `undefined` (23).
+    Simple(u8),
+    /// The bits of a half precision float.
+    F16(u16),
+    /// A single precision float.
+    F32(f32),
+    /// A double precision float.
+    F64(f64),
+    /// The end of an item of indefinite length.
+    Break,
+}
+
+impl Header {
+    /// Returns the major type of the item, from 0 to 7.
+    #[inline]
+    #[must_use]
+    pub const fn major(&self) -> u8 {
+        match self {
+            Self::Unsigned(_) => 0,
+            Self::Negative(_) => 1,
+            Self::Bytes(_) => 2,
+            Self::Text(_) => 3,
+            Self::Array(_) => 4,
+            Self::Map(_) => 5,
+            Self::Tag(_) => 6,
+            Self::Simple(_) | Self::F16(_) | Self::F32(_) | Self::F64(_) | Self::Break => 7,
+        }
+    }
+
+    /// Returns the value of an integer, if this is one.
+    #[inline]
+    #[must_use]
+    pub const fn as_i128(&self) -> Option<i128> {
+        match *self {
+            Self::Unsigned(n) => Some(n as i128),
+            Self::Negative(n) => Some(-1 - n as i128),
+            _ => None,
+        }
+    }
+}
+
+/// A [`Decoder`] for the headers of CBOR data items.
+///
+/// Headers are at most 9 bytes, and are only yielded once complete. The
+/// payloads of strings are left in the source, so they can be read
+/// incrementally or skipped.
+///
+/// Headers that are not well-formed are rejected, which are the reserved
+/// argument sizes, indefinite lengths for major types without a length,
+/// and simple values below 32 in the two byte form.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Headers;
+
+impl Headers {
+    /// Create a new decoder for headers.
+    #[inline]
+    #[must_use]
+    pub const fn new() -> Headers {
+        Headers
+    }
+}
+
+impl Decoder for Headers {
+    type Item<'src> = Header;
+    type Error = HeaderError;
+
+    #[inline]
+    fn hint(&self) -> Size {
+        Size::new(1)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> SizeHint {
+        SizeHint::new(self.hint(), Size::new(9))
+    }
+
+    fn decode<'s>(&mut self, src: &mut &'s [u8]) -> DResult<'s, Self> {
+        let Some(&initial) = src.first() else {
+            return Err(DError::Incomplete {
+                needed: Size::new(1),
+            });
+        };
+
+        let major = initial >> 5;
+        let info = initial & 0x1f;
+
+        let size = match info {
+            0..=23 | 31 => 0,
+            24 => 1,
+            25 => 2,
+            26 => 4,
+            27 => 8,
+            _ => {
+                return Err(DError::Fatal {
+                    error: HeaderError::Reserved(initial),
+                })
+            }
+        };
+
+        let Some((bytes, rest)) = src.split_at_checked(1 + size) else {
+            return Err(DError::Incomplete {
+                needed: Size::new(1 + size - src.len()),
+            });
+        };
+
+        let argument = match info {
+            0..=23 => Some(u64::from(info)),
+            31 => None,
+            _ => Some(bytes[1..].iter().fold(0u64, |n, &b| n << 8 | u64::from(b))),
+        };
+
+        let invalid = |error| Err(DError::Fatal { error });
+
+        let header = match (major, argument) {
+            (0, Some(n)) => Header::Unsigned(n),
+            (1, Some(n)) => Header::Negative(n),
+            (2, len) => Header::Bytes(len),
+            (3, len) => Header::Text(len),
+            (4, len) => Header::Array(len),
+            (5, len) => Header::Map(len),
+            (6, Some(n)) => Header::Tag(n),
+            (7, None) => Header::Break,
+            (7, Some(n)) => match info {
+                24 if n < 32 => return invalid(HeaderError::InvalidSimple(n as u8)),
+                25 => Header::F16(n as u16),
+                26 => Header::F32(f32::from_bits(n as u32)),
+                27 => Header::F64(f64::from_bits(n)),
+                _ => Header::Simple(n as u8),
+            },
+            _ => return invalid(HeaderError::Indefinite(initial)),
+        };
+
+        *src = rest;
+
+        Ok(header)
+    }
+}
+
+/// Type for errors that may occur while decoding CBOR headers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum HeaderError {
+    /// The initial byte has a reserved argument size.
+    Reserved(u8),
+    /// The initial byte has an indefinite length for a major type
+    /// without a length.
+    Indefinite(u8),
+    /// A simple value below 32 is in the two byte form.
+    InvalidSimple(u8),
+}
+
+impl fmt::Display for HeaderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Reserved(byte) => write!(f, "reserved argument size: {byte:#04x}"),
+            Self::Indefinite(byte) => write!(f, "invalid indefinite length: {byte:#04x}"),
+            Self::InvalidSimple(value) => write!(f, "invalid simple value: {value}"),
+        }
+    }
+}
+
+impl core::error::Error for HeaderError {}
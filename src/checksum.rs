@@ -0,0 +1,306 @@
+/// Cyclic redundancy checks.
+pub mod crc;
+
+/// Trait for checksums that are computed incrementally over bytes.
+///
+/// A new checksum is created with [`Default::default`] for each frame.
+pub trait Checksum: Default {
+    /// The amount of bytes in the checksum, at most 8.
+    const LEN: usize;
+
+    /// Add `bytes` to the checksum.
+    fn update(&mut self, bytes: &[u8]);
+
+    /// Returns the checksum of the bytes added so far.
+    fn finish(&self) -> u64;
+
+    /// Compute the checksum of `bytes`.
+    #[inline]
+    #[must_use]
+    fn checksum(bytes: &[u8]) -> u64 {
+        let mut checksum = Self::default();
+
+        checksum.update(bytes);
+        checksum.finish()
+    }
+}
+
+/// A [`Checksum`] for Adler-32, the checksum of zlib.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Adler32 {
+    a: u32,
+    b: u32,
+}
+
+impl Adler32 {
+    /// The modulus of the sums.
+    const MOD: u32 = 65521;
+
+    /// The most bytes that can be summed before the sums may overflow.
+    const CHUNK: usize = 5552;
+
+    /// Create a new [`Adler32`].
+    #[inline]
+    #[must_use]
+    pub const fn new() -> Adler32 {
+        Adler32 { a: 1, b: 0 }
+    }
+}
+
+impl Default for Adler32 {
+    #[inline]
+    fn default() -> Self {
+        Adler32::new()
+    }
+}
+
+impl Checksum for Adler32 {
+    const LEN: usize = 4;
+
+    #[inline]
+    fn update(&mut self, bytes: &[u8]) {
+        for chunk in bytes.chunks(Self::CHUNK) {
+            for &byte in chunk {
+                self.a += u32::from(byte);
+                self.b += self.a;
+            }
+
+            self.a %= Self::MOD;
+            self.b %= Self::MOD;
+        }
+    }
+
+    #[inline]
+    fn finish(&self) -> u64 {
+        u64::from(self.b << 16 | self.a)
+    }
+}
+
+/// A [`Checksum`] for Fletcher-16, over bytes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Fletcher16 {
+    a: u16,
+    b: u16,
+}
+
+impl Fletcher16 {
+    /// Create a new [`Fletcher16`].
+    #[inline]
+    #[must_use]
+    pub const fn new() -> Fletcher16 {
+        Fletcher16 { a: 0, b: 0 }
+    }
+}
+
+impl Checksum for Fletcher16 {
+    const LEN: usize = 2;
+
+    #[inline]
+    fn update(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.a = (self.a + u16::from(byte)) % 255;
+            self.b = (self.b + self.a) % 255;
+        }
+    }
+
+    #[inline]
+    fn finish(&self) -> u64 {
+        u64::from(self.b << 8 | self.a)
+    }
+}
+
+/// A [`Checksum`] for Fletcher-32, over little endian 16-bit words.
+///
+/// An odd amount of bytes is padded with a zero byte.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Fletcher32 {
+    a: u32,
+    b: u32,
+    pending: Option<u8>,
+}
+
+impl Fletcher32 {
+    /// Create a new [`Fletcher32`].
+    #[inline]
+    #[must_use]
+    pub const fn new() -> Fletcher32 {
+        Fletcher32 {
+            a: 0,
+            b: 0,
+            pending: None,
+        }
+    }
+
+    fn word(&mut self, word: u16) {
+        self.a = (self.a + u32::from(word)) % 65535;
+        self.b = (self.b + self.a) % 65535;
+    }
+}
+
+impl Checksum for Fletcher32 {
+    const LEN: usize = 4;
+
+    #[inline]
+    fn update(&mut self, mut bytes: &[u8]) {
+        if let Some(low) = self.pending.take() {
+            let Some((&high, rest)) = bytes.split_first() else {
+                self.pending = Some(low);
+                return;
+            };
+
+            self.word(u16::from_le_bytes([low, high]));
+            bytes = rest;
+        }
+
+        let (words, rest) = bytes.as_chunks::<2>();
+
+        for &word in words {
+            self.word(u16::from_le_bytes(word));
+        }
+
+        self.pending = rest.first().copied();
+    }
+
+    #[inline]
+    fn finish(&self) -> u64 {
+        let mut checksum = *self;
+
+        if let Some(low) = checksum.pending.take() {
+            checksum.word(u16::from(low));
+        }
+
+        u64::from(checksum.b << 16 | checksum.a)
+    }
+}
+
+/// Returns the SHA-256 of `bytes`, as in FIPS 180-4.
+///
+/// This is not a [`Checksum`], as the hash is longer than 8 bytes.
+///
+/// ```
+/// use kodek::checksum::sha256;
+///
+/// # fn hex(hash: [u8; 32]) -> String {
+/// #     hash.iter().map(|byte| format!("{byte:02x}")).collect()
+/// # }
+/// assert_eq!(
+///     hex(sha256(b"")),
+///     "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855",
+/// );
+/// assert_eq!(
+///     hex(sha256(b"abc")),
+///     "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad",
+/// );
+/// assert_eq!(
+///     hex(sha256(b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq")),
+///     "248d6a61d20638b8e5c026930c3e6039a33ce45964ff2167f6ecedd419db06c1",
+/// );
+/// assert_eq!(
+///     hex(sha256(b"abcdefghbcdefghicdefghijdefghijkefghijklfghijklmghijklmnhijklmnoijklmnopjklmnopqklmnopqrlmnopqrsmnopqrstnopqrstu")),
+///     "cf5b16a778af8380036ce59e7b0492370b249b11e8f07a51afac45037afee9d1",
+/// );
+/// assert_eq!(
+///     hex(sha256(&vec![b'a'; 1_000_000])),
+///     "cdc76e5c9914fb9281a1c7e284d73e67f1809a48a497200e046d39ccc7112cd0",
+/// );
+/// ```
+#[must_use]
+pub fn sha256(bytes: &[u8]) -> [u8; 32] {
+    const K: [u32; 64] = [
+        0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4,
+        0xab1c5ed5, 0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe,
+        0x9bdc06a7, 0xc19bf174, 0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f,
+        0x4a7484aa, 0x5cb0a9dc, 0x76f988da, 0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7,
+        0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967, 0x27b70a85, 0x2e1b2138, 0x4d2c6dfc,
+        0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85, 0xa2bfe8a1, 0xa81a664b,
+        0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070, 0x19a4c116,
+        0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
+        0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7,
+        0xc67178f2,
+    ];
+
+    let mut state: [u32; 8] = [
+        0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab,
+        0x5be0cd19,
+    ];
+
+    let mut compress = |block: &[u8; 64]| {
+        let mut w = [0u32; 64];
+
+        for (word, bytes) in w.iter_mut().zip(block.chunks_exact(4)) {
+            *word = u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+        }
+
+        for i in 16..64 {
+            let s0 = w[i - 15].rotate_right(7) ^ w[i - 15].rotate_right(18) ^ (w[i - 15] >> 3);
+            let s1 = w[i - 2].rotate_right(17) ^ w[i - 2].rotate_right(19) ^ (w[i - 2] >> 10);
+
+            w[i] = w[i - 16]
+                .wrapping_add(s0)
+                .wrapping_add(w[i - 7])
+                .wrapping_add(s1);
+        }
+
+        let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = state;
+
+        for (&k, &w) in K.iter().zip(&w) {
+            let s1 = e.rotate_right(6) ^ e.rotate_right(11) ^ e.rotate_right(25);
+            let ch = (e & f) ^ (!e & g);
+            let t1 = h
+                .wrapping_add(s1)
+                .wrapping_add(ch)
+                .wrapping_add(k)
+                .wrapping_add(w);
+            let s0 = a.rotate_right(2) ^ a.rotate_right(13) ^ a.rotate_right(22);
+            let maj = (a & b) ^ (a & c) ^ (b & c);
+            let t2 = s0.wrapping_add(maj);
+
+            h = g;
+            g = f;
+            f = e;
+            e = d.wrapping_add(t1);
+            d = c;
+            c = b;
+            b = a;
+            a = t1.wrapping_add(t2);
+        }
+
+        for (word, value) in state.iter_mut().zip([a, b, c, d, e, f, g, h]) {
+            *word = word.wrapping_add(value);
+        }
+    };
+
+    let mut blocks = bytes.chunks_exact(64);
+
+    for block in &mut blocks {
+        compress(block.try_into().expect("blocks are 64 bytes"));
+    }
+
+    let rest = blocks.remainder();
+    let mut last = [0u8; 128];
+
+    last[..rest.len()].copy_from_slice(rest);
+    last[rest.len()] = 0x80;
+
+    let last = if rest.len() < 56 {
+        &mut last[..64]
+    } else {
+        &mut last[..]
+    };
+
+    let len = last.len();
+
+    last[len - 8..].copy_from_slice(&((bytes.len() as u64) * 8).to_be_bytes());
+
+    for block in last.chunks_exact(64) {
+        compress(block.try_into().expect("blocks are 64 bytes"));
+    }
+
+    let mut hash = [0; 32];
+
+    for (bytes, word) in hash.chunks_exact_mut(4).zip(state) {
+        bytes.copy_from_slice(&word.to_be_bytes());
+    }
+
+    hash
+}
@@ -0,0 +1,182 @@
+use super::Checksum;
+
+/// CRC-8/MAXIM, the CRC of the 1-Wire bus.
+pub type Crc8Maxim = Crc<8, 0x31, 0x00, true, true, 0x00>;
+
+/// CRC-12/UMTS, which reflects the result but not the input.
+pub type Crc12Umts = Crc<12, 0x80f, 0x000, false, true, 0x000>;
+
+/// CRC-16/CCITT, also known as CRC-16/KERMIT.
+pub type Crc16Ccitt = Crc<16, 0x1021, 0x0000, true, true, 0x0000>;
+
+/// CRC-16/IBM-3740, also known as CRC-16/CCITT-FALSE.
+pub type Crc16Ibm3740 = Crc<16, 0x1021, 0xffff, false, false, 0x0000>;
+
+/// CRC-32/ISO-HDLC, the CRC-32 of zlib, PNG and Ethernet.
+pub type Crc32IsoHdlc = Crc<32, 0x04c1_1db7, 0xffff_ffff, true, true, 0xffff_ffff>;
+
+/// CRC-64/XZ, the CRC-64 of xz.
+pub type Crc64Xz = Crc<64, 0x42f0_e1eb_a9ea_3693, { u64::MAX }, true, true, { u64::MAX }>;
+
+/// A [`Checksum`] for a cyclic redundancy check of `WIDTH` bits, between
+/// 8 and 64.
+///
+/// The parameters follow the catalogue of parametrised CRC algorithms:
+///
+/// - `POLY` is the polynomial, without its highest bit.
+/// - `INIT` is the initial value of the register.
+/// - `REFIN` is whether input bytes are reflected.
+/// - `REFOUT` is whether the result is reflected.
+/// - `XOR_OUT` is xored into the result.
+///
+/// The lookup table is generated at compile time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Crc<
+    const WIDTH: u32,
+    const POLY: u64,
+    const INIT: u64,
+    const REFIN: bool,
+    const REFOUT: bool,
+    const XOR_OUT: u64,
+> {
+    register: u64,
+}
+
+impl<
+        const WIDTH: u32,
+        const POLY: u64,
+        const INIT: u64,
+        const REFIN: bool,
+        const REFOUT: bool,
+        const XOR_OUT: u64,
+    > Crc<WIDTH, POLY, INIT, REFIN, REFOUT, XOR_OUT>
+{
+    /// The bits of the register that are in use.
+    const MASK: u64 = {
+        assert!(
+            WIDTH >= 8 && WIDTH <= 64,
+            "CRC width must be between 8 and 64"
+        );
+
+        u64::MAX >> (64 - WIDTH)
+    };
+
+    /// The lookup table for each value of a byte.
+    const TABLE: [u64; 256] = {
+        let mut table = [0; 256];
+        let mut i = 0;
+
+        while i < 256 {
+            let mut register = if REFIN {
+                i as u64
+            } else {
+                (i as u64) << (WIDTH - 8)
+            };
+            let mut bit = 0;
+
+            while bit < 8 {
+                register = if REFIN {
+                    if register & 1 == 1 {
+                        (register >> 1) ^ reflect(POLY, WIDTH)
+                    } else {
+                        register >> 1
+                    }
+                } else if register >> (WIDTH - 1) & 1 == 1 {
+                    (register << 1) ^ POLY
+                } else {
+                    register << 1
+                };
+                bit += 1;
+            }
+
+            table[i] = register & Self::MASK;
+            i += 1;
+        }
+
+        table
+    };
+
+    /// Create a new [`Crc`] with the initial value.
+    #[inline]
+    #[must_use]
+    pub const fn new() -> Self {
+        Crc {
+            register: if REFIN {
+                reflect(INIT, WIDTH)
+            } else {
+                INIT & Self::MASK
+            },
+        }
+    }
+
+    /// Add `bytes` to the CRC.
+    #[inline]
+    pub fn update(&mut self, bytes: &[u8]) {
+        let table = &Self::TABLE;
+
+        for &byte in bytes {
+            self.register = if REFIN {
+                table[usize::from(self.register as u8 ^ byte)] ^ (self.register >> 8)
+            } else {
+                let index = (self.register >> (WIDTH - 8)) as u8 ^ byte;
+
+                (table[usize::from(index)] ^ (self.register << 8)) & Self::MASK
+            };
+        }
+    }
+
+    /// Returns the CRC of the bytes added so far.
+    #[inline]
+    #[must_use]
+    pub const fn finish(&self) -> u64 {
+        let register = if REFIN == REFOUT {
+            self.register
+        } else {
+            reflect(self.register, WIDTH)
+        };
+
+        (register ^ XOR_OUT) & Self::MASK
+    }
+}
+
+impl<
+        const WIDTH: u32,
+        const POLY: u64,
+        const INIT: u64,
+        const REFIN: bool,
+        const REFOUT: bool,
+        const XOR_OUT: u64,
+    > Default for Crc<WIDTH, POLY, INIT, REFIN, REFOUT, XOR_OUT>
+{
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<
+        const WIDTH: u32,
+        const POLY: u64,
+        const INIT: u64,
+        const REFIN: bool,
+        const REFOUT: bool,
+        const XOR_OUT: u64,
+    > Checksum for Crc<WIDTH, POLY, INIT, REFIN, REFOUT, XOR_OUT>
+{
+    const LEN: usize = WIDTH.div_ceil(8) as usize;
+
+    #[inline]
+    fn update(&mut self, bytes: &[u8]) {
+        self.update(bytes);
+    }
+
+    #[inline]
+    fn finish(&self) -> u64 {
+        self.finish()
+    }
+}
+
+/// Reverse the lowest `width` bits of `value`.
+const fn reflect(value: u64, width: u32) -> u64 {
+    value.reverse_bits() >> (64 - width)
+}
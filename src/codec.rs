@@ -0,0 +1,176 @@
+use core::{fmt, marker::PhantomData};
+
+use crate::decoder::{Decoder, Error as DError, Result as DResult};
+use crate::encoder::{Encoder, Error as EError, Result as EResult};
+use crate::Size;
+
+/// Trait for types that can both decode frames and encode `Item`s.
+///
+/// This is implemented for every type that implements both [`Decoder`]
+/// and [`Encoder`].
+pub trait Codec<Item: ?Sized>: Decoder + Encoder<Item> {}
+
+impl<T: Decoder + Encoder<Item> + ?Sized, Item: ?Sized> Codec<Item> for T {}
+
+/// A [`Codec`] built from a pair of closures, see [`fn_codec`].
+pub struct FnCodec<T, Err, D, E> {
+    decode: D,
+    encode: E,
+    hint: Size,
+    encode_hint: Size,
+    _marker: PhantomData<fn() -> (T, Err)>,
+}
+
+/// Create a [`Codec`] from a closure that decodes frames, and a closure
+/// that encodes them.
+///
+/// Both closures follow the contracts of [`Decoder::decode`] and
+/// [`Encoder::encode`] respectively. Decoded frames are owned, as they
+/// cannot borrow from the source.
+///
+/// This is meant for prototypes and tests; prefer dedicated types otherwise.
+///
+/// ```
+/// # #[cfg(feature = "std")] {
+/// use kodek::codec::fn_codec;
+/// use kodek::decoder::Error as DError;
+/// use kodek::encoder::Error as EError;
+/// use kodek::io::{FramedRead, FramedWrite};
+/// use kodek::Size;
+///
+/// // Frames of a big endian `u16`.
+/// let codec = fn_codec(
+///     |src: &mut &[u8]| match src.split_first_chunk::<2>() {
+///         Some((bytes, rest)) => {
+///             *src = rest;
+///             Ok(u16::from_be_bytes(*bytes))
+///         }
+///         None => Err(DError::<core::convert::Infallible>::Incomplete {
+///             needed: Size::new(2 - src.len()),
+///         }),
+///     },
+///     |item: &u16, dst: &mut &mut [u8]| {
+///         if dst.len() < 2 {
+///             return Err(EError::<core::convert::Infallible>::Full {
+///                 needed: Size::new(2 - dst.len()),
+///             });
+///         }
+///
+///         let (bytes, rest) = core::mem::take(dst).split_at_mut(2);
+///         bytes.copy_from_slice(&item.to_be_bytes());
+///         *dst = rest;
+///         Ok(())
+///     },
+/// )
+/// .with_hint(Size::new(2))
+/// .with_encode_hint(Size::new(2));
+///
+/// let mut write = FramedWrite::new(Vec::new(), codec.clone());
+///
+/// write.write_frame(&1).unwrap();
+/// write.write_frame(&515).unwrap();
+/// write.flush().unwrap();
+///
+/// let bytes = write.into_inner();
+///
+/// assert_eq!(bytes, [0, 1, 2, 3]);
+///
+/// let mut read = FramedRead::new(&bytes[..], codec);
+///
+/// assert_eq!(read.next_frame().unwrap(), Some(1));
+/// assert_eq!(read.next_frame().unwrap(), Some(515));
+/// assert_eq!(read.next_frame().unwrap(), None);
+/// # }
+/// ```
+#[inline]
+#[must_use]
+pub fn fn_codec<T, Err, D, E>(decode: D, encode: E) -> FnCodec<T, Err, D, E>
+where
+    D: FnMut(&mut &[u8]) -> Result<T, DError<Err>>,
+{
+    FnCodec {
+        decode,
+        encode,
+        hint: Size::Unknown,
+        encode_hint: Size::Unknown,
+        _marker: PhantomData,
+    }
+}
+
+impl<T, Err, D, E> FnCodec<T, Err, D, E> {
+    /// Set the estimate returned by [`Decoder::hint`].
+    #[inline]
+    #[must_use]
+    pub fn with_hint(mut self, hint: Size) -> FnCodec<T, Err, D, E> {
+        self.hint = hint;
+        self
+    }
+
+    /// Set the estimate returned by [`Encoder::encode_hint`] for every item.
+    #[inline]
+    #[must_use]
+    pub fn with_encode_hint(mut self, encode_hint: Size) -> FnCodec<T, Err, D, E> {
+        self.encode_hint = encode_hint;
+        self
+    }
+}
+
+impl<T, Err, D: Clone, E: Clone> Clone for FnCodec<T, Err, D, E> {
+    #[inline]
+    fn clone(&self) -> Self {
+        FnCodec {
+            decode: self.decode.clone(),
+            encode: self.encode.clone(),
+            hint: self.hint,
+            encode_hint: self.encode_hint,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<T, Err, D, E> fmt::Debug for FnCodec<T, Err, D, E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FnCodec")
+            .field("hint", &self.hint)
+            .field("encode_hint", &self.encode_hint)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T, Err, D, E> Decoder for FnCodec<T, Err, D, E>
+where
+    D: FnMut(&mut &[u8]) -> Result<T, DError<Err>>,
+    Err: fmt::Display + fmt::Debug,
+{
+    type Item<'src> = T;
+    type Error = Err;
+
+    #[inline]
+    fn hint(&self) -> Size {
+        self.hint
+    }
+
+    #[inline]
+    fn decode<'s>(&mut self, src: &mut &'s [u8]) -> DResult<'s, Self> {
+        (self.decode)(src)
+    }
+}
+
+impl<T, Err, D, E, Item, EncodeErr> Encoder<Item> for FnCodec<T, Err, D, E>
+where
+    Item: ?Sized,
+    E: FnMut(&Item, &mut &mut [u8]) -> Result<(), EError<EncodeErr>>,
+    EncodeErr: fmt::Display + fmt::Debug,
+{
+    type Error = EncodeErr;
+
+    #[inline]
+    fn encode_hint(&self, _: &Item) -> Size {
+        self.encode_hint
+    }
+
+    #[inline]
+    fn encode(&mut self, item: &Item, dst: &mut &mut [u8]) -> EResult<Self::Error> {
+        (self.encode)(item, dst)
+    }
+}
@@ -0,0 +1,1492 @@
+use core::{
+    fmt,
+    hash::{BuildHasher, BuildHasherDefault, Hasher},
+    marker::PhantomData,
+};
+
+use arrayvec::ArrayVec;
+
+use crate::checksum::{crc::Crc32IsoHdlc, Checksum};
+use crate::decoder::{Decoder, Error as DError, Result as DResult};
+use crate::encoder::{Encoder, Error as EError, Result as EResult};
+use crate::endian::{BigEndian, ByteOrder, Endian};
+use crate::{Size, SizeHint};
+
+/// A [`Hasher`] implementing 64-bit FNV-1a.
+///
+/// This is fast for the short inputs typical of frames, but is not
+/// resistant to collisions crafted by an adversary.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Fnv(u64);
+
+impl Fnv {
+    const OFFSET_BASIS: u64 = 0xCBF2_9CE4_8422_2325;
+    const PRIME: u64 = 0x0000_0100_0000_01B3;
+}
+
+impl Default for Fnv {
+    #[inline]
+    fn default() -> Self {
+        Fnv(Self::OFFSET_BASIS)
+    }
+}
+
+impl Hasher for Fnv {
+    #[inline]
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 ^= u64::from(byte);
+            self.0 = self.0.wrapping_mul(Self::PRIME);
+        }
+    }
+
+    #[inline]
+    fn finish(&self) -> u64 {
+        self.0
+    }
+}
+
+/// A [`BuildHasher`] for [`Fnv`].
+pub type BuildFnv = BuildHasherDefault<Fnv>;
+
+/// A [`BuildHasher`] for XXH3.
+#[cfg(feature = "xxhash")]
+pub type BuildXxh3 = xxhash_rust::xxh3::Xxh3DefaultBuilder;
+
+/// What a [`Dedup`] does with a frame identical to the one before it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum DedupMode {
+    /// Yield the frame, marking it as a duplicate.
+    #[default]
+    Flag,
+    /// Skip the frame, and decode the next one instead.
+    Suppress,
+}
+
+/// A frame along with the digest of its bytes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Digested<T> {
+    /// The decoded frame.
+    pub item: T,
+    /// The digest of the bytes the frame was decoded from.
+    pub digest: u64,
+    /// Whether the frame has the same digest as the frame before it.
+    pub duplicate: bool,
+}
+
+/// A [`Decoder`] that digests the bytes of each frame decoded by an inner
+/// decoder, to detect consecutive duplicates.
+///
+/// When suppressing duplicates, a skipped frame is decoded again should the
+/// frame after it be incomplete, so the inner decoder must be able to decode
+/// the same bytes twice.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Dedup<D, S = BuildFnv> {
+    /// The inner decoder.
+    pub decoder: D,
+    /// The hasher used for digesting frames.
+    pub hasher: S,
+    /// What to do with duplicate frames.
+    pub mode: DedupMode,
+    last: Option<u64>,
+}
+
+impl<D> Dedup<D> {
+    /// Create a new [`Dedup`] that flags duplicates using [`Fnv`].
+    #[inline]
+    #[must_use]
+    pub const fn new(decoder: D) -> Dedup<D> {
+        Dedup::with_hasher(decoder, BuildHasherDefault::new())
+    }
+}
+
+impl<D, S> Dedup<D, S> {
+    /// Create a new [`Dedup`] that flags duplicates using `hasher`.
+    #[inline]
+    #[must_use]
+    pub const fn with_hasher(decoder: D, hasher: S) -> Dedup<D, S> {
+        Dedup {
+            decoder,
+            hasher,
+            mode: DedupMode::Flag,
+            last: None,
+        }
+    }
+
+    /// Set what to do with duplicate frames.
+    #[inline]
+    #[must_use]
+    pub fn with_mode(mut self, mode: DedupMode) -> Dedup<D, S> {
+        self.mode = mode;
+        self
+    }
+
+    /// Returns the digest of the last decoded frame, if any.
+    #[inline]
+    #[must_use]
+    pub const fn last_digest(&self) -> Option<u64> {
+        self.last
+    }
+
+    /// Forget the last decoded frame, so that the next frame is never
+    /// a duplicate.
+    #[inline]
+    pub fn reset(&mut self) {
+        self.last = None;
+    }
+}
+
+impl<D: Decoder, S: BuildHasher> Dedup<D, S> {
+    fn next<'s>(&mut self, src: &mut &'s [u8], eof: bool) -> DResult<'s, Self> {
+        let start = *src;
+
+        loop {
+            let frame = *src;
+            let result = if eof {
+                self.decoder.decode_eof(src)
+            } else {
+                self.decoder.decode(src)
+            };
+
+            let item = match result {
+                Ok(item) => item,
+                Err(error @ DError::Incomplete { .. }) => {
+                    *src = start;
+
+                    return Err(error);
+                }
+                Err(error) => return Err(error),
+            };
+
+            let frame = &frame[..frame.len() - src.len()];
+            let digest = {
+                let mut hasher = self.hasher.build_hasher();
+                hasher.write(frame);
+                hasher.finish()
+            };
+
+            let duplicate = self.last == Some(digest);
+            self.last = Some(digest);
+
+            // Frames that consume nothing are never skipped, as we'd never stop.
+            if duplicate && self.mode == DedupMode::Suppress && !frame.is_empty() {
+                continue;
+            }
+
+            return Ok(Digested {
+                item,
+                digest,
+                duplicate,
+            });
+        }
+    }
+}
+
+impl<D: Decoder, S: BuildHasher> Decoder for Dedup<D, S> {
+    type Item<'src> = Digested<D::Item<'src>>;
+    type Error = D::Error;
+
+    #[inline]
+    fn hint(&self) -> Size {
+        self.decoder.hint()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> SizeHint {
+        self.decoder.size_hint()
+    }
+
+    #[inline]
+    fn decode<'s>(&mut self, src: &mut &'s [u8]) -> DResult<'s, Self> {
+        self.next(src, false)
+    }
+
+    #[inline]
+    fn decode_eof<'s>(&mut self, src: &mut &'s [u8]) -> DResult<'s, Self> {
+        self.next(src, true)
+    }
+}
+
+/// A [`Decoder`] that maps the frames of another decoder with a closure.
+///
+/// This is mostly useful for turning borrowed frames into owned ones,
+/// such as for drivers that can only yield owned frames.
+#[derive(Clone)]
+pub struct Map<D, F> {
+    /// The inner decoder.
+    pub decoder: D,
+    f: F,
+}
+
+impl<D, F> Map<D, F> {
+    /// Create a new decoder mapping the frames of `decoder` with `f`.
+    #[inline]
+    #[must_use]
+    pub const fn new(decoder: D, f: F) -> Map<D, F> {
+        Map { decoder, f }
+    }
+}
+
+impl<D: fmt::Debug, F> fmt::Debug for Map<D, F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Map")
+            .field("decoder", &self.decoder)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<D, F, T> Decoder for Map<D, F>
+where
+    D: Decoder,
+    F: for<'a> FnMut(D::Item<'a>) -> T,
+{
+    type Item<'src> = T;
+    type Error = D::Error;
+
+    #[inline]
+    fn hint(&self) -> Size {
+        self.decoder.hint()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> SizeHint {
+        self.decoder.size_hint()
+    }
+
+    #[inline]
+    fn decode<'s>(&mut self, src: &mut &'s [u8]) -> DResult<'s, Self> {
+        self.decoder.decode(src).map(&mut self.f)
+    }
+
+    #[inline]
+    fn decode_eof<'s>(&mut self, src: &mut &'s [u8]) -> DResult<'s, Self> {
+        self.decoder.decode_eof(src).map(&mut self.f)
+    }
+}
+
+/// A [`Decoder`] for byte frames of another decoder that are masked with a
+/// repeating XOR mask, such as the 4-byte mask of WebSocket client frames.
+///
+/// The mask starts over with each frame. Frames are yielded as a
+/// [`Masked`] view, which unmasks them lazily or into a scratch buffer.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct XorMask<D, const N: usize = 4> {
+    /// The inner decoder.
+    pub decoder: D,
+    /// The mask.
+    pub mask: [u8; N],
+}
+
+impl<D, const N: usize> XorMask<D, N> {
+    /// Create a new decoder unmasking the frames of `decoder` with `mask`.
+    #[inline]
+    #[must_use]
+    pub const fn new(decoder: D, mask: [u8; N]) -> XorMask<D, N> {
+        XorMask { decoder, mask }
+    }
+}
+
+impl<D, const N: usize> XorMask<D, N>
+where
+    D: for<'a> Decoder<Item<'a> = &'a [u8]>,
+{
+    fn next<'s>(&mut self, src: &mut &'s [u8], eof: bool) -> DResult<'s, Self> {
+        let bytes = if eof {
+            self.decoder.decode_eof(src)
+        } else {
+            self.decoder.decode(src)
+        }?;
+
+        Ok(Masked::new(bytes, self.mask))
+    }
+}
+
+impl<D, const N: usize> Decoder for XorMask<D, N>
+where
+    D: for<'a> Decoder<Item<'a> = &'a [u8]>,
+{
+    type Item<'src> = Masked<'src, N>;
+    type Error = D::Error;
+
+    #[inline]
+    fn hint(&self) -> Size {
+        self.decoder.hint()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> SizeHint {
+        self.decoder.size_hint()
+    }
+
+    #[inline]
+    fn decode<'s>(&mut self, src: &mut &'s [u8]) -> DResult<'s, Self> {
+        self.next(src, false)
+    }
+
+    #[inline]
+    fn decode_eof<'s>(&mut self, src: &mut &'s [u8]) -> DResult<'s, Self> {
+        self.next(src, true)
+    }
+}
+
+/// XOR `bytes` in place with a repeating `mask`, starting from its first
+/// byte.
+///
+/// Applying the same mask twice restores the original bytes.
+#[inline]
+pub fn xor_mask<const N: usize>(bytes: &mut [u8], mask: [u8; N]) {
+    for (byte, mask) in bytes.iter_mut().zip(mask.iter().cycle()) {
+        *byte ^= mask;
+    }
+}
+
+/// A view of bytes masked with a repeating XOR mask.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Masked<'src, const N: usize = 4> {
+    bytes: &'src [u8],
+    mask: [u8; N],
+}
+
+impl<'src, const N: usize> Masked<'src, N> {
+    /// Create a new view of `bytes` masked with `mask`.
+    #[inline]
+    #[must_use]
+    pub const fn new(bytes: &'src [u8], mask: [u8; N]) -> Masked<'src, N> {
+        Masked { bytes, mask }
+    }
+
+    /// Returns the masked bytes.
+    #[inline]
+    #[must_use]
+    pub const fn masked(&self) -> &'src [u8] {
+        self.bytes
+    }
+
+    /// Returns the mask.
+    #[inline]
+    #[must_use]
+    pub const fn mask(&self) -> [u8; N] {
+        self.mask
+    }
+
+    /// Returns the amount of bytes.
+    #[inline]
+    #[must_use]
+    pub const fn len(&self) -> usize {
+        self.bytes.len()
+    }
+
+    /// Returns whether there are no bytes.
+    #[inline]
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+
+    /// Returns an iterator over the unmasked bytes.
+    #[inline]
+    pub fn iter(&self) -> Unmasked<'src, N> {
+        Unmasked {
+            bytes: self.bytes.iter(),
+            mask: self.mask,
+            index: 0,
+        }
+    }
+
+    /// Unmask the bytes into the start of `buf`, returning the unmasked
+    /// bytes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `buf` is shorter than the bytes.
+    #[inline]
+    pub fn unmask_into<'b>(&self, buf: &'b mut [u8]) -> &'b mut [u8] {
+        let buf = &mut buf[..self.bytes.len()];
+
+        buf.copy_from_slice(self.bytes);
+        xor_mask(buf, self.mask);
+
+        buf
+    }
+}
+
+impl<'src, const N: usize> IntoIterator for Masked<'src, N> {
+    type Item = u8;
+    type IntoIter = Unmasked<'src, N>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'src, const N: usize> IntoIterator for &Masked<'src, N> {
+    type Item = u8;
+    type IntoIter = Unmasked<'src, N>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// An iterator over the unmasked bytes of a [`Masked`] view.
+#[derive(Debug, Clone)]
+pub struct Unmasked<'src, const N: usize = 4> {
+    bytes: core::slice::Iter<'src, u8>,
+    mask: [u8; N],
+    index: usize,
+}
+
+impl<const N: usize> Iterator for Unmasked<'_, N> {
+    type Item = u8;
+
+    #[inline]
+    fn next(&mut self) -> Option<u8> {
+        let byte = *self.bytes.next()?;
+        let mask = self.index.checked_rem(N).map_or(0, |i| self.mask[i]);
+
+        self.index += 1;
+
+        Some(byte ^ mask)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.bytes.size_hint()
+    }
+}
+
+impl<const N: usize> ExactSizeIterator for Unmasked<'_, N> {}
+
+impl<const N: usize> core::iter::FusedIterator for Unmasked<'_, N> {}
+
+/// A frame along with its position in the stream.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Spanned<T> {
+    /// The decoded frame.
+    pub item: T,
+    /// The offset of the first byte of the frame.
+    pub start: u64,
+    /// The offset just past the last byte of the frame.
+    pub end: u64,
+}
+
+/// A [`Decoder`] that tracks the position of each frame decoded by an
+/// inner decoder, counting every byte consumed across calls.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Positioned<D> {
+    /// The inner decoder.
+    pub decoder: D,
+    position: u64,
+}
+
+impl<D> Positioned<D> {
+    /// Create a new decoder tracking the position of the frames of
+    /// `decoder`, starting at offset zero.
+    #[inline]
+    #[must_use]
+    pub const fn new(decoder: D) -> Positioned<D> {
+        Positioned::with_position(decoder, 0)
+    }
+
+    /// Create a new decoder tracking the position of the frames of
+    /// `decoder`, starting at `position`.
+    #[inline]
+    #[must_use]
+    pub const fn with_position(decoder: D, position: u64) -> Positioned<D> {
+        Positioned { decoder, position }
+    }
+
+    /// Returns the amount of bytes consumed so far.
+    #[inline]
+    #[must_use]
+    pub const fn position(&self) -> u64 {
+        self.position
+    }
+}
+
+impl<D: Decoder> Positioned<D> {
+    fn next<'s>(&mut self, src: &mut &'s [u8], eof: bool) -> DResult<'s, Self> {
+        let len = src.len();
+        let item = if eof {
+            self.decoder.decode_eof(src)
+        } else {
+            self.decoder.decode(src)
+        }?;
+
+        let start = self.position;
+        self.position += (len - src.len()) as u64;
+
+        Ok(Spanned {
+            item,
+            start,
+            end: self.position,
+        })
+    }
+}
+
+impl<D: Decoder> Decoder for Positioned<D> {
+    type Item<'src> = Spanned<D::Item<'src>>;
+    type Error = D::Error;
+
+    #[inline]
+    fn hint(&self) -> Size {
+        self.decoder.hint()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> SizeHint {
+        self.decoder.size_hint()
+    }
+
+    #[inline]
+    fn decode<'s>(&mut self, src: &mut &'s [u8]) -> DResult<'s, Self> {
+        self.next(src, false)
+    }
+
+    #[inline]
+    fn decode_eof<'s>(&mut self, src: &mut &'s [u8]) -> DResult<'s, Self> {
+        self.next(src, true)
+    }
+}
+
+/// A [`Decoder`] that limits the amount of bytes a frame of another decoder
+/// may span.
+///
+/// Frames that are known to be longer than the limit are a fatal error,
+/// rather than incomplete, so that a driver never buffers more than
+/// [`max_len`](Limited::max_len) bytes for a single frame.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Limited<D> {
+    /// The inner decoder.
+    pub decoder: D,
+    /// The maximum amount of bytes in a frame.
+    pub max_len: usize,
+}
+
+impl<D> Limited<D> {
+    /// Create a new decoder limiting the frames of `decoder` to `max_len`
+    /// bytes.
+    #[inline]
+    #[must_use]
+    pub const fn new(decoder: D, max_len: usize) -> Limited<D> {
+        Limited { decoder, max_len }
+    }
+}
+
+impl<D: Decoder> Limited<D> {
+    fn next<'s>(&mut self, src: &mut &'s [u8], eof: bool) -> DResult<'s, Self> {
+        let mut rest = *src;
+        let result = if eof {
+            self.decoder.decode_eof(&mut rest)
+        } else {
+            self.decoder.decode(&mut rest)
+        };
+
+        let len = match &result {
+            Ok(_) => src.len() - rest.len(),
+            // Incomplete frames need at least one more byte.
+            Err(DError::Incomplete { needed }) => {
+                src.len().saturating_add(needed.get_or_one().get())
+            }
+            Err(_) => 0,
+        };
+
+        if len > self.max_len {
+            return Err(DError::Fatal {
+                error: LimitedError::TooLong,
+            });
+        }
+
+        let item = result.map_err(|error| error.map(LimitedError::Decoder))?;
+
+        *src = rest;
+
+        Ok(item)
+    }
+}
+
+impl<D: Decoder> Decoder for Limited<D> {
+    type Item<'src> = D::Item<'src>;
+    type Error = LimitedError<D::Error>;
+
+    #[inline]
+    fn hint(&self) -> Size {
+        self.decoder.hint()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> SizeHint {
+        let hint = self.decoder.size_hint();
+        let max_len = Size::new(self.max_len);
+
+        SizeHint::new(
+            hint.min,
+            match hint.max {
+                Size::Unknown => max_len,
+                max => max.min(max_len),
+            },
+        )
+    }
+
+    #[inline]
+    fn decode<'s>(&mut self, src: &mut &'s [u8]) -> DResult<'s, Self> {
+        self.next(src, false)
+    }
+
+    #[inline]
+    fn decode_eof<'s>(&mut self, src: &mut &'s [u8]) -> DResult<'s, Self> {
+        self.next(src, true)
+    }
+}
+
+/// Type for errors that may occur while decoding a limited frame.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum LimitedError<E> {
+    /// The frame is longer than the limit.
+    TooLong,
+    /// The inner decoder failed.
+    Decoder(E),
+}
+
+impl<E: fmt::Display> fmt::Display for LimitedError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::TooLong => f.write_str("frame too long"),
+            Self::Decoder(error) => fmt::Display::fmt(error, f),
+        }
+    }
+}
+
+impl<E: fmt::Display + fmt::Debug> core::error::Error for LimitedError<E> {}
+
+/// A [`Decoder`] that records the context of the fatal errors of another
+/// decoder, such as the name of the field it decodes.
+///
+/// Wrapping decoders that already record context adds to their context,
+/// so that errors render as `packet > header > flags: invalid bits`.
+pub struct WithContext<D, E> {
+    /// The inner decoder.
+    pub decoder: D,
+    /// The context of errors.
+    pub context: &'static str,
+    _marker: PhantomData<fn() -> E>,
+}
+
+impl<D: Decoder> WithContext<D, D::Error> {
+    /// Create a new decoder recording `context` for errors of `decoder`.
+    #[inline]
+    #[must_use]
+    pub const fn new(decoder: D, context: &'static str) -> WithContext<D, D::Error> {
+        WithContext {
+            decoder,
+            context,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<D, E> WithContext<D, E>
+where
+    D: Decoder<Error = ContextError<E>>,
+{
+    /// Create a new decoder adding `context` to errors of `decoder`, which
+    /// already records context.
+    #[inline]
+    #[must_use]
+    pub const fn nested(decoder: D, context: &'static str) -> WithContext<D, E> {
+        WithContext {
+            decoder,
+            context,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<D: fmt::Debug, E> fmt::Debug for WithContext<D, E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WithContext")
+            .field("decoder", &self.decoder)
+            .field("context", &self.context)
+            .finish()
+    }
+}
+
+impl<D: Clone, E> Clone for WithContext<D, E> {
+    #[inline]
+    fn clone(&self) -> Self {
+        WithContext {
+            decoder: self.decoder.clone(),
+            context: self.context,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<D, E> Decoder for WithContext<D, E>
+where
+    D: Decoder,
+    D::Error: Into<ContextError<E>>,
+    E: fmt::Display + fmt::Debug,
+{
+    type Item<'src> = D::Item<'src>;
+    type Error = ContextError<E>;
+
+    #[inline]
+    fn hint(&self) -> Size {
+        self.decoder.hint()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> SizeHint {
+        self.decoder.size_hint()
+    }
+
+    #[inline]
+    fn decode<'s>(&mut self, src: &mut &'s [u8]) -> DResult<'s, Self> {
+        self.decoder
+            .decode(src)
+            .map_err(|error| error.map(|error| error.into().with_context(self.context)))
+    }
+
+    #[inline]
+    fn decode_eof<'s>(&mut self, src: &mut &'s [u8]) -> DResult<'s, Self> {
+        self.decoder
+            .decode_eof(src)
+            .map_err(|error| error.map(|error| error.into().with_context(self.context)))
+    }
+}
+
+/// An error along with the contexts it occurred in, without allocating.
+///
+/// Up to [`ContextError::MAX_DEPTH`] of the innermost contexts are kept.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ContextError<E> {
+    /// The error.
+    pub error: E,
+    contexts: ArrayVec<&'static str, { ContextError::<()>::MAX_DEPTH }>,
+    truncated: bool,
+}
+
+impl<E> ContextError<E> {
+    /// The maximum amount of contexts kept.
+    pub const MAX_DEPTH: usize = 8;
+
+    /// Create a new [`ContextError`] without any context.
+    #[inline]
+    #[must_use]
+    pub const fn new(error: E) -> ContextError<E> {
+        ContextError {
+            error,
+            contexts: ArrayVec::new_const(),
+            truncated: false,
+        }
+    }
+
+    /// Add an outer context to the error.
+    #[inline]
+    #[must_use]
+    pub fn with_context(mut self, context: &'static str) -> ContextError<E> {
+        if self.contexts.try_push(context).is_err() {
+            self.truncated = true;
+        }
+
+        self
+    }
+
+    /// Returns the contexts of the error, from the outermost to the innermost.
+    #[inline]
+    pub fn contexts(&self) -> impl DoubleEndedIterator<Item = &'static str> + '_ {
+        self.contexts.iter().rev().copied()
+    }
+
+    /// Returns whether outer contexts were dropped for exceeding
+    /// [`ContextError::MAX_DEPTH`].
+    #[inline]
+    #[must_use]
+    pub const fn is_truncated(&self) -> bool {
+        self.truncated
+    }
+}
+
+impl<E> From<E> for ContextError<E> {
+    #[inline]
+    fn from(error: E) -> Self {
+        ContextError::new(error)
+    }
+}
+
+impl<E: fmt::Display> fmt::Display for ContextError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.truncated {
+            f.write_str("... > ")?;
+        }
+
+        for (i, context) in self.contexts().enumerate() {
+            if i > 0 {
+                f.write_str(" > ")?;
+            }
+
+            f.write_str(context)?;
+        }
+
+        if !self.contexts.is_empty() {
+            f.write_str(": ")?;
+        }
+
+        fmt::Display::fmt(&self.error, f)
+    }
+}
+
+impl<E: fmt::Display + fmt::Debug> core::error::Error for ContextError<E> {}
+
+#[cfg(feature = "defmt")]
+impl<E: defmt::Format> defmt::Format for ContextError<E> {
+    fn format(&self, f: defmt::Formatter<'_>) {
+        if self.truncated {
+            defmt::write!(f, "... > ");
+        }
+
+        for (i, context) in self.contexts().enumerate() {
+            if i > 0 {
+                defmt::write!(f, " > ");
+            }
+
+            defmt::write!(f, "{=str}", context);
+        }
+
+        if !self.contexts.is_empty() {
+            defmt::write!(f, ": ");
+        }
+
+        self.error.format(f);
+    }
+}
+
+/// A [`Decoder`] and [`Encoder`] for frames followed by a [`Checksum`] of
+/// their bytes, CRC-32 by default.
+///
+/// Frames are split off by the [`framing`](Checksummed::framing) decoder,
+/// such as a [`LengthDelimited`](crate::framing::LengthDelimited), and the
+/// checksum covers every byte it reads, including any header. The checksum
+/// is verified before the [`inner`](Checksummed::inner) decoder is invoked
+/// on the body of the frame, which it must decode exactly, so corrupted
+/// bodies are a fatal [`ChecksummedError::ChecksumMismatch`] rather than
+/// being passed on. A corrupted header may still fail to frame, as the
+/// checksum can only be found once the frame is.
+///
+/// Encoding writes a frame with the framing encoder, followed by its
+/// checksum; the inner decoder is not involved.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Checksummed<F, D, C = Crc32IsoHdlc, B: ByteOrder = BigEndian> {
+    /// The framing decoder or encoder.
+    pub framing: F,
+    /// The decoder for the bodies of frames.
+    pub inner: D,
+    /// The byte order of the checksum.
+    pub byte_order: B,
+    _marker: PhantomData<fn() -> C>,
+}
+
+impl<F, D> Checksummed<F, D> {
+    /// Create a new decoder for frames split off by `framing`, followed by
+    /// a big endian CRC-32, with bodies decoded by `inner`.
+    #[inline]
+    #[must_use]
+    pub const fn new(framing: F, inner: D) -> Checksummed<F, D> {
+        Checksummed {
+            framing,
+            inner,
+            byte_order: BigEndian,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<F, D, C: Checksum, B: ByteOrder> Checksummed<F, D, C, B> {
+    /// Set the checksum following each frame.
+    #[inline]
+    #[must_use]
+    pub fn with_checksum<T: Checksum>(self) -> Checksummed<F, D, T, B> {
+        Checksummed {
+            framing: self.framing,
+            inner: self.inner,
+            byte_order: self.byte_order,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Set the byte order of the checksum.
+    #[inline]
+    #[must_use]
+    pub fn with_byte_order<T: ByteOrder>(self, byte_order: T) -> Checksummed<F, D, C, T> {
+        Checksummed {
+            framing: self.framing,
+            inner: self.inner,
+            byte_order,
+            _marker: PhantomData,
+        }
+    }
+
+    fn read(&self, bytes: &[u8]) -> u64 {
+        const { assert!(C::LEN <= 8, "checksums are at most 8 bytes") };
+
+        let mut buf = [0; 8];
+
+        match self.byte_order.endian() {
+            Endian::Little => {
+                buf[..C::LEN].copy_from_slice(bytes);
+                u64::from_le_bytes(buf)
+            }
+            Endian::Big => {
+                buf[8 - C::LEN..].copy_from_slice(bytes);
+                u64::from_be_bytes(buf)
+            }
+        }
+    }
+
+    fn write(&self, checksum: u64, bytes: &mut [u8]) {
+        const { assert!(C::LEN <= 8, "checksums are at most 8 bytes") };
+
+        match self.byte_order.endian() {
+            Endian::Little => bytes.copy_from_slice(&checksum.to_le_bytes()[..C::LEN]),
+            Endian::Big => bytes.copy_from_slice(&checksum.to_be_bytes()[8 - C::LEN..]),
+        }
+    }
+}
+
+impl<F, D, C, B> Checksummed<F, D, C, B>
+where
+    F: for<'a> Decoder<Item<'a> = &'a [u8]>,
+    D: Decoder,
+    C: Checksum,
+    B: ByteOrder,
+{
+    fn next<'s>(&mut self, src: &mut &'s [u8], eof: bool) -> DResult<'s, Self> {
+        let mut rest = *src;
+        let result = if eof {
+            self.framing.decode_eof(&mut rest)
+        } else {
+            self.framing.decode(&mut rest)
+        };
+
+        let mut body = result.map_err(|error| match error {
+            DError::Incomplete { needed } => DError::Incomplete {
+                needed: needed + Size::new(C::LEN),
+            },
+            error => error.map(ChecksummedError::Framing),
+        })?;
+
+        let len = src.len() - rest.len();
+
+        let Some((checksum, rest)) = rest.split_at_checked(C::LEN) else {
+            return Err(DError::Incomplete {
+                needed: Size::new(C::LEN - rest.len()),
+            });
+        };
+
+        let expected = self.read(checksum);
+        let actual = C::checksum(&src[..len]);
+
+        if expected != actual {
+            return Err(DError::Fatal {
+                error: ChecksummedError::ChecksumMismatch { expected, actual },
+            });
+        }
+
+        let item = match self.inner.decode_eof(&mut body) {
+            Ok(item) if body.is_empty() => item,
+            Err(DError::Fatal { error }) => {
+                return Err(DError::Fatal {
+                    error: ChecksummedError::Decoder(error),
+                })
+            }
+            _ => {
+                return Err(DError::Fatal {
+                    error: ChecksummedError::Body,
+                })
+            }
+        };
+
+        *src = rest;
+
+        Ok(item)
+    }
+}
+
+impl<F, D, C, B> Decoder for Checksummed<F, D, C, B>
+where
+    F: for<'a> Decoder<Item<'a> = &'a [u8]>,
+    D: Decoder,
+    C: Checksum,
+    B: ByteOrder,
+{
+    type Item<'src> = D::Item<'src>;
+    type Error = ChecksummedError<F::Error, D::Error>;
+
+    #[inline]
+    fn hint(&self) -> Size {
+        self.framing.hint() + Size::new(C::LEN)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> SizeHint {
+        let hint = self.framing.size_hint();
+
+        SizeHint::new(hint.min + Size::new(C::LEN), hint.max + Size::new(C::LEN))
+    }
+
+    #[inline]
+    fn decode<'s>(&mut self, src: &mut &'s [u8]) -> DResult<'s, Self> {
+        self.next(src, false)
+    }
+
+    #[inline]
+    fn decode_eof<'s>(&mut self, src: &mut &'s [u8]) -> DResult<'s, Self> {
+        self.next(src, true)
+    }
+}
+
+impl<F, D, C, B, Item> Encoder<Item> for Checksummed<F, D, C, B>
+where
+    F: Encoder<Item>,
+    C: Checksum,
+    B: ByteOrder,
+    Item: ?Sized,
+{
+    type Error = F::Error;
+
+    #[inline]
+    fn encode_hint(&self, item: &Item) -> Size {
+        self.framing.encode_hint(item) + Size::new(C::LEN)
+    }
+
+    #[inline]
+    fn encode(&mut self, item: &Item, dst: &mut &mut [u8]) -> EResult<Self::Error> {
+        let capacity = dst.len();
+        let mut rest = &mut **dst;
+
+        self.framing
+            .encode(item, &mut rest)
+            .map_err(|error| match error {
+                EError::Full { needed } => EError::Full {
+                    needed: needed + Size::new(C::LEN),
+                },
+                error => error,
+            })?;
+
+        if rest.len() < C::LEN {
+            return Err(EError::Full {
+                needed: Size::new(C::LEN - rest.len()),
+            });
+        }
+
+        let len = capacity - rest.len();
+        let checksum = C::checksum(&dst[..len]);
+        let (bytes, rest) = core::mem::take(dst).split_at_mut(len + C::LEN);
+
+        self.write(checksum, &mut bytes[len..]);
+
+        *dst = rest;
+
+        Ok(())
+    }
+}
+
+/// Type for errors that may occur while decoding a checksummed frame.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum ChecksummedError<F, E> {
+    /// The checksum of the frame does not match its bytes.
+    ChecksumMismatch {
+        /// The checksum that followed the frame.
+        expected: u64,
+        /// The checksum of the bytes of the frame.
+        actual: u64,
+    },
+    /// The framing decoder failed.
+    Framing(F),
+    /// The inner decoder failed.
+    Decoder(E),
+    /// The inner decoder did not decode exactly the body of the frame.
+    Body,
+}
+
+impl<F: fmt::Display, E: fmt::Display> fmt::Display for ChecksummedError<F, E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ChecksumMismatch { expected, actual } => {
+                write!(
+                    f,
+                    "checksum mismatch: expected {expected:#x}, found {actual:#x}"
+                )
+            }
+            Self::Framing(error) => fmt::Display::fmt(error, f),
+            Self::Decoder(error) => fmt::Display::fmt(error, f),
+            Self::Body => f.write_str("frame body not decoded exactly"),
+        }
+    }
+}
+
+impl<F, E> core::error::Error for ChecksummedError<F, E>
+where
+    F: fmt::Display + fmt::Debug,
+    E: fmt::Display + fmt::Debug,
+{
+}
+
+/// A [`Decoder`] and [`Encoder`] that feeds every byte of the frames of
+/// another decoder or encoder into a [`digest::Update`], such as any
+/// [`digest::Digest`].
+///
+/// This computes the hash of a stream while decoding it, without a second
+/// pass over its bytes.
+#[cfg(feature = "digest")]
+#[derive(Debug, Clone, Default)]
+pub struct Digesting<D, H> {
+    /// The inner decoder or encoder.
+    pub inner: D,
+    digest: H,
+}
+
+#[cfg(feature = "digest")]
+impl<D, H> Digesting<D, H> {
+    /// Create a new decoder feeding the frames of `inner` into `digest`.
+    #[inline]
+    #[must_use]
+    pub const fn new(inner: D, digest: H) -> Digesting<D, H> {
+        Digesting { inner, digest }
+    }
+
+    /// Returns a reference to the digest.
+    #[inline]
+    #[must_use]
+    pub const fn digest(&self) -> &H {
+        &self.digest
+    }
+
+    /// Returns a mutable reference to the digest, such as to finalize it.
+    #[inline]
+    #[must_use]
+    pub fn digest_mut(&mut self) -> &mut H {
+        &mut self.digest
+    }
+
+    /// Returns the inner decoder or encoder and the digest.
+    #[inline]
+    #[must_use]
+    pub fn into_parts(self) -> (D, H) {
+        (self.inner, self.digest)
+    }
+}
+
+#[cfg(feature = "digest")]
+impl<D: Decoder, H: digest::Update> Digesting<D, H> {
+    fn next<'s>(&mut self, src: &mut &'s [u8], eof: bool) -> DResult<'s, Self> {
+        let start = *src;
+        let item = if eof {
+            self.inner.decode_eof(src)
+        } else {
+            self.inner.decode(src)
+        }?;
+
+        self.digest.update(&start[..start.len() - src.len()]);
+
+        Ok(item)
+    }
+}
+
+#[cfg(feature = "digest")]
+impl<D: Decoder, H: digest::Update> Decoder for Digesting<D, H> {
+    type Item<'src> = D::Item<'src>;
+    type Error = D::Error;
+
+    #[inline]
+    fn hint(&self) -> Size {
+        self.inner.hint()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> SizeHint {
+        self.inner.size_hint()
+    }
+
+    #[inline]
+    fn decode<'s>(&mut self, src: &mut &'s [u8]) -> DResult<'s, Self> {
+        self.next(src, false)
+    }
+
+    #[inline]
+    fn decode_eof<'s>(&mut self, src: &mut &'s [u8]) -> DResult<'s, Self> {
+        self.next(src, true)
+    }
+}
+
+#[cfg(feature = "digest")]
+impl<D, H, Item> Encoder<Item> for Digesting<D, H>
+where
+    D: Encoder<Item>,
+    H: digest::Update,
+    Item: ?Sized,
+{
+    type Error = D::Error;
+
+    #[inline]
+    fn encode_hint(&self, item: &Item) -> Size {
+        self.inner.encode_hint(item)
+    }
+
+    #[inline]
+    fn encode(&mut self, item: &Item, dst: &mut &mut [u8]) -> EResult<Self::Error> {
+        let capacity = dst.len();
+        let mut rest = &mut **dst;
+
+        self.inner.encode(item, &mut rest)?;
+
+        let len = capacity - rest.len();
+        let (bytes, rest) = core::mem::take(dst).split_at_mut(len);
+
+        self.digest.update(bytes);
+
+        *dst = rest;
+
+        Ok(())
+    }
+}
+
+/// Trait for looking up the decoder for the body of a variant of a
+/// [`TaggedUnion`] by its tag.
+///
+/// Bodies are all decoded by the same type of decoder, so variants with
+/// different bodies are usually decoded with an enum of their decoders
+/// that implements [`Decoder`] by forwarding to the one it holds.
+///
+/// This is implemented for tables of tags and decoders, such as arrays and
+/// slices, and with the `alloc` feature for `BTreeMap`s. Closures creating
+/// the decoder for each tag can be used with [`FnLookup`].
+pub trait Lookup<Tag> {
+    /// The type of decoder for bodies.
+    type Decoder: Decoder;
+
+    /// Returns the decoder for the body of the variant with `tag`, if any.
+    fn lookup(&mut self, tag: &Tag) -> Option<&mut Self::Decoder>;
+}
+
+impl<Tag, L: Lookup<Tag> + ?Sized> Lookup<Tag> for &mut L {
+    type Decoder = L::Decoder;
+
+    #[inline]
+    fn lookup(&mut self, tag: &Tag) -> Option<&mut Self::Decoder> {
+        (**self).lookup(tag)
+    }
+}
+
+impl<Tag: PartialEq, B: Decoder> Lookup<Tag> for [(Tag, B)] {
+    type Decoder = B;
+
+    #[inline]
+    fn lookup(&mut self, tag: &Tag) -> Option<&mut B> {
+        self.iter_mut()
+            .find_map(|(key, body)| (key == tag).then_some(body))
+    }
+}
+
+impl<Tag: PartialEq, B: Decoder, const N: usize> Lookup<Tag> for [(Tag, B); N] {
+    type Decoder = B;
+
+    #[inline]
+    fn lookup(&mut self, tag: &Tag) -> Option<&mut B> {
+        self.as_mut_slice().lookup(tag)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<Tag: Ord, B: Decoder> Lookup<Tag> for alloc::collections::BTreeMap<Tag, B> {
+    type Decoder = B;
+
+    #[inline]
+    fn lookup(&mut self, tag: &Tag) -> Option<&mut B> {
+        self.get_mut(tag)
+    }
+}
+
+/// A [`Lookup`] that calls a closure to create the decoder for the body of
+/// each variant, which is kept until the next lookup.
+///
+/// Decoders are created again whenever a tag is decoded, including after
+/// an incomplete body, so state kept by a decoder between calls is lost.
+pub struct FnLookup<F, B> {
+    lookup: F,
+    decoder: Option<B>,
+}
+
+impl<F, B> FnLookup<F, B> {
+    /// Create a new [`Lookup`] calling `lookup` with each tag.
+    #[inline]
+    #[must_use]
+    pub const fn new(lookup: F) -> FnLookup<F, B> {
+        FnLookup {
+            lookup,
+            decoder: None,
+        }
+    }
+}
+
+impl<F: Clone, B: Clone> Clone for FnLookup<F, B> {
+    #[inline]
+    fn clone(&self) -> Self {
+        FnLookup {
+            lookup: self.lookup.clone(),
+            decoder: self.decoder.clone(),
+        }
+    }
+}
+
+impl<F, B: fmt::Debug> fmt::Debug for FnLookup<F, B> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FnLookup")
+            .field("decoder", &self.decoder)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<Tag, F, B> Lookup<Tag> for FnLookup<F, B>
+where
+    F: FnMut(&Tag) -> Option<B>,
+    B: Decoder,
+{
+    type Decoder = B;
+
+    #[inline]
+    fn lookup(&mut self, tag: &Tag) -> Option<&mut B> {
+        self.decoder = (self.lookup)(tag);
+        self.decoder.as_mut()
+    }
+}
+
+/// A [`Decoder`] for tagged unions, such as the messages of an RPC
+/// protocol, which decodes a tag and then the body of the variant with
+/// that tag.
+///
+/// The decoder for each body is found with a [`Lookup`], and the tag is
+/// decoded again if the body is incomplete.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct TaggedUnion<D, L> {
+    /// The decoder for tags.
+    pub tag: D,
+    /// The lookup for the decoders of bodies.
+    pub lookup: L,
+}
+
+impl<D, L> TaggedUnion<D, L> {
+    /// Create a new decoder for tagged unions with tags decoded by `tag`,
+    /// and bodies decoded by the decoders found with `lookup`.
+    #[inline]
+    #[must_use]
+    pub const fn new(tag: D, lookup: L) -> TaggedUnion<D, L> {
+        TaggedUnion { tag, lookup }
+    }
+}
+
+impl<D, L, Tag> TaggedUnion<D, L>
+where
+    D: for<'a> Decoder<Item<'a> = Tag>,
+    L: Lookup<Tag>,
+    Tag: fmt::Debug,
+{
+    fn next<'s>(&mut self, src: &mut &'s [u8], eof: bool) -> DResult<'s, Self> {
+        let mut rest = *src;
+        let tag = if eof {
+            self.tag.decode_eof(&mut rest)
+        } else {
+            self.tag.decode(&mut rest)
+        }
+        .map_err(|error| error.map(TaggedUnionError::Tag))?;
+
+        let Some(body) = self.lookup.lookup(&tag) else {
+            return Err(DError::Fatal {
+                error: TaggedUnionError::UnknownTag(tag),
+            });
+        };
+
+        let item = if eof {
+            body.decode_eof(&mut rest)
+        } else {
+            body.decode(&mut rest)
+        }
+        .map_err(|error| error.map(TaggedUnionError::Body))?;
+
+        *src = rest;
+
+        Ok(item)
+    }
+}
+
+impl<D, L, Tag> Decoder for TaggedUnion<D, L>
+where
+    D: for<'a> Decoder<Item<'a> = Tag>,
+    L: Lookup<Tag>,
+    Tag: fmt::Debug,
+{
+    type Item<'src> = <L::Decoder as Decoder>::Item<'src>;
+    type Error = TaggedUnionError<Tag, D::Error, <L::Decoder as Decoder>::Error>;
+
+    #[inline]
+    fn hint(&self) -> Size {
+        self.tag.hint()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> SizeHint {
+        SizeHint::at_least(self.tag.size_hint().min)
+    }
+
+    #[inline]
+    fn decode<'s>(&mut self, src: &mut &'s [u8]) -> DResult<'s, Self> {
+        self.next(src, false)
+    }
+
+    #[inline]
+    fn decode_eof<'s>(&mut self, src: &mut &'s [u8]) -> DResult<'s, Self> {
+        self.next(src, true)
+    }
+}
+
+/// Type for errors that may occur while decoding a tagged union.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum TaggedUnionError<Tag, T, E> {
+    /// The decoder for tags failed.
+    Tag(T),
+    /// No variant has the tag.
+    UnknownTag(Tag),
+    /// The decoder for the body failed.
+    Body(E),
+}
+
+impl<Tag, T, E> fmt::Display for TaggedUnionError<Tag, T, E>
+where
+    Tag: fmt::Debug,
+    T: fmt::Display,
+    E: fmt::Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Tag(error) => fmt::Display::fmt(error, f),
+            Self::UnknownTag(tag) => write!(f, "unknown tag: {tag:?}"),
+            Self::Body(error) => fmt::Display::fmt(error, f),
+        }
+    }
+}
+
+impl<Tag, T, E> core::error::Error for TaggedUnionError<Tag, T, E>
+where
+    Tag: fmt::Debug,
+    T: fmt::Display + fmt::Debug,
+    E: fmt::Display + fmt::Debug,
+{
+}
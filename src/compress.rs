@@ -0,0 +1,387 @@
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::decoder::{Decoder, Error as DError, Result as DResult};
+use crate::Size;
+
+/// DEFLATE, as in RFC 1951, and zlib, as in RFC 1950.
+#[cfg(feature = "deflate")]
+pub mod deflate;
+
+/// The LZ4 frame format.
+#[cfg(feature = "lz4")]
+pub mod lz4;
+
+/// Zstandard, as in RFC 8878.
+#[cfg(feature = "zstd")]
+pub mod zstd;
+
+/// Trait for decompressors that work incrementally over a stream.
+pub trait Decompressor {
+    type Error: fmt::Display + fmt::Debug;
+
+    /// Decompress bytes from `src` into `dst`.
+    ///
+    /// Making no progress is not an error, as more input or room may be
+    /// needed to go on.
+    fn decompress(&mut self, src: &[u8], dst: &mut [u8]) -> Result<Progress, Self::Error>;
+}
+
+/// The progress of a call to [`Decompressor::decompress`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Progress {
+    /// The amount of bytes read from the source.
+    pub read: usize,
+    /// The amount of bytes written to the destination.
+    pub written: usize,
+    /// Whether the compressed stream has ended, and every byte of it has
+    /// been written.
+    pub done: bool,
+}
+
+/// A [`Decoder`] for frames of another decoder within a compressed stream,
+/// which is decompressed on the fly.
+///
+/// Frames are decoded from a buffer of decompressed bytes, so the inner
+/// decoder must yield items that do not borrow from its source, such as
+/// with [`Map`](crate::combinator::Map). The buffer grows to fit a single
+/// frame, up to [`max_len`](Decompressed::max_len) bytes.
+///
+/// Bytes of the source are handed to the decompressor as they arrive, but
+/// are only consumed once a frame is yielded, so the source must not be
+/// advanced after an incomplete read, as usual. Once the compressed stream
+/// ends, the inner decoder reaches the end of its stream, and any bytes
+/// after the compressed stream are left in the source.
+#[derive(Debug, Clone)]
+pub struct Decompressed<D, Z> {
+    /// The inner decoder.
+    pub inner: D,
+    /// The decompressor.
+    pub decompressor: Z,
+    /// The maximum amount of decompressed bytes buffered for a frame.
+    ///
+    /// This is 8 MiB by default.
+    pub max_len: usize,
+    buf: Vec<u8>,
+    start: usize,
+    /// The amount of bytes of the source already decompressed.
+    fed: usize,
+    done: bool,
+}
+
+impl<D, Z> Decompressed<D, Z> {
+    /// The amount of bytes the buffer grows by at least.
+    const CHUNK: usize = 8 * 1024;
+
+    /// Create a new decoder for frames of `inner` within a stream
+    /// compressed for `decompressor`.
+    #[inline]
+    #[must_use]
+    pub const fn new(inner: D, decompressor: Z) -> Decompressed<D, Z> {
+        Decompressed {
+            inner,
+            decompressor,
+            max_len: 8 * 1024 * 1024,
+            buf: Vec::new(),
+            start: 0,
+            fed: 0,
+            done: false,
+        }
+    }
+
+    /// Set the maximum amount of decompressed bytes buffered for a frame.
+    #[inline]
+    #[must_use]
+    pub const fn with_max_len(mut self, max_len: usize) -> Decompressed<D, Z> {
+        self.max_len = max_len;
+        self
+    }
+
+    /// Returns whether the compressed stream has ended.
+    #[inline]
+    #[must_use]
+    pub const fn is_done(&self) -> bool {
+        self.done
+    }
+}
+
+impl<D, Z, T> Decompressed<D, Z>
+where
+    D: for<'a> Decoder<Item<'a> = T>,
+    Z: Decompressor,
+{
+    fn next<'s>(&mut self, src: &mut &'s [u8], eof: bool) -> DResult<'s, Self> {
+        let fatal = |error| DError::Fatal { error };
+
+        loop {
+            let mut bytes = &self.buf[self.start..];
+            let len = bytes.len();
+
+            let result = if self.done {
+                self.inner.decode_eof(&mut bytes)
+            } else {
+                self.inner.decode(&mut bytes)
+            };
+
+            let needed = match result {
+                Ok(item) => {
+                    self.start += len - bytes.len();
+                    *src = &src[self.fed..];
+                    self.fed = 0;
+
+                    return Ok(item);
+                }
+                Err(DError::Fatal { error }) => {
+                    return Err(fatal(DecompressedError::Decoder(error)))
+                }
+                Err(error @ (DError::Eof | DError::DataRemains)) => {
+                    *src = &src[self.fed..];
+                    self.fed = 0;
+
+                    return Err(match error {
+                        DError::Eof if eof && !src.is_empty() => DError::DataRemains,
+                        error => error.map(DecompressedError::Decoder),
+                    });
+                }
+                Err(DError::Incomplete { .. }) if self.done => {
+                    return Err(fatal(DecompressedError::Truncated))
+                }
+                Err(DError::Incomplete { needed }) => needed,
+            };
+
+            self.buf.drain(..self.start);
+            self.start = 0;
+
+            let len = self.buf.len();
+
+            if len >= self.max_len {
+                return Err(fatal(DecompressedError::TooLong));
+            }
+
+            let grow = needed.get().map_or(0, |needed| needed.get());
+            let capacity = (len + grow.max(Self::CHUNK)).min(self.max_len);
+
+            self.buf.resize(capacity, 0);
+
+            let progress = self
+                .decompressor
+                .decompress(&src[self.fed..], &mut self.buf[len..]);
+
+            let progress = match progress {
+                Ok(progress) => progress,
+                Err(error) => {
+                    self.buf.truncate(len);
+
+                    return Err(fatal(DecompressedError::Decompressor(error)));
+                }
+            };
+
+            self.buf.truncate(len + progress.written);
+            self.fed += progress.read;
+            self.done = progress.done;
+
+            if progress.read == 0 && progress.written == 0 && !progress.done {
+                return Err(if eof {
+                    fatal(DecompressedError::Truncated)
+                } else {
+                    DError::Incomplete {
+                        needed: Size::new(1),
+                    }
+                });
+            }
+        }
+    }
+}
+
+impl<D, Z, T> Decoder for Decompressed<D, Z>
+where
+    D: for<'a> Decoder<Item<'a> = T>,
+    Z: Decompressor,
+{
+    type Item<'src> = T;
+    type Error = DecompressedError<D::Error, Z::Error>;
+
+    #[inline]
+    fn decode<'s>(&mut self, src: &mut &'s [u8]) -> DResult<'s, Self> {
+        self.next(src, false)
+    }
+
+    #[inline]
+    fn decode_eof<'s>(&mut self, src: &mut &'s [u8]) -> DResult<'s, Self> {
+        self.next(src, true)
+    }
+}
+
+/// Type for errors that may occur while decoding frames within a
+/// compressed stream.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum DecompressedError<E, Z> {
+    /// The inner decoder failed.
+    Decoder(E),
+    /// The decompressor failed.
+    Decompressor(Z),
+    /// A frame decompresses to too many bytes.
+    TooLong,
+    /// The compressed stream ends in the middle of a frame, or is cut
+    /// short by the end of the source.
+    Truncated,
+}
+
+impl<E: fmt::Display, Z: fmt::Display> fmt::Display for DecompressedError<E, Z> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Decoder(error) => fmt::Display::fmt(error, f),
+            Self::Decompressor(error) => fmt::Display::fmt(error, f),
+            Self::TooLong => f.write_str("decompressed frame too long"),
+            Self::Truncated => f.write_str("truncated compressed stream"),
+        }
+    }
+}
+
+impl<E, Z> core::error::Error for DecompressedError<E, Z>
+where
+    E: fmt::Display + fmt::Debug,
+    Z: fmt::Display + fmt::Debug,
+{
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use alloc::vec;
+
+    use super::*;
+    use crate::combinator::Map;
+    use crate::framing::{Lines, LinesError, Netstring, NetstringError};
+
+    /// A decompressor for a stream of `len` bytes stored as is.
+    struct Stored {
+        len: usize,
+    }
+
+    impl Decompressor for Stored {
+        type Error = core::convert::Infallible;
+
+        fn decompress(&mut self, src: &[u8], dst: &mut [u8]) -> Result<Progress, Self::Error> {
+            let len = src.len().min(dst.len()).min(self.len);
+
+            dst[..len].copy_from_slice(&src[..len]);
+            self.len -= len;
+
+            Ok(Progress {
+                read: len,
+                written: len,
+                done: self.len == 0,
+            })
+        }
+    }
+
+    type LinesResult<Z> =
+        Result<Vec<Vec<u8>>, DError<DecompressedError<LinesError, <Z as Decompressor>::Error>>>;
+
+    /// Decode the lines of a compressed stream, revealing `step` more bytes
+    /// of it after each incomplete read.
+    pub(crate) fn lines<Z: Decompressor>(
+        decompressor: Z,
+        stream: &[u8],
+        step: usize,
+    ) -> LinesResult<Z> {
+        let mut decoder = Decompressed::new(
+            Map::new(Lines::new(), |line: &[u8]| line.to_vec()),
+            decompressor,
+        );
+        let mut lines = Vec::new();
+        let mut start = 0;
+        let mut end = 0;
+
+        loop {
+            let mut src = &stream[start..end];
+
+            let result = if end == stream.len() {
+                decoder.decode_eof(&mut src)
+            } else {
+                decoder.decode(&mut src)
+            };
+
+            match result {
+                Ok(line) => {
+                    lines.push(line);
+                    start = end - src.len();
+                }
+                Err(DError::Incomplete { .. }) => end = (end + step).min(stream.len()),
+                Err(DError::Eof) => return Ok(lines),
+                Err(error) => return Err(error),
+            }
+        }
+    }
+
+    #[test]
+    fn incremental() {
+        let stream = b"a\nbb\nccc";
+
+        for step in [1, 3, 64] {
+            let lines = lines(Stored { len: stream.len() }, stream, step);
+
+            assert_eq!(
+                lines,
+                Ok(vec![b"a".to_vec(), b"bb".to_vec(), b"ccc".to_vec()])
+            );
+        }
+    }
+
+    #[test]
+    fn after_the_stream() {
+        let mut decoder = Decompressed::new(
+            Map::new(Lines::new(), |line: &[u8]| line.to_vec()),
+            Stored { len: 2 },
+        );
+        let mut src = &b"a\nxyz"[..];
+
+        assert_eq!(decoder.decode(&mut src), Ok(b"a".to_vec()));
+        assert_eq!(src, b"xyz");
+        assert!(decoder.is_done());
+        assert_eq!(decoder.decode(&mut src), Err(DError::Eof));
+        assert_eq!(src, b"xyz");
+        assert_eq!(decoder.decode_eof(&mut src), Err(DError::DataRemains));
+    }
+
+    #[test]
+    fn truncated() {
+        assert_eq!(
+            lines(Stored { len: 10 }, b"a\nb", 1),
+            Err(DError::Fatal {
+                error: DecompressedError::Truncated
+            })
+        );
+
+        // The stream ends in the middle of a frame, which is left over as at
+        // the end of any other stream.
+        let mut decoder = Decompressed::new(
+            Map::new(Netstring::new(), |payload: &[u8]| payload.to_vec()),
+            Stored { len: 4 },
+        );
+
+        assert_eq!(
+            decoder.decode(&mut &b"5:ab"[..]),
+            Err(DError::<DecompressedError<NetstringError, _>>::DataRemains)
+        );
+    }
+
+    #[test]
+    fn too_long() {
+        let stream = b"abcdefgh\n";
+        let mut decoder = Decompressed::new(
+            Map::new(Lines::new(), |line: &[u8]| line.to_vec()),
+            Stored { len: stream.len() },
+        )
+        .with_max_len(4);
+
+        assert_eq!(
+            decoder.decode(&mut &stream[..]),
+            Err(DError::Fatal {
+                error: DecompressedError::TooLong
+            })
+        );
+    }
+}
@@ -0,0 +1,161 @@
+use alloc::boxed::Box;
+use core::fmt;
+
+use miniz_oxide::inflate::stream::{inflate, InflateState};
+use miniz_oxide::{DataFormat, MZError, MZFlush, MZStatus};
+
+use super::{Decompressor, Progress};
+
+/// The format of a DEFLATE stream.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Format {
+    /// Raw DEFLATE, without a header.
+    Raw,
+    /// DEFLATE wrapped in zlib's header and Adler-32 trailer, which is
+    /// verified.
+    #[default]
+    Zlib,
+}
+
+/// A [`Decompressor`] for DEFLATE streams.
+#[derive(Clone)]
+pub struct Inflate {
+    format: Format,
+    state: Box<InflateState>,
+}
+
+impl Inflate {
+    /// Create a new decompressor for a DEFLATE stream in `format`.
+    #[inline]
+    #[must_use]
+    pub fn new(format: Format) -> Inflate {
+        let data_format = match format {
+            Format::Raw => DataFormat::Raw,
+            Format::Zlib => DataFormat::Zlib,
+        };
+
+        Inflate {
+            format,
+            state: InflateState::new_boxed(data_format),
+        }
+    }
+
+    /// Returns the format of the stream.
+    #[inline]
+    #[must_use]
+    pub const fn format(&self) -> Format {
+        self.format
+    }
+}
+
+impl Default for Inflate {
+    #[inline]
+    fn default() -> Self {
+        Inflate::new(Format::default())
+    }
+}
+
+impl fmt::Debug for Inflate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Inflate")
+            .field("format", &self.format)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Decompressor for Inflate {
+    type Error = InflateError;
+
+    fn decompress(&mut self, src: &[u8], dst: &mut [u8]) -> Result<Progress, InflateError> {
+        let result = inflate(&mut self.state, src, dst, MZFlush::None);
+
+        let done = match result.status {
+            Ok(status) => status == MZStatus::StreamEnd,
+            // No progress could be made, which is not an error here.
+            Err(MZError::Buf) => false,
+            Err(_) => return Err(InflateError(())),
+        };
+
+        Ok(Progress {
+            read: result.bytes_consumed,
+            written: result.bytes_written,
+            done,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct InflateError(());
+
+impl fmt::Display for InflateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("invalid deflate stream")
+    }
+}
+
+impl core::error::Error for InflateError {}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+
+    use miniz_oxide::deflate::{compress_to_vec, compress_to_vec_zlib};
+
+    use super::*;
+    use crate::compress::tests::lines;
+    use crate::compress::DecompressedError;
+    use crate::decoder::Error as DError;
+
+    const TEXT: &[u8] = b"one\ntwo\nthree\nthree\nthree\n";
+
+    #[test]
+    fn round_trip() {
+        let expected = Ok(vec![
+            b"one".to_vec(),
+            b"two".to_vec(),
+            b"three".to_vec(),
+            b"three".to_vec(),
+            b"three".to_vec(),
+        ]);
+
+        for step in [1, 7, 1024] {
+            let zlib = compress_to_vec_zlib(TEXT, 6);
+            let raw = compress_to_vec(TEXT, 6);
+
+            assert_eq!(lines(Inflate::new(Format::Zlib), &zlib, step), expected);
+            assert_eq!(lines(Inflate::new(Format::Raw), &raw, step), expected);
+        }
+    }
+
+    #[test]
+    fn malformed() {
+        let mut zlib = compress_to_vec_zlib(TEXT, 6);
+        let last = zlib.len() - 1;
+
+        // The Adler-32 trailer does not match.
+        zlib[last] ^= 1;
+
+        assert_eq!(
+            lines(Inflate::default(), &zlib, 1024),
+            Err(DError::Fatal {
+                error: DecompressedError::Decompressor(InflateError(()))
+            })
+        );
+        assert_eq!(
+            Inflate::default().decompress(&[0xff; 8], &mut [0; 64]),
+            Err(InflateError(()))
+        );
+
+        let mut zlib = compress_to_vec_zlib(TEXT, 6);
+
+        zlib.extend_from_slice(b"extra");
+
+        assert_eq!(
+            lines(Inflate::default(), &zlib, 1024),
+            Err(DError::DataRemains)
+        );
+    }
+}
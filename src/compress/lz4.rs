@@ -0,0 +1,441 @@
+use alloc::vec::Vec;
+use core::fmt;
+
+use lz4_flex::block::decompress_into_with_dict;
+use xxhash_rust::xxh32::{xxh32, Xxh32};
+
+use super::{Decompressor, Progress};
+
+/// The magic number starting a frame.
+const MAGIC: u32 = 0x184d_2204;
+
+/// The magic numbers starting a skippable frame, ignoring the lowest 4 bits.
+const SKIPPABLE: u32 = 0x184d_2a50;
+
+/// The amount of earlier bytes that linked blocks may refer to.
+const WINDOW: usize = 64 * 1024;
+
+/// The state of an [`Lz4Frame`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum State {
+    /// Reading the header of a frame.
+    Header,
+    /// Skipping the rest of a skippable frame.
+    Skip(u32),
+    /// Reading blocks.
+    Blocks,
+    /// Reading the checksum of the content.
+    Checksum,
+    /// The frame has ended.
+    Done,
+}
+
+/// A [`Decompressor`] for a frame in the LZ4 frame format.
+///
+/// Skippable frames before the frame are skipped. Every checksum in the
+/// frame is verified, as is the size of the content, if present. Frames
+/// requiring an external dictionary are not supported.
+#[derive(Clone)]
+pub struct Lz4Frame {
+    state: State,
+    independent: bool,
+    block_checksum: bool,
+    content_checksum: bool,
+    block_max: usize,
+    content_size: Option<u64>,
+    /// The amount of bytes decompressed so far.
+    total: u64,
+    checksum: Xxh32,
+    /// The window of linked blocks, followed by the output not yet written.
+    out: Vec<u8>,
+    /// The start of the output not yet written.
+    pos: usize,
+}
+
+impl Lz4Frame {
+    /// Create a new decompressor for an LZ4 frame.
+    #[inline]
+    #[must_use]
+    pub const fn new() -> Lz4Frame {
+        Lz4Frame {
+            state: State::Header,
+            independent: true,
+            block_checksum: false,
+            content_checksum: false,
+            block_max: 0,
+            content_size: None,
+            total: 0,
+            checksum: Xxh32::new(0),
+            out: Vec::new(),
+            pos: 0,
+        }
+    }
+
+    /// Read the header of a frame, returning its length if complete.
+    fn header(&mut self, src: &[u8]) -> Result<Option<usize>, Lz4Error> {
+        let Some(magic) = src
+            .first_chunk::<4>()
+            .map(|magic| u32::from_le_bytes(*magic))
+        else {
+            return Ok(None);
+        };
+
+        if magic & !0xf == SKIPPABLE {
+            let Some(size) = src.get(4..8) else {
+                return Ok(None);
+            };
+
+            self.state = State::Skip(u32::from_le_bytes([size[0], size[1], size[2], size[3]]));
+
+            return Ok(Some(8));
+        }
+
+        if magic != MAGIC {
+            return Err(Lz4Error::InvalidHeader);
+        }
+
+        let (Some(&flags), Some(&block)) = (src.get(4), src.get(5)) else {
+            return Ok(None);
+        };
+
+        // The version must be 1, and reserved bits and dictionaries are
+        // rejected.
+        if flags & 0b1100_0011 != 0b0100_0000 || block & 0b1000_1111 != 0 {
+            return Err(Lz4Error::InvalidHeader);
+        }
+
+        let has_size = flags & 0b1000 != 0;
+        let len = 7 + if has_size { 8 } else { 0 };
+
+        let Some(header) = src.get(..len) else {
+            return Ok(None);
+        };
+
+        if (xxh32(&header[4..len - 1], 0) >> 8) as u8 != header[len - 1] {
+            return Err(Lz4Error::Checksum);
+        }
+
+        self.block_max = match block >> 4 {
+            4 => 64 * 1024,
+            5 => 256 * 1024,
+            6 => 1024 * 1024,
+            7 => 4 * 1024 * 1024,
+            _ => return Err(Lz4Error::InvalidHeader),
+        };
+
+        self.independent = flags & 0b10_0000 != 0;
+        self.block_checksum = flags & 0b1_0000 != 0;
+        self.content_checksum = flags & 0b100 != 0;
+        self.content_size = has_size
+            .then(|| u64::from_le_bytes(header[6..14].try_into().expect("size is 8 bytes")));
+        self.state = State::Blocks;
+
+        Ok(Some(len))
+    }
+
+    /// Read a block, returning its length if complete.
+    fn block(&mut self, src: &[u8]) -> Result<Option<usize>, Lz4Error> {
+        let Some(&size) = src.first_chunk::<4>() else {
+            return Ok(None);
+        };
+
+        let size = u32::from_le_bytes(size);
+
+        if size == 0 {
+            if self.content_size.is_some_and(|size| size != self.total) {
+                return Err(Lz4Error::ContentSize);
+            }
+
+            self.state = if self.content_checksum {
+                State::Checksum
+            } else {
+                State::Done
+            };
+
+            return Ok(Some(4));
+        }
+
+        let compressed = size & (1 << 31) == 0;
+        let size = (size & !(1 << 31)) as usize;
+
+        if size > self.block_max {
+            return Err(Lz4Error::InvalidBlock);
+        }
+
+        let len = 4 + size + if self.block_checksum { 4 } else { 0 };
+
+        let Some(block) = src.get(4..len) else {
+            return Ok(None);
+        };
+
+        let (data, checksum) = block.split_at(size);
+
+        if let Some(checksum) = checksum.first_chunk::<4>() {
+            if xxh32(data, 0) != u32::from_le_bytes(*checksum) {
+                return Err(Lz4Error::Checksum);
+            }
+        }
+
+        // Every byte before the block has been written by now, so only the
+        // window needs to be kept.
+        let keep = if self.independent {
+            0
+        } else {
+            self.out.len().min(WINDOW)
+        };
+
+        self.out.drain(..self.out.len() - keep);
+
+        if compressed {
+            self.out.resize(keep + self.block_max, 0);
+
+            let (window, buf) = self.out.split_at_mut(keep);
+            let written =
+                decompress_into_with_dict(data, buf, window).map_err(|_| Lz4Error::InvalidBlock)?;
+
+            self.out.truncate(keep + written);
+        } else {
+            self.out.extend_from_slice(data);
+        }
+
+        self.pos = keep;
+        self.total += (self.out.len() - keep) as u64;
+        self.checksum.update(&self.out[keep..]);
+
+        Ok(Some(len))
+    }
+}
+
+impl Default for Lz4Frame {
+    #[inline]
+    fn default() -> Self {
+        Lz4Frame::new()
+    }
+}
+
+impl fmt::Debug for Lz4Frame {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Lz4Frame")
+            .field("state", &self.state)
+            .field("total", &self.total)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Decompressor for Lz4Frame {
+    type Error = Lz4Error;
+
+    fn decompress(&mut self, src: &[u8], dst: &mut [u8]) -> Result<Progress, Lz4Error> {
+        let mut read = 0;
+        let mut written = 0;
+
+        loop {
+            let pending = &self.out[self.pos..];
+            let len = pending.len().min(dst.len() - written);
+
+            dst[written..written + len].copy_from_slice(&pending[..len]);
+            written += len;
+            self.pos += len;
+
+            if self.pos < self.out.len() {
+                break;
+            }
+
+            let src = &src[read..];
+
+            let len = match self.state {
+                State::Header => self.header(src)?,
+                State::Skip(size) if src.is_empty() && size > 0 => None,
+                State::Skip(size) => {
+                    let len = src.len().min(size as usize);
+
+                    self.state = match size as usize - len {
+                        0 => State::Header,
+                        rest => State::Skip(rest as u32),
+                    };
+
+                    Some(len)
+                }
+                State::Blocks => self.block(src)?,
+                State::Checksum => match src.first_chunk::<4>() {
+                    Some(&checksum) if u32::from_le_bytes(checksum) == self.checksum.digest() => {
+                        self.state = State::Done;
+
+                        Some(4)
+                    }
+                    Some(_) => return Err(Lz4Error::Checksum),
+                    None => None,
+                },
+                State::Done => None,
+            };
+
+            match len {
+                Some(len) => read += len,
+                None => break,
+            }
+        }
+
+        Ok(Progress {
+            read,
+            written,
+            done: self.state == State::Done && self.pos == self.out.len(),
+        })
+    }
+}
+
+/// Type for errors that may occur while decompressing an LZ4 frame.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Lz4Error {
+    /// The header is invalid or unsupported.
+    InvalidHeader,
+    /// A checksum does not match.
+    Checksum,
+    /// A block is too long or is corrupt.
+    InvalidBlock,
+    /// The size of the content does not match the header.
+    ContentSize,
+}
+
+impl fmt::Display for Lz4Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidHeader => f.write_str("invalid lz4 frame header"),
+            Self::Checksum => f.write_str("lz4 checksum mismatch"),
+            Self::InvalidBlock => f.write_str("invalid lz4 block"),
+            Self::ContentSize => f.write_str("lz4 content size mismatch"),
+        }
+    }
+}
+
+impl core::error::Error for Lz4Error {}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+
+    use lz4_flex::block::{compress_into, get_maximum_output_size};
+
+    use super::*;
+    use crate::compress::tests::lines;
+
+    /// Flags for version 1 with independent blocks.
+    const INDEPENDENT: u8 = 0b0110_0000;
+
+    fn header(flags: u8, content_size: Option<u64>) -> Vec<u8> {
+        let mut header = MAGIC.to_le_bytes().to_vec();
+        let flags = flags | if content_size.is_some() { 0b1000 } else { 0 };
+
+        header.extend_from_slice(&[flags, 0x40]);
+
+        if let Some(size) = content_size {
+            header.extend_from_slice(&size.to_le_bytes());
+        }
+
+        header.push((xxh32(&header[4..], 0) >> 8) as u8);
+        header
+    }
+
+    fn block(data: &[u8], compressed: bool) -> Vec<u8> {
+        let size = data.len() as u32 | if compressed { 0 } else { 1 << 31 };
+
+        [&size.to_le_bytes()[..], data].concat()
+    }
+
+    fn compress(data: &[u8]) -> Vec<u8> {
+        let mut out = vec![0; get_maximum_output_size(data.len())];
+        let len = compress_into(data, &mut out).unwrap();
+
+        out.truncate(len);
+        out
+    }
+
+    fn decompress(frame: &[u8]) -> Result<Vec<u8>, Lz4Error> {
+        let mut lz4 = Lz4Frame::new();
+        let mut out = vec![0; 1024];
+        let progress = lz4.decompress(frame, &mut out)?;
+
+        assert!(progress.done);
+        assert_eq!(progress.read, frame.len());
+
+        out.truncate(progress.written);
+
+        Ok(out)
+    }
+
+    #[test]
+    fn frames() {
+        let text = b"ab\ncd\nab\ncd\nab\ncd\n";
+        let frame = [
+            header(INDEPENDENT | 0b100, Some(text.len() as u64)),
+            block(&compress(text), true),
+            vec![0; 4],
+            xxh32(text, 0).to_le_bytes().to_vec(),
+        ]
+        .concat();
+
+        for step in [1, 5, 1024] {
+            let lines = lines(Lz4Frame::new(), &frame, step).unwrap();
+
+            assert_eq!(lines.len(), 6);
+            assert_eq!(lines[4], b"ab");
+        }
+
+        // Skippable frames before the frame are skipped.
+        let skippable = [&[0x5a, 0x2a, 0x4d, 0x18, 3, 0, 0, 0][..], b"xyz", &frame].concat();
+
+        assert_eq!(decompress(&skippable).as_deref(), Ok(&text[..]));
+    }
+
+    #[test]
+    fn linked_blocks() {
+        // The second block copies the first, then adds a literal.
+        let second = [0x02, 0x03, 0x00, 0x10, b'c'];
+        let blocks = [block(b"ab\n", false), block(&second, true), vec![0; 4]].concat();
+
+        let frame = [header(0b0100_0000, None), blocks.clone()].concat();
+
+        assert_eq!(decompress(&frame).as_deref(), Ok(&b"ab\nab\nab\nc"[..]));
+
+        let frame = [header(INDEPENDENT, None), blocks].concat();
+
+        assert_eq!(decompress(&frame), Err(Lz4Error::InvalidBlock));
+    }
+
+    #[test]
+    fn malformed() {
+        let text = b"hello\n";
+        let end = [block(text, false), vec![0; 4]].concat();
+
+        let mut frame = [header(INDEPENDENT, None), end.clone()].concat();
+
+        frame[0] ^= 1;
+        assert_eq!(decompress(&frame), Err(Lz4Error::InvalidHeader));
+
+        let mut frame = [header(INDEPENDENT, None), end.clone()].concat();
+
+        frame[6] ^= 1;
+        assert_eq!(decompress(&frame), Err(Lz4Error::Checksum));
+
+        let frame = [header(INDEPENDENT | 0b1, None), end.clone()].concat();
+
+        assert_eq!(decompress(&frame), Err(Lz4Error::InvalidHeader));
+
+        let frame = [header(INDEPENDENT, Some(5)), end.clone()].concat();
+
+        assert_eq!(decompress(&frame), Err(Lz4Error::ContentSize));
+
+        let frame = [header(INDEPENDENT | 0b100, None), end, vec![0; 4]].concat();
+
+        assert_eq!(decompress(&frame), Err(Lz4Error::Checksum));
+
+        let frame = [
+            header(INDEPENDENT | 0b1_0000, None),
+            block(text, false),
+            vec![0; 8],
+        ]
+        .concat();
+
+        assert_eq!(decompress(&frame), Err(Lz4Error::Checksum));
+    }
+}
@@ -0,0 +1,164 @@
+use core::fmt;
+use std::io;
+
+use zstd::stream::raw::{Decoder, Operation};
+
+use super::{Decompressor, Progress};
+
+/// The magic numbers starting a skippable frame, ignoring the lowest 4 bits.
+const SKIPPABLE: u32 = 0x184d_2a50;
+
+/// A [`Decompressor`] for a zstd frame.
+///
+/// Skippable frames before the frame are skipped.
+pub struct Zstd {
+    decoder: Decoder<'static>,
+    /// Whether the next byte starts a frame.
+    start: bool,
+    /// Whether the current frame is skippable.
+    skippable: bool,
+    done: bool,
+}
+
+impl Zstd {
+    /// Create a new decompressor for a zstd frame.
+    ///
+    /// This fails if the decompression context cannot be allocated.
+    #[inline]
+    pub fn new() -> io::Result<Zstd> {
+        Ok(Zstd {
+            decoder: Decoder::new()?,
+            start: true,
+            skippable: false,
+            done: false,
+        })
+    }
+
+    /// Create a new decompressor for a zstd frame compressed with
+    /// `dictionary`.
+    ///
+    /// This fails if the decompression context cannot be allocated, or if
+    /// the dictionary is invalid.
+    #[inline]
+    pub fn with_dictionary(dictionary: &[u8]) -> io::Result<Zstd> {
+        Ok(Zstd {
+            decoder: Decoder::with_dictionary(dictionary)?,
+            start: true,
+            skippable: false,
+            done: false,
+        })
+    }
+}
+
+impl fmt::Debug for Zstd {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Zstd")
+            .field("done", &self.done)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Decompressor for Zstd {
+    type Error = ZstdError;
+
+    fn decompress(&mut self, src: &[u8], dst: &mut [u8]) -> Result<Progress, ZstdError> {
+        if self.done {
+            return Ok(Progress {
+                done: true,
+                ..Progress::default()
+            });
+        }
+
+        if self.start {
+            let Some(magic) = src.first_chunk::<4>() else {
+                return Ok(Progress::default());
+            };
+
+            self.start = false;
+            self.skippable = u32::from_le_bytes(*magic) & !0xf == SKIPPABLE;
+        }
+
+        let status = self
+            .decoder
+            .run_on_buffers(src, dst)
+            .map_err(|_| ZstdError(()))?;
+
+        if status.remaining == 0 {
+            self.start = self.skippable;
+            self.done = !self.skippable;
+        }
+
+        Ok(Progress {
+            read: status.bytes_read,
+            written: status.bytes_written,
+            done: self.done,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct ZstdError(());
+
+impl fmt::Display for ZstdError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("invalid zstd stream")
+    }
+}
+
+impl core::error::Error for ZstdError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compress::tests::lines;
+    use crate::compress::DecompressedError;
+    use crate::decoder::Error as DError;
+
+    const TEXT: &[u8] = b"one\ntwo\nthree\nthree\nthree\n";
+
+    #[test]
+    fn round_trip() {
+        let frame = zstd::encode_all(TEXT, 3).unwrap();
+
+        for step in [1, 7, 1024] {
+            let lines = lines(Zstd::new().unwrap(), &frame, step).unwrap();
+
+            assert_eq!(lines.len(), 5);
+            assert_eq!(lines[2], b"three");
+        }
+
+        // Skippable frames before the frame are skipped.
+        let skippable = [&[0x5a, 0x2a, 0x4d, 0x18, 3, 0, 0, 0][..], b"xyz", &frame].concat();
+
+        assert_eq!(lines(Zstd::new().unwrap(), &skippable, 3).unwrap().len(), 5);
+    }
+
+    #[test]
+    fn malformed() {
+        assert_eq!(
+            lines(Zstd::new().unwrap(), &[0xff; 16], 4),
+            Err(DError::Fatal {
+                error: DecompressedError::Decompressor(ZstdError(()))
+            })
+        );
+
+        let mut frame = zstd::encode_all(TEXT, 3).unwrap();
+
+        frame.extend_from_slice(b"extra");
+
+        assert_eq!(
+            lines(Zstd::new().unwrap(), &frame, 1024),
+            Err(DError::DataRemains)
+        );
+
+        let frame = zstd::encode_all(TEXT, 3).unwrap();
+
+        assert_eq!(
+            lines(Zstd::new().unwrap(), &frame[..frame.len() - 2], 1024),
+            Err(DError::Fatal {
+                error: DecompressedError::Truncated
+            })
+        );
+    }
+}
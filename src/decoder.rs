@@ -1,6 +1,6 @@
 use core::{convert::Infallible, fmt, write};
 
-use crate::Size;
+use crate::{Size, SizeHint};
 
 #[allow(type_alias_bounds)]
 pub type Result<'s, D: Decoder> = ::core::result::Result<D::Item<'s>, Error<D::Error>>;
@@ -19,6 +19,15 @@ pub trait Decoder {
         Size::Unknown
     }
 
+    /// Get the range of the amount of bytes required to read the next frame.
+    ///
+    /// The lower bound should match [`Decoder::hint`], which is what it
+    /// defaults to, without an upper bound.
+    #[inline]
+    fn size_hint(&self) -> SizeHint {
+        SizeHint::at_least(self.hint())
+    }
+
     /// Try to decode a single frame from a byte stream.
     fn decode<'s>(&mut self, src: &mut &'s [u8]) -> Result<'s, Self>;
 
@@ -34,8 +43,56 @@ pub trait Decoder {
     }
 }
 
+/// Extension trait for decoders that cannot fail fatally, such as
+/// the integer decoders.
+pub trait InfallibleDecoderExt: Decoder<Error = Infallible> {
+    /// Try to decode a single frame from a byte stream, where the only
+    /// possible error is incomplete input.
+    ///
+    /// Decoders only report the end of a stream from [`Decoder::decode_eof`],
+    /// so any other error is treated as incomplete input of unknown size.
+    #[inline]
+    fn decode_infallible<'s>(
+        &mut self,
+        src: &mut &'s [u8],
+    ) -> core::result::Result<Self::Item<'s>, Incomplete> {
+        self.decode(src).map_err(|error| match error {
+            Error::Incomplete { needed } => Incomplete { needed },
+            _ => Incomplete::new(Size::Unknown),
+        })
+    }
+}
+
+impl<D: Decoder<Error = Infallible> + ?Sized> InfallibleDecoderExt for D {}
+
+impl<D: Decoder + ?Sized> Decoder for &mut D {
+    type Item<'src> = D::Item<'src>;
+    type Error = D::Error;
+
+    #[inline]
+    fn hint(&self) -> Size {
+        (**self).hint()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> SizeHint {
+        (**self).size_hint()
+    }
+
+    #[inline]
+    fn decode<'s>(&mut self, src: &mut &'s [u8]) -> Result<'s, Self> {
+        (**self).decode(src)
+    }
+
+    #[inline]
+    fn decode_eof<'s>(&mut self, src: &mut &'s [u8]) -> Result<'s, Self> {
+        (**self).decode_eof(src)
+    }
+}
+
 /// Type for errors that may occur while decoding a frame.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum Error<E> {
     /// We've reached the end of a stream.
     ///
@@ -50,6 +107,9 @@ pub enum Error<E> {
     Incomplete {
         /// The minimum amount of bytes required for
         /// reading the next frame.
+        ///
+        /// At least one more byte is always needed, so this is never
+        /// [`Size::Zero`]; use [`Size::Unknown`] when the amount is not known.
         needed: Size,
     },
     /// A fatal error has occurred while reading the
@@ -86,6 +146,16 @@ impl<E> Error<E> {
         }
     }
 
+    /// Returns the amount of bytes needed, if the frame is incomplete.
+    #[inline]
+    #[must_use]
+    pub const fn incomplete(&self) -> Option<Incomplete> {
+        match self {
+            Self::Incomplete { needed } => Some(Incomplete::new(*needed)),
+            _ => None,
+        }
+    }
+
     #[inline]
     #[must_use]
     pub const fn from_infallible(error: Error<Infallible>) -> Error<E> {
@@ -104,7 +174,7 @@ impl<E: fmt::Display> fmt::Display for Error<E> {
         match self {
             Self::Eof | Self::DataRemains => Ok(()),
             Self::Incomplete {
-                needed: Size::Unknown,
+                needed: Size::Unknown | Size::Zero,
             } => f.write_str(": requires more data"),
             Self::Incomplete {
                 needed: Size::Known(n),
@@ -114,5 +184,58 @@ impl<E: fmt::Display> fmt::Display for Error<E> {
     }
 }
 
-#[cfg(feature = "std")]
-impl<E: fmt::Display + fmt::Debug> std::error::Error for Error<E> {}
+impl<E: fmt::Display + fmt::Debug> core::error::Error for Error<E> {}
+
+/// Type for when a frame could not be read due to incomplete input.
+///
+/// This converts into [`Error::Incomplete`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Incomplete {
+    /// The minimum amount of bytes required for
+    /// reading the next frame.
+    pub needed: Size,
+}
+
+impl Incomplete {
+    /// Create a new [`Incomplete`].
+    #[inline]
+    pub const fn new(needed: Size) -> Incomplete {
+        Incomplete { needed }
+    }
+}
+
+impl<E> From<Incomplete> for Error<E> {
+    #[inline]
+    fn from(Incomplete { needed }: Incomplete) -> Self {
+        Error::Incomplete { needed }
+    }
+}
+
+impl<E> TryFrom<Error<E>> for Incomplete {
+    type Error = Error<E>;
+
+    #[inline]
+    fn try_from(error: Error<E>) -> core::result::Result<Self, Self::Error> {
+        match error {
+            Error::Incomplete { needed } => Ok(Incomplete { needed }),
+            error => Err(error),
+        }
+    }
+}
+
+impl From<Infallible> for Incomplete {
+    #[inline]
+    fn from(never: Infallible) -> Self {
+        match never {}
+    }
+}
+
+impl fmt::Display for Incomplete {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&Error::<Infallible>::from(*self), f)
+    }
+}
+
+impl core::error::Error for Incomplete {}
@@ -0,0 +1,438 @@
+use core::fmt;
+
+use crate::decoder::{Decoder, Error as DError, Result as DResult};
+use crate::{Size, SizeHint};
+
+/// A [`Decoder`] for names in the DNS wire format, within a message.
+///
+/// Names may end in a compression pointer to an earlier name in the
+/// message, so the source must be the rest of `message`, such as after
+/// reading the header.
+///
+/// Every pointer must point before all of the bytes of the name read so
+/// far, which rejects loops, and names may expand to at most
+/// [`max_len`](Names::max_len) bytes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Names<'m> {
+    message: &'m [u8],
+    /// The maximum length of a name, once expanded.
+    ///
+    /// This is 255 bytes by default, as in RFC 1035.
+    pub max_len: usize,
+}
+
+impl<'m> Names<'m> {
+    /// The maximum length of a name in RFC 1035.
+    pub const MAX_LEN: usize = 255;
+
+    /// Create a new decoder for the names in `message`.
+    #[inline]
+    #[must_use]
+    pub const fn new(message: &'m [u8]) -> Names<'m> {
+        Names {
+            message,
+            max_len: Self::MAX_LEN,
+        }
+    }
+
+    /// Set the maximum length of a name, once expanded.
+    #[inline]
+    #[must_use]
+    pub const fn with_max_len(mut self, max_len: usize) -> Names<'m> {
+        self.max_len = max_len;
+        self
+    }
+
+    /// Returns the message.
+    #[inline]
+    #[must_use]
+    pub const fn message(&self) -> &'m [u8] {
+        self.message
+    }
+}
+
+impl<'m> Decoder for Names<'m> {
+    type Item<'src> = Name<'m>;
+    type Error = NameError;
+
+    #[inline]
+    fn hint(&self) -> Size {
+        Size::new(1)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> SizeHint {
+        SizeHint::new(self.hint(), Size::new(self.max_len.saturating_add(1)))
+    }
+
+    fn decode<'s>(&mut self, src: &mut &'s [u8]) -> DResult<'s, Self> {
+        let fatal = |error| DError::Fatal { error };
+        let message = self.message;
+
+        let start = message
+            .len()
+            .checked_sub(src.len())
+            .filter(|&start| core::ptr::eq(message[start..].as_ptr(), src.as_ptr()))
+            .ok_or(fatal(NameError::NotInMessage))?;
+
+        // Running out of bytes is only incomplete before following a
+        // pointer, as pointers only point to earlier bytes.
+        let incomplete = |end: usize| DError::Incomplete {
+            needed: Size::new(end - message.len()),
+        };
+
+        let mut pos = start;
+        let mut lowest = start;
+        let mut end = None;
+        let mut len = 0usize;
+
+        loop {
+            let Some(&byte) = message.get(pos) else {
+                return Err(match end {
+                    None => incomplete(pos + 1),
+                    Some(_) => fatal(NameError::InvalidPointer),
+                });
+            };
+
+            match byte >> 6 {
+                0b00 => {
+                    let label_len = usize::from(byte);
+
+                    len += 1 + label_len;
+
+                    if len > self.max_len {
+                        return Err(fatal(NameError::TooLong));
+                    }
+
+                    if label_len == 0 {
+                        end.get_or_insert(pos + 1);
+                        break;
+                    }
+
+                    pos += 1 + label_len;
+
+                    if pos > message.len() {
+                        return Err(match end {
+                            None => incomplete(pos),
+                            Some(_) => fatal(NameError::InvalidPointer),
+                        });
+                    }
+                }
+                0b11 => {
+                    let Some(&low) = message.get(pos + 1) else {
+                        return Err(match end {
+                            None => incomplete(pos + 2),
+                            Some(_) => fatal(NameError::InvalidPointer),
+                        });
+                    };
+
+                    let target = usize::from(u16::from_be_bytes([byte & 0x3f, low]));
+
+                    if target >= lowest {
+                        return Err(fatal(NameError::PointerLoop));
+                    }
+
+                    end.get_or_insert(pos + 2);
+                    lowest = target;
+                    pos = target;
+                }
+                _ => return Err(fatal(NameError::InvalidLabelType(byte))),
+            }
+        }
+
+        let end = end.unwrap_or(pos + 1);
+
+        *src = &src[end - start..];
+
+        Ok(Name {
+            message,
+            start,
+            len,
+        })
+    }
+}
+
+/// A name in the DNS wire format, which has been validated.
+#[derive(Clone, Copy)]
+pub struct Name<'m> {
+    message: &'m [u8],
+    start: usize,
+    len: usize,
+}
+
+impl<'m> Name<'m> {
+    /// Returns the length of the name in the wire format, once expanded.
+    #[inline]
+    #[must_use]
+    pub const fn wire_len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns whether this is the root name, which has no labels.
+    #[inline]
+    #[must_use]
+    pub const fn is_root(&self) -> bool {
+        self.len == 1
+    }
+
+    /// Returns an iterator over the labels of the name, from the leftmost.
+    #[inline]
+    pub fn labels(&self) -> Labels<'m> {
+        Labels {
+            message: self.message,
+            pos: self.start,
+        }
+    }
+}
+
+impl PartialEq for Name<'_> {
+    /// Names are compared by their labels, ignoring ASCII case.
+    fn eq(&self, other: &Self) -> bool {
+        self.len == other.len
+            && self
+                .labels()
+                .zip(other.labels())
+                .all(|(a, b)| a.eq_ignore_ascii_case(b))
+    }
+}
+
+impl Eq for Name<'_> {}
+
+impl fmt::Display for Name<'_> {
+    /// Writes the name in the presentation format, such as `example.com.`,
+    /// escaping special bytes.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_root() {
+            return f.write_str(".");
+        }
+
+        for label in self.labels() {
+            for &byte in label {
+                match byte {
+                    b'.' | b'\\' => write!(f, "\\{}", char::from(byte))?,
+                    0x21..=0x7e => write!(f, "{}", char::from(byte))?,
+                    _ => write!(f, "\\{byte:03}")?,
+                }
+            }
+
+            f.write_str(".")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Debug for Name<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Name(\"{self}\")")
+    }
+}
+
+/// An iterator over the labels of a [`Name`].
+#[derive(Debug, Clone)]
+pub struct Labels<'m> {
+    message: &'m [u8],
+    pos: usize,
+}
+
+impl<'m> Iterator for Labels<'m> {
+    type Item = &'m [u8];
+
+    fn next(&mut self) -> Option<&'m [u8]> {
+        loop {
+            let byte = *self.message.get(self.pos)?;
+
+            if byte >> 6 == 0b11 {
+                let low = *self.message.get(self.pos + 1)?;
+
+                self.pos = usize::from(u16::from_be_bytes([byte & 0x3f, low]));
+                continue;
+            }
+
+            if byte == 0 {
+                return None;
+            }
+
+            let label = self
+                .message
+                .get(self.pos + 1..self.pos + 1 + usize::from(byte))?;
+
+            self.pos += 1 + label.len();
+
+            return Some(label);
+        }
+    }
+}
+
+impl core::iter::FusedIterator for Labels<'_> {}
+
+/// Type for errors that may occur while decoding a DNS name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum NameError {
+    /// The source is not the rest of the message.
+    NotInMessage,
+    /// A label has a reserved type.
+    InvalidLabelType(u8),
+    /// A pointer does not point before the rest of the name, which could
+    /// loop forever.
+    PointerLoop,
+    /// A pointer points to a name that runs past the end of the message.
+    InvalidPointer,
+    /// The name is too long once expanded.
+    TooLong,
+}
+
+impl fmt::Display for NameError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotInMessage => f.write_str("source is not part of the message"),
+            Self::InvalidLabelType(byte) => write!(f, "invalid label type: {byte:#04x}"),
+            Self::PointerLoop => f.write_str("compression pointer does not point backwards"),
+            Self::InvalidPointer => f.write_str("compression pointer to a truncated name"),
+            Self::TooLong => f.write_str("name too long"),
+        }
+    }
+}
+
+impl core::error::Error for NameError {}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use std::string::ToString;
+    use std::vec::Vec;
+
+    use super::*;
+
+    fn fatal<T>(error: NameError) -> Result<T, DError<NameError>> {
+        Err(DError::Fatal { error })
+    }
+
+    #[test]
+    fn compression() {
+        let message = b"\x07example\x03com\x00\x03www\xc0\x00\x00";
+        let mut names = Names::new(message);
+        let mut src = &message[..];
+
+        let example = names.decode(&mut src).unwrap();
+
+        assert_eq!(example.to_string(), "example.com.");
+        assert_eq!(example.wire_len(), 13);
+        assert_eq!(src.len(), message.len() - 13);
+
+        let www = names.decode(&mut src).unwrap();
+
+        assert_eq!(www.to_string(), "www.example.com.");
+        assert_eq!(www.wire_len(), 17);
+        assert_eq!(
+            www.labels().collect::<Vec<_>>(),
+            [&b"www"[..], b"example", b"com"]
+        );
+        assert_eq!(src, b"\x00");
+
+        let root = names.decode(&mut src).unwrap();
+
+        assert!(root.is_root());
+        assert_eq!(root.to_string(), ".");
+        assert!(src.is_empty());
+    }
+
+    #[test]
+    fn equality_and_display() {
+        let message = b"\x03WWW\x07Example\x03COM\x00\x03www\x07example\x03com\x00";
+        let mut names = Names::new(message);
+        let mut src = &message[..];
+
+        let a = names.decode(&mut src).unwrap();
+        let b = names.decode(&mut src).unwrap();
+
+        let root = b"\x00";
+        let root = Names::new(root).decode(&mut &root[..]).unwrap();
+
+        assert_eq!(a, b);
+        assert_ne!(a, root);
+
+        let message = b"\x03a.b\x02\x01\\\x00";
+        let mut src = &message[..];
+        let name = Names::new(message).decode(&mut src).unwrap();
+
+        assert_eq!(name.to_string(), "a\\.b.\\001\\\\.");
+    }
+
+    #[test]
+    fn incomplete() {
+        let message = b"\x07exam";
+        let mut names = Names::new(message);
+
+        assert_eq!(
+            names.decode(&mut &message[..]),
+            Err(DError::Incomplete {
+                needed: Size::new(3)
+            })
+        );
+
+        let message = b"\x03www\xc0";
+        let mut names = Names::new(message);
+
+        assert_eq!(
+            names.decode(&mut &message[..]),
+            Err(DError::Incomplete {
+                needed: Size::new(1)
+            })
+        );
+    }
+
+    #[test]
+    fn malformed() {
+        let message = b"\xc0\x00";
+
+        assert_eq!(
+            Names::new(message).decode(&mut &message[..]),
+            fatal(NameError::PointerLoop)
+        );
+
+        // A pointer back into the name itself.
+        let message = b"\x01a\xc0\x00";
+
+        assert_eq!(
+            Names::new(message).decode(&mut &message[..]),
+            fatal(NameError::PointerLoop)
+        );
+
+        let message = b"\x00\xc0\x03\x00";
+
+        assert_eq!(
+            Names::new(message).decode(&mut &message[1..]),
+            fatal(NameError::PointerLoop)
+        );
+
+        let message = b"\x05ab\xc0\x00";
+
+        assert_eq!(
+            Names::new(message).decode(&mut &message[3..]),
+            fatal(NameError::InvalidPointer)
+        );
+
+        let message = b"\x41a\x00";
+
+        assert_eq!(
+            Names::new(message).decode(&mut &message[..]),
+            fatal(NameError::InvalidLabelType(0x41))
+        );
+
+        let message = b"\x07example\x00";
+
+        assert_eq!(
+            Names::new(message)
+                .with_max_len(8)
+                .decode(&mut &message[..]),
+            fatal(NameError::TooLong)
+        );
+        assert_eq!(
+            Names::new(message).decode(&mut &b"\x00"[..]),
+            fatal(NameError::NotInMessage)
+        );
+    }
+}
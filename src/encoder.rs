@@ -0,0 +1,108 @@
+use core::{convert::Infallible, fmt, write};
+
+use crate::Size;
+
+pub type Result<E> = ::core::result::Result<(), Error<E>>;
+
+/// Trait for encoders.
+pub trait Encoder<Item: ?Sized> {
+    type Error: fmt::Display + fmt::Debug;
+
+    /// Get an estimate for the amount of bytes required to write `item`.
+    #[inline]
+    fn encode_hint(&self, item: &Item) -> Size {
+        let _ = item;
+
+        Size::Unknown
+    }
+
+    /// Try to encode a single frame into a byte buffer.
+    ///
+    /// On success `dst` is advanced past the written bytes.
+    fn encode(&mut self, item: &Item, dst: &mut &mut [u8]) -> Result<Self::Error>;
+}
+
+impl<Item: ?Sized, E: Encoder<Item> + ?Sized> Encoder<Item> for &mut E {
+    type Error = E::Error;
+
+    #[inline]
+    fn encode_hint(&self, item: &Item) -> Size {
+        (**self).encode_hint(item)
+    }
+
+    #[inline]
+    fn encode(&mut self, item: &Item, dst: &mut &mut [u8]) -> Result<Self::Error> {
+        (**self).encode(item, dst)
+    }
+}
+
+/// Type for errors that may occur while encoding a frame.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Error<E> {
+    /// The destination buffer is too small to hold the frame.
+    ///
+    /// Do not advance the destination buffer when returning this.
+    Full {
+        /// The minimum amount of additional bytes required for
+        /// writing the frame.
+        ///
+        /// At least one more byte is always needed, so this is never
+        /// [`Size::Zero`]; use [`Size::Unknown`] when the amount is not known.
+        needed: Size,
+    },
+    /// A fatal error has occurred while writing the
+    /// current frame.
+    ///
+    /// This indicates that the frame cannot be represented.
+    Fatal {
+        /// The error.
+        error: E,
+    },
+}
+
+impl<E> Error<E> {
+    #[inline]
+    #[must_use]
+    pub fn map<T, F: FnOnce(E) -> T>(self, f: F) -> Error<T> {
+        match self {
+            Error::Full { needed } => Error::Full { needed },
+            Error::Fatal { error } => Error::Fatal { error: f(error) },
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    pub const fn message(&self) -> &'static str {
+        match self {
+            Self::Full { .. } => "destination full",
+            Self::Fatal { .. } => "fatal error occurred",
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    pub const fn from_infallible(error: Error<Infallible>) -> Error<E> {
+        match error {
+            Error::Full { needed } => Error::Full { needed },
+        }
+    }
+}
+
+impl<E: fmt::Display> fmt::Display for Error<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.message())?;
+
+        match self {
+            Self::Full {
+                needed: Size::Unknown | Size::Zero,
+            } => f.write_str(": requires more space"),
+            Self::Full {
+                needed: Size::Known(n),
+            } => write!(f, ": requires at least {n} more bytes"),
+            Self::Fatal { error } => write!(f, ": {error}"),
+        }
+    }
+}
+
+impl<E: fmt::Display + fmt::Debug> core::error::Error for Error<E> {}
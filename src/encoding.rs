@@ -0,0 +1,85 @@
+use core::{fmt, ops::Deref};
+
+use arrayvec::ArrayVec;
+
+/// Base64, as in RFC 4648.
+pub mod base64;
+
+/// Base32, as in RFC 4648 or in Crockford's alphabet.
+pub mod base32;
+
+/// Base58, in the Bitcoin alphabet.
+pub mod base58;
+
+/// Hexadecimal text.
+pub mod hex;
+
+/// Percent-encoded text, as in URLs.
+pub mod percent;
+
+/// A chunk of bytes decoded from text.
+///
+/// Decoders for text yield these rather than borrowing from the source, as
+/// the decoded bytes differ from the source, holding at most
+/// [`CAPACITY`](Decoded::CAPACITY) bytes each.
+#[derive(Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Decoded {
+    bytes: ArrayVec<u8, { Decoded::CAPACITY }>,
+}
+
+impl Decoded {
+    /// The maximum amount of bytes in a chunk.
+    pub const CAPACITY: usize = 64;
+
+    /// Create a new empty chunk.
+    #[inline]
+    #[must_use]
+    pub const fn new() -> Decoded {
+        Decoded {
+            bytes: ArrayVec::new_const(),
+        }
+    }
+
+    /// Returns the decoded bytes.
+    #[inline]
+    #[must_use]
+    pub fn as_slice(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    /// Returns the amount of bytes that can still be added.
+    #[inline]
+    pub(crate) fn remaining_capacity(&self) -> usize {
+        self.bytes.remaining_capacity()
+    }
+
+    /// Add `bytes`, which must fit in the remaining capacity.
+    #[inline]
+    pub(crate) fn extend(&mut self, bytes: &[u8]) {
+        self.bytes
+            .try_extend_from_slice(bytes)
+            .expect("decoded bytes fit in the chunk");
+    }
+}
+
+impl Deref for Decoded {
+    type Target = [u8];
+
+    #[inline]
+    fn deref(&self) -> &[u8] {
+        self.as_slice()
+    }
+}
+
+impl AsRef<[u8]> for Decoded {
+    #[inline]
+    fn as_ref(&self) -> &[u8] {
+        self.as_slice()
+    }
+}
+
+impl fmt::Debug for Decoded {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Decoded").field(&self.as_slice()).finish()
+    }
+}
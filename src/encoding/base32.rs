@@ -0,0 +1,502 @@
+use core::fmt;
+
+use super::Decoded;
+use crate::decoder::{Decoder, Error as DError, Result as DResult};
+use crate::encoder::{Encoder, Error as EError, Result as EResult};
+use crate::{Size, SizeHint};
+
+/// The alphabet of base32 text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Alphabet {
+    /// The alphabet of RFC 4648, `A` to `Z` then `2` to `7`.
+    #[default]
+    Rfc4648,
+    /// Crockford's alphabet, `0` to `9` then `A` to `Z` without `I`, `L`,
+    /// `O` and `U`.
+    ///
+    /// Decoding ignores case, reads `O` as `0` and `I` and `L` as `1`, and
+    /// skips hyphens.
+    Crockford,
+}
+
+impl Alphabet {
+    /// Returns the character for the lowest 5 bits of `value`.
+    #[inline]
+    #[must_use]
+    pub const fn encode(self, value: u8) -> u8 {
+        let symbols = match self {
+            Self::Rfc4648 => b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567",
+            Self::Crockford => b"0123456789ABCDEFGHJKMNPQRSTVWXYZ",
+        };
+
+        symbols[(value & 0x1f) as usize]
+    }
+
+    /// Returns the value of the character `byte`, if it is in the alphabet.
+    #[inline]
+    #[must_use]
+    pub const fn decode(self, byte: u8) -> Option<u8> {
+        match self {
+            Self::Rfc4648 => match byte {
+                b'A'..=b'Z' => Some(byte - b'A'),
+                b'2'..=b'7' => Some(byte - b'2' + 26),
+                _ => None,
+            },
+            Self::Crockford => match byte.to_ascii_uppercase() {
+                b'O' => Some(0),
+                b'I' | b'L' => Some(1),
+                byte @ b'0'..=b'9' => Some(byte - b'0'),
+                byte @ b'A'..=b'H' => Some(byte - b'A' + 10),
+                byte @ b'J'..=b'K' => Some(byte - b'J' + 18),
+                byte @ b'M'..=b'N' => Some(byte - b'M' + 20),
+                byte @ b'P'..=b'T' => Some(byte - b'P' + 22),
+                byte @ b'V'..=b'Z' => Some(byte - b'V' + 27),
+                _ => None,
+            },
+        }
+    }
+}
+
+/// A codec for base32 text.
+///
+/// This works like [`Base64`](super::base64::Base64), with quantums of
+/// eight characters for five bytes.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Base32 {
+    /// The alphabet of the text.
+    pub alphabet: Alphabet,
+    /// Whether partial quantums are padded with `=`.
+    ///
+    /// This is disabled by default.
+    pub padding: bool,
+    /// The bits of the partial quantum being decoded.
+    bits: u64,
+    /// The amount of characters in `bits`.
+    chars: u8,
+    /// The bytes of the partial quantum being encoded.
+    pending: [u8; 4],
+    /// The amount of bytes in `pending`.
+    pending_len: u8,
+}
+
+impl Base32 {
+    /// Create a new codec for base32 text in `alphabet`.
+    #[inline]
+    #[must_use]
+    pub const fn new(alphabet: Alphabet) -> Base32 {
+        Base32 {
+            alphabet,
+            padding: false,
+            bits: 0,
+            chars: 0,
+            pending: [0; 4],
+            pending_len: 0,
+        }
+    }
+
+    /// Set whether partial quantums are padded with `=`.
+    #[inline]
+    #[must_use]
+    pub const fn with_padding(mut self, padding: bool) -> Base32 {
+        self.padding = padding;
+        self
+    }
+
+    /// Returns the length of the text for `len` bytes, in full.
+    #[inline]
+    #[must_use]
+    pub const fn encoded_len(&self, len: usize) -> usize {
+        let quantums = (len / 5) * 8;
+
+        match len % 5 {
+            0 => quantums,
+            _ if self.padding => quantums + 8,
+            rest => quantums + (rest * 8).div_ceil(5),
+        }
+    }
+
+    /// Write the final partial quantum, if any.
+    pub fn finish(&mut self, dst: &mut &mut [u8]) -> EResult<::core::convert::Infallible> {
+        let pending = usize::from(self.pending_len);
+        let chars = (pending * 8).div_ceil(5);
+
+        let len = match pending {
+            0 => return Ok(()),
+            _ if self.padding => 8,
+            _ => chars,
+        };
+
+        if dst.len() < len {
+            return Err(EError::Full {
+                needed: Size::new(len - dst.len()),
+            });
+        }
+
+        let (out, rest) = core::mem::take(dst).split_at_mut(len);
+        let [a, b, c, d] = self.pending;
+
+        out[..chars].copy_from_slice(&self.quantum([a, b, c, d, 0])[..chars]);
+        out[chars..].fill(b'=');
+
+        self.pending_len = 0;
+        *dst = rest;
+
+        Ok(())
+    }
+
+    /// Returns the characters for five bytes.
+    #[inline]
+    fn quantum(&self, bytes: [u8; 5]) -> [u8; 8] {
+        let mut bits = [0; 8];
+
+        bits[3..].copy_from_slice(&bytes);
+
+        let bits = u64::from_be_bytes(bits);
+
+        core::array::from_fn(|i| self.alphabet.encode((bits >> (35 - 5 * i)) as u8))
+    }
+
+    fn next<'s>(&mut self, src: &mut &'s [u8], eof: bool) -> DResult<'s, Self> {
+        let fatal = |error| DError::Fatal { error };
+
+        let mut bits = self.bits;
+        let mut chars = self.chars;
+        let mut decoded = Decoded::new();
+        let mut pos = 0;
+
+        // The amount of characters needed to go on, once out of characters.
+        let mut needed = 8 - usize::from(chars);
+
+        while decoded.remaining_capacity() >= 5 {
+            let Some(&byte) = src.get(pos) else {
+                break;
+            };
+
+            if byte == b'-' && self.alphabet == Alphabet::Crockford {
+                pos += 1;
+                continue;
+            }
+
+            if byte == b'=' {
+                if !self.padding || !matches!(chars, 2 | 4 | 5 | 7) {
+                    return Err(fatal(Base32Error::InvalidPadding));
+                }
+
+                let padding = usize::from(8 - chars);
+
+                match src.get(pos..pos + padding) {
+                    Some(padding) if padding.iter().all(|&byte| byte == b'=') => {}
+                    Some(_) => return Err(fatal(Base32Error::InvalidPadding)),
+                    None => {
+                        needed = pos + padding - src.len();
+                        break;
+                    }
+                }
+
+                Self::partial(&mut decoded, bits, chars)?;
+
+                pos += padding;
+                bits = 0;
+                chars = 0;
+                needed = 8;
+
+                continue;
+            }
+
+            let value = self
+                .alphabet
+                .decode(byte)
+                .ok_or(fatal(Base32Error::InvalidByte(byte)))?;
+
+            bits = bits << 5 | u64::from(value);
+            chars += 1;
+            pos += 1;
+
+            if chars == 8 {
+                decoded.extend(&bits.to_be_bytes()[3..]);
+
+                bits = 0;
+                chars = 0;
+            }
+
+            needed = 8 - usize::from(chars);
+        }
+
+        if decoded.is_empty() {
+            if !eof {
+                return Err(DError::Incomplete {
+                    needed: Size::new(needed),
+                });
+            }
+
+            if pos < src.len() || (chars > 0 && self.padding) {
+                return Err(fatal(Base32Error::Truncated));
+            }
+
+            if chars == 0 {
+                return Err(DError::Eof);
+            }
+
+            Self::partial(&mut decoded, bits, chars)?;
+
+            bits = 0;
+            chars = 0;
+        }
+
+        *src = &src[pos..];
+        self.bits = bits;
+        self.chars = chars;
+
+        Ok(decoded)
+    }
+
+    /// Add the bytes of a partial quantum of `chars` characters, rejecting
+    /// any bits left over.
+    fn partial(decoded: &mut Decoded, bits: u64, chars: u8) -> Result<(), DError<Base32Error>> {
+        if !matches!(chars, 2 | 4 | 5 | 7) {
+            return Err(DError::Fatal {
+                error: Base32Error::Truncated,
+            });
+        }
+
+        let len = usize::from(chars) * 5 / 8;
+        let rest = u32::from(chars) * 5 % 8;
+
+        if bits & ((1 << rest) - 1) != 0 {
+            return Err(DError::Fatal {
+                error: Base32Error::NonCanonical,
+            });
+        }
+
+        decoded.extend(&(bits >> rest).to_be_bytes()[8 - len..]);
+
+        Ok(())
+    }
+}
+
+impl Decoder for Base32 {
+    type Item<'src> = Decoded;
+    type Error = Base32Error;
+
+    #[inline]
+    fn hint(&self) -> Size {
+        Size::new(8 - usize::from(self.chars))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> SizeHint {
+        SizeHint::at_least(self.hint())
+    }
+
+    #[inline]
+    fn decode<'s>(&mut self, src: &mut &'s [u8]) -> DResult<'s, Self> {
+        self.next(src, false)
+    }
+
+    #[inline]
+    fn decode_eof<'s>(&mut self, src: &mut &'s [u8]) -> DResult<'s, Self> {
+        self.next(src, true)
+    }
+}
+
+impl Encoder<[u8]> for Base32 {
+    type Error = ::core::convert::Infallible;
+
+    #[inline]
+    fn encode_hint(&self, item: &[u8]) -> Size {
+        Size::new((usize::from(self.pending_len) + item.len()) / 5 * 8)
+    }
+
+    fn encode(&mut self, item: &[u8], dst: &mut &mut [u8]) -> EResult<Self::Error> {
+        let pending = usize::from(self.pending_len);
+        let total = pending + item.len();
+        let len = total / 5 * 8;
+
+        if dst.len() < len {
+            return Err(EError::Full {
+                needed: Size::new(len - dst.len()),
+            });
+        }
+
+        let (out, rest) = core::mem::take(dst).split_at_mut(len);
+        let mut bytes = self.pending[..pending].iter().chain(item).copied();
+
+        for chars in out.chunks_exact_mut(8) {
+            let quantum = [0; 5].map(|_| bytes.next().unwrap_or_default());
+
+            chars.copy_from_slice(&self.quantum(quantum));
+        }
+
+        let mut carried = [0; 4];
+        let carried_len = total % 5;
+
+        for (slot, byte) in carried.iter_mut().zip(bytes) {
+            *slot = byte;
+        }
+
+        self.pending = carried;
+        self.pending_len = carried_len as u8;
+        *dst = rest;
+
+        Ok(())
+    }
+}
+
+/// Type for errors that may occur while decoding base32 text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Base32Error {
+    /// A byte is not in the alphabet.
+    InvalidByte(u8),
+    /// Padding is disabled, or does not end a partial quantum.
+    InvalidPadding,
+    /// The bits left over in a partial quantum are not zero.
+    NonCanonical,
+    /// The text ends with a partial quantum of an invalid length, or that
+    /// is missing its padding.
+    Truncated,
+}
+
+impl fmt::Display for Base32Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidByte(byte) => write!(f, "invalid base32 byte: {byte:#04x}"),
+            Self::InvalidPadding => f.write_str("invalid base32 padding"),
+            Self::NonCanonical => f.write_str("non-canonical base32"),
+            Self::Truncated => f.write_str("truncated base32"),
+        }
+    }
+}
+
+impl core::error::Error for Base32Error {}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use std::vec::Vec;
+
+    use super::*;
+
+    fn encode(mut base32: Base32, bytes: &[u8]) -> Vec<u8> {
+        let mut buf = [0; 64];
+        let mut dst = &mut buf[..];
+
+        base32.encode(bytes, &mut dst).unwrap();
+        base32.finish(&mut dst).unwrap();
+
+        let len = 64 - dst.len();
+
+        buf[..len].to_vec()
+    }
+
+    fn decode(mut base32: Base32, text: &[u8]) -> Result<Vec<u8>, DError<Base32Error>> {
+        let mut src = text;
+        let mut bytes = Vec::new();
+
+        loop {
+            match base32.decode_eof(&mut src) {
+                Ok(decoded) => bytes.extend_from_slice(&decoded),
+                Err(DError::Eof) => return Ok(bytes),
+                Err(error) => return Err(error),
+            }
+        }
+    }
+
+    fn fatal(error: Base32Error) -> Result<Vec<u8>, DError<Base32Error>> {
+        Err(DError::Fatal { error })
+    }
+
+    #[test]
+    fn rfc_4648_vectors() {
+        let cases: [(&[u8], &[u8]); 7] = [
+            (b"", b""),
+            (b"f", b"MY======"),
+            (b"fo", b"MZXQ===="),
+            (b"foo", b"MZXW6==="),
+            (b"foob", b"MZXW6YQ="),
+            (b"fooba", b"MZXW6YTB"),
+            (b"foobar", b"MZXW6YTBOI======"),
+        ];
+
+        let padded = Base32::default().with_padding(true);
+
+        for (bytes, text) in cases {
+            let len = text.iter().rposition(|&b| b != b'=').map_or(0, |i| i + 1);
+            let unpadded = &text[..len];
+
+            assert_eq!(encode(padded.clone(), bytes), text);
+            assert_eq!(decode(padded.clone(), text).as_deref(), Ok(bytes));
+            assert_eq!(encode(Base32::default(), bytes), unpadded);
+            assert_eq!(decode(Base32::default(), unpadded).as_deref(), Ok(bytes));
+        }
+    }
+
+    #[test]
+    fn crockford() {
+        let crockford = Base32::new(Alphabet::Crockford);
+
+        assert_eq!(encode(crockford.clone(), b"foobar"), b"CSQPYRK1E8");
+        assert_eq!(
+            decode(crockford.clone(), b"csqp-yrkle8").as_deref(),
+            Ok(&b"foobar"[..])
+        );
+        assert_eq!(
+            decode(crockford.clone(), b"0O").as_deref(),
+            decode(crockford.clone(), b"00").as_deref()
+        );
+        assert_eq!(
+            decode(crockford, b"CSQU"),
+            fatal(Base32Error::InvalidByte(b'U'))
+        );
+        assert_eq!(
+            decode(Base32::default(), b"MZXW-6YQ"),
+            fatal(Base32Error::InvalidByte(b'-'))
+        );
+    }
+
+    #[test]
+    fn streaming() {
+        let mut base32 = Base32::default();
+
+        assert_eq!(
+            base32.decode(&mut &b"MZX"[..]),
+            Err(DError::Incomplete {
+                needed: Size::new(5)
+            })
+        );
+
+        let mut src = &b"MZXW6YTBOI"[..];
+
+        assert_eq!(base32.decode(&mut src).as_deref(), Ok(&b"fooba"[..]));
+        assert!(src.is_empty());
+        assert_eq!(base32.hint(), Size::new(6));
+        assert_eq!(base32.decode_eof(&mut src).as_deref(), Ok(&b"r"[..]));
+        assert_eq!(base32.decode_eof(&mut src), Err(DError::Eof));
+    }
+
+    #[test]
+    fn malformed() {
+        let padded = Base32::default().with_padding(true);
+
+        assert_eq!(
+            decode(Base32::default(), b"MY======"),
+            fatal(Base32Error::InvalidPadding)
+        );
+        assert_eq!(
+            decode(padded.clone(), b"MZX====="),
+            fatal(Base32Error::InvalidPadding)
+        );
+        assert_eq!(
+            decode(padded.clone(), b"MZ======"),
+            fatal(Base32Error::NonCanonical)
+        );
+        assert_eq!(decode(padded, b"MY"), fatal(Base32Error::Truncated));
+        assert_eq!(
+            decode(Base32::default(), b"MZX"),
+            fatal(Base32Error::Truncated)
+        );
+    }
+}
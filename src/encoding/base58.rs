@@ -0,0 +1,391 @@
+use core::fmt;
+
+use super::Decoded;
+use crate::checksum::sha256;
+use crate::decoder::{Decoder, Error as DError, Result as DResult};
+use crate::encoder::{Encoder, Error as EError, Result as EResult};
+use crate::{Size, SizeHint};
+
+/// The Bitcoin alphabet, without `0`, `I`, `O` and `l`.
+const ALPHABET: &[u8; 58] = b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+
+/// The amount of bytes in the checksum of Base58Check.
+const CHECK_LEN: usize = 4;
+
+/// A codec for base58 text in the Bitcoin alphabet.
+///
+/// Base58 is not split into quantums, so each value is decoded in full,
+/// from a run of characters in the alphabet ending at the first byte that
+/// is not, which is left in the source, or at the end of the stream. Values
+/// may decode to at most [`Decoded::CAPACITY`] bytes.
+///
+/// With [`check`](Base58::check), values end in the first four bytes of
+/// the double SHA-256 of the rest, as in Base58Check, which is verified and
+/// removed when decoding and appended when encoding.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Base58 {
+    /// Whether values end in a checksum.
+    ///
+    /// This is disabled by default.
+    pub check: bool,
+}
+
+impl Base58 {
+    /// Create a new codec for base58 text.
+    #[inline]
+    #[must_use]
+    pub const fn new() -> Base58 {
+        Base58 { check: false }
+    }
+
+    /// Set whether values end in a checksum.
+    #[inline]
+    #[must_use]
+    pub const fn with_check(mut self, check: bool) -> Base58 {
+        self.check = check;
+        self
+    }
+
+    /// Returns the value of the character `byte`, if it is in the alphabet.
+    #[inline]
+    const fn digit(byte: u8) -> Option<u8> {
+        match byte {
+            b'1'..=b'9' => Some(byte - b'1'),
+            b'A'..=b'H' => Some(byte - b'A' + 9),
+            b'J'..=b'N' => Some(byte - b'J' + 17),
+            b'P'..=b'Z' => Some(byte - b'P' + 22),
+            b'a'..=b'k' => Some(byte - b'a' + 33),
+            b'm'..=b'z' => Some(byte - b'm' + 44),
+            _ => None,
+        }
+    }
+
+    fn next<'s>(&mut self, src: &mut &'s [u8], eof: bool) -> DResult<'s, Self> {
+        let fatal = |error| DError::Fatal { error };
+
+        // The value, in big endian at the end of the buffer.
+        let mut value = [0u8; Decoded::CAPACITY + CHECK_LEN];
+        let mut len = 0;
+        let mut zeros = 0;
+        let mut pos = 0;
+
+        let end = loop {
+            let Some(&byte) = src.get(pos) else {
+                if !eof {
+                    return Err(DError::Incomplete {
+                        needed: Size::new(1),
+                    });
+                }
+
+                break pos;
+            };
+
+            let Some(digit) = Self::digit(byte) else {
+                break pos;
+            };
+
+            pos += 1;
+
+            if digit == 0 && len == 0 {
+                zeros += 1;
+            }
+
+            let mut carry = u32::from(digit);
+
+            for byte in value.iter_mut().rev().take(len) {
+                carry += u32::from(*byte) * 58;
+                *byte = carry as u8;
+                carry >>= 8;
+            }
+
+            while carry > 0 {
+                len += 1;
+
+                let Some(byte) = value.len().checked_sub(len).map(|i| &mut value[i]) else {
+                    return Err(fatal(Base58Error::TooLong));
+                };
+
+                *byte = carry as u8;
+                carry >>= 8;
+            }
+
+            if zeros + len > value.len() {
+                return Err(fatal(Base58Error::TooLong));
+            }
+        };
+
+        match src.get(end) {
+            _ if end > 0 => {}
+            Some(&byte) => return Err(fatal(Base58Error::InvalidByte(byte))),
+            None => return Err(DError::Eof),
+        }
+
+        let bytes = &value[value.len() - len - zeros..];
+
+        let bytes = if self.check {
+            let (bytes, check) = bytes
+                .split_last_chunk::<CHECK_LEN>()
+                .ok_or(fatal(Base58Error::Checksum))?;
+
+            if checksum(bytes) != *check {
+                return Err(fatal(Base58Error::Checksum));
+            }
+
+            bytes
+        } else {
+            bytes
+        };
+
+        if bytes.len() > Decoded::CAPACITY {
+            return Err(fatal(Base58Error::TooLong));
+        }
+
+        let mut decoded = Decoded::new();
+
+        decoded.extend(bytes);
+
+        *src = &src[end..];
+
+        Ok(decoded)
+    }
+}
+
+impl Decoder for Base58 {
+    type Item<'src> = Decoded;
+    type Error = Base58Error;
+
+    #[inline]
+    fn hint(&self) -> Size {
+        Size::new(1)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> SizeHint {
+        SizeHint::at_least(self.hint())
+    }
+
+    #[inline]
+    fn decode<'s>(&mut self, src: &mut &'s [u8]) -> DResult<'s, Self> {
+        self.next(src, false)
+    }
+
+    #[inline]
+    fn decode_eof<'s>(&mut self, src: &mut &'s [u8]) -> DResult<'s, Self> {
+        self.next(src, true)
+    }
+}
+
+impl Encoder<[u8]> for Base58 {
+    type Error = ::core::convert::Infallible;
+
+    #[inline]
+    fn encode_hint(&self, item: &[u8]) -> Size {
+        let len = item.len() + if self.check { CHECK_LEN } else { 0 };
+
+        // Each byte takes at most log(256) / log(58) < 1.37 characters.
+        Size::new(len.saturating_mul(137) / 100 + 1)
+    }
+
+    /// Write `item` in base58.
+    ///
+    /// As the length of the text is only known once written, this returns
+    /// [`Size::Unknown`] if `dst` is too small.
+    fn encode(&mut self, item: &[u8], dst: &mut &mut [u8]) -> EResult<Self::Error> {
+        let check = if self.check {
+            checksum(item)
+        } else {
+            [0; CHECK_LEN]
+        };
+
+        let check = &check[..if self.check { CHECK_LEN } else { 0 }];
+        let zeros = item
+            .iter()
+            .chain(check)
+            .take_while(|&&byte| byte == 0)
+            .count();
+
+        let full = || EError::Full {
+            needed: Size::Unknown,
+        };
+
+        // The digits, in little endian at the start of `dst`.
+        let mut len = 0;
+
+        for &byte in item.iter().chain(check).skip(zeros) {
+            let mut carry = u32::from(byte);
+
+            for digit in &mut dst[..len] {
+                carry += u32::from(*digit) << 8;
+                *digit = (carry % 58) as u8;
+                carry /= 58;
+            }
+
+            while carry > 0 {
+                *dst.get_mut(len).ok_or_else(full)? = (carry % 58) as u8;
+                len += 1;
+                carry /= 58;
+            }
+        }
+
+        if dst.len() < zeros + len {
+            return Err(full());
+        }
+
+        let (out, rest) = core::mem::take(dst).split_at_mut(zeros + len);
+
+        out[..len].reverse();
+        out.copy_within(..len, zeros);
+        out[..zeros].fill(0);
+
+        for digit in out.iter_mut() {
+            *digit = ALPHABET[usize::from(*digit)];
+        }
+
+        *dst = rest;
+
+        Ok(())
+    }
+}
+
+/// Type for errors that may occur while decoding base58 text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Base58Error {
+    /// The text starts with a byte that is not in the alphabet.
+    InvalidByte(u8),
+    /// The value is too long.
+    TooLong,
+    /// The checksum is missing or does not match.
+    Checksum,
+}
+
+impl fmt::Display for Base58Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidByte(byte) => write!(f, "invalid base58 byte: {byte:#04x}"),
+            Self::TooLong => f.write_str("base58 value too long"),
+            Self::Checksum => f.write_str("base58 checksum mismatch"),
+        }
+    }
+}
+
+impl core::error::Error for Base58Error {}
+
+/// Returns the checksum of Base58Check, the first bytes of the double
+/// SHA-256 of `bytes`.
+fn checksum(bytes: &[u8]) -> [u8; CHECK_LEN] {
+    let hash = sha256(&sha256(bytes));
+
+    [hash[0], hash[1], hash[2], hash[3]]
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use std::vec::Vec;
+
+    use super::*;
+
+    fn encode(mut base58: Base58, bytes: &[u8]) -> Vec<u8> {
+        let mut buf = [0; 128];
+        let mut dst = &mut buf[..];
+
+        base58.encode(bytes, &mut dst).unwrap();
+
+        let len = 128 - dst.len();
+
+        buf[..len].to_vec()
+    }
+
+    fn decode(mut base58: Base58, text: &[u8]) -> Result<Vec<u8>, DError<Base58Error>> {
+        let mut src = text;
+        let decoded = base58.decode_eof(&mut src)?;
+
+        assert!(src.is_empty(), "trailing bytes: {src:?}");
+
+        Ok(decoded.to_vec())
+    }
+
+    fn fatal(error: Base58Error) -> Result<Vec<u8>, DError<Base58Error>> {
+        Err(DError::Fatal { error })
+    }
+
+    #[test]
+    fn round_trip() {
+        let cases: [(&[u8], &[u8]); 4] = [
+            (b"\0", b"1"),
+            (b"\x39", b"z"),
+            (b"Hello World!", b"2NEpo7TZRRrLZSi2U"),
+            (b"\0\0\x28\x7f\xb4\xcd", b"11233QC4"),
+        ];
+
+        for (bytes, text) in cases {
+            assert_eq!(encode(Base58::new(), bytes), text);
+            assert_eq!(decode(Base58::new(), text).as_deref(), Ok(bytes));
+        }
+
+        assert_eq!(encode(Base58::new(), b""), b"");
+        assert_eq!(decode(Base58::new(), b""), Err(DError::Eof));
+    }
+
+    #[test]
+    fn check() {
+        let address = b"1PMycacnJaSqwwJqjawXBErnLsZ7RkXUAs";
+        let payload =
+            b"\0\xf5\x4a\x58\x51\xe9\x37\x2b\x87\x81\x0a\x8e\x60\xcd\xd2\xe7\xcf\xd8\x0b\x6e\x31";
+        let check = Base58::new().with_check(true);
+
+        assert_eq!(encode(check.clone(), payload), address);
+        assert_eq!(decode(check.clone(), address).as_deref(), Ok(&payload[..]));
+
+        let mut corrupt = *address;
+
+        corrupt[33] = b'B';
+
+        assert_eq!(
+            decode(check.clone(), &corrupt),
+            fatal(Base58Error::Checksum)
+        );
+        assert_eq!(decode(check, b"1"), fatal(Base58Error::Checksum));
+    }
+
+    #[test]
+    fn edge_cases() {
+        let mut base58 = Base58::new();
+        let mut src = &b"2NEpo7TZRRrLZSi2U 1"[..];
+
+        assert_eq!(base58.decode(&mut src).as_deref(), Ok(&b"Hello World!"[..]));
+        assert_eq!(src, b" 1");
+        assert_eq!(
+            base58.decode(&mut src),
+            Err(DError::Fatal {
+                error: Base58Error::InvalidByte(b' ')
+            })
+        );
+        assert_eq!(
+            base58.decode(&mut &b"2NEpo"[..]),
+            Err(DError::Incomplete {
+                needed: Size::new(1)
+            })
+        );
+        assert_eq!(
+            decode(Base58::new(), &[b'z'; 100]),
+            fatal(Base58Error::TooLong)
+        );
+        assert_eq!(
+            decode(Base58::new(), &[b'1'; 100]),
+            fatal(Base58Error::TooLong)
+        );
+
+        let mut buf = [0; 4];
+
+        assert_eq!(
+            Base58::new().encode(b"Hello World!", &mut &mut buf[..]),
+            Err(EError::Full {
+                needed: Size::Unknown
+            })
+        );
+    }
+}
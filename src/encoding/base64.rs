@@ -0,0 +1,507 @@
+use core::fmt;
+
+use super::Decoded;
+use crate::decoder::{Decoder, Error as DError, Result as DResult};
+use crate::encoder::{Encoder, Error as EError, Result as EResult};
+use crate::{Size, SizeHint};
+
+/// The alphabet of base64 text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Alphabet {
+    /// The standard alphabet, ending in `+` and `/`.
+    #[default]
+    Standard,
+    /// The URL and filename safe alphabet, ending in `-` and `_`.
+    UrlSafe,
+}
+
+impl Alphabet {
+    /// Returns the character for the lowest 6 bits of `value`.
+    #[inline]
+    #[must_use]
+    pub const fn encode(self, value: u8) -> u8 {
+        let symbols = match self {
+            Self::Standard => b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/",
+            Self::UrlSafe => b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_",
+        };
+
+        symbols[(value & 0x3f) as usize]
+    }
+
+    /// Returns the value of the character `byte`, if it is in the alphabet.
+    #[inline]
+    #[must_use]
+    pub const fn decode(self, byte: u8) -> Option<u8> {
+        match (self, byte) {
+            (_, b'A'..=b'Z') => Some(byte - b'A'),
+            (_, b'a'..=b'z') => Some(byte - b'a' + 26),
+            (_, b'0'..=b'9') => Some(byte - b'0' + 52),
+            (Self::Standard, b'+') | (Self::UrlSafe, b'-') => Some(62),
+            (Self::Standard, b'/') | (Self::UrlSafe, b'_') => Some(63),
+            _ => None,
+        }
+    }
+}
+
+/// A codec for base64 text, as in RFC 4648.
+///
+/// Decoding yields the bytes of as many quantums as are available, at most
+/// [`Decoded::CAPACITY`] at a time. A partial quantum at the end of the
+/// source is carried over into the next call, once some bytes have been
+/// decoded; otherwise the source is left as is.
+///
+/// Encoding writes the quantums completed by each slice of bytes, carrying
+/// over the rest into the next, and [`finish`](Base64::finish) writes the
+/// final partial quantum.
+///
+/// Padding may be disabled, in which case decoding rejects `=` and accepts
+/// a partial quantum at the end of the stream. Otherwise `=` ends a quantum,
+/// so padded text may be concatenated.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Base64 {
+    /// The alphabet of the text.
+    pub alphabet: Alphabet,
+    /// Whether partial quantums are padded with `=`.
+    ///
+    /// This is disabled by default.
+    pub padding: bool,
+    /// The bits of the partial quantum being decoded.
+    bits: u32,
+    /// The amount of characters in `bits`.
+    chars: u8,
+    /// The bytes of the partial quantum being encoded.
+    pending: [u8; 2],
+    /// The amount of bytes in `pending`.
+    pending_len: u8,
+}
+
+impl Base64 {
+    /// Create a new codec for base64 text in `alphabet`.
+    #[inline]
+    #[must_use]
+    pub const fn new(alphabet: Alphabet) -> Base64 {
+        Base64 {
+            alphabet,
+            padding: false,
+            bits: 0,
+            chars: 0,
+            pending: [0; 2],
+            pending_len: 0,
+        }
+    }
+
+    /// Set whether partial quantums are padded with `=`.
+    #[inline]
+    #[must_use]
+    pub const fn with_padding(mut self, padding: bool) -> Base64 {
+        self.padding = padding;
+        self
+    }
+
+    /// Returns the length of the text for `len` bytes, in full.
+    #[inline]
+    #[must_use]
+    pub const fn encoded_len(&self, len: usize) -> usize {
+        let quantums = (len / 3) * 4;
+
+        match len % 3 {
+            0 => quantums,
+            _ if self.padding => quantums + 4,
+            rest => quantums + rest + 1,
+        }
+    }
+
+    /// Write the final partial quantum, if any.
+    pub fn finish(&mut self, dst: &mut &mut [u8]) -> EResult<::core::convert::Infallible> {
+        let len = match self.pending_len {
+            0 => return Ok(()),
+            _ if self.padding => 4,
+            pending => usize::from(pending) + 1,
+        };
+
+        if dst.len() < len {
+            return Err(EError::Full {
+                needed: Size::new(len - dst.len()),
+            });
+        }
+
+        let (out, rest) = core::mem::take(dst).split_at_mut(len);
+        let pending = usize::from(self.pending_len);
+        let [a, b] = self.pending;
+
+        out[..=pending].copy_from_slice(&self.quantum([a, b, 0])[..=pending]);
+        out[pending + 1..].fill(b'=');
+
+        self.pending_len = 0;
+        *dst = rest;
+
+        Ok(())
+    }
+
+    /// Returns the characters for three bytes.
+    #[inline]
+    const fn quantum(&self, [a, b, c]: [u8; 3]) -> [u8; 4] {
+        [
+            self.alphabet.encode(a >> 2),
+            self.alphabet.encode(a << 4 | b >> 4),
+            self.alphabet.encode(b << 2 | c >> 6),
+            self.alphabet.encode(c),
+        ]
+    }
+
+    fn next<'s>(&mut self, src: &mut &'s [u8], eof: bool) -> DResult<'s, Self> {
+        let fatal = |error| DError::Fatal { error };
+
+        let mut bits = self.bits;
+        let mut chars = self.chars;
+        let mut decoded = Decoded::new();
+        let mut pos = 0;
+
+        // The amount of characters needed to go on, once out of characters.
+        let mut needed = 4 - usize::from(chars);
+
+        while decoded.remaining_capacity() >= 3 {
+            let Some(&byte) = src.get(pos) else {
+                break;
+            };
+
+            if byte == b'=' {
+                if !self.padding || chars < 2 {
+                    return Err(fatal(Base64Error::InvalidPadding));
+                }
+
+                let padding = usize::from(4 - chars);
+
+                match src.get(pos..pos + padding) {
+                    Some(padding) if padding.iter().all(|&byte| byte == b'=') => {}
+                    Some(_) => return Err(fatal(Base64Error::InvalidPadding)),
+                    None => {
+                        needed = pos + padding - src.len();
+                        break;
+                    }
+                }
+
+                Self::partial(&mut decoded, bits, chars)?;
+
+                pos += padding;
+                bits = 0;
+                chars = 0;
+                needed = 4;
+
+                continue;
+            }
+
+            let value = self
+                .alphabet
+                .decode(byte)
+                .ok_or(fatal(Base64Error::InvalidByte(byte)))?;
+
+            bits = bits << 6 | u32::from(value);
+            chars += 1;
+            pos += 1;
+
+            if chars == 4 {
+                decoded.extend(&bits.to_be_bytes()[1..]);
+
+                bits = 0;
+                chars = 0;
+            }
+
+            needed = 4 - usize::from(chars);
+        }
+
+        if decoded.is_empty() {
+            if !eof {
+                return Err(DError::Incomplete {
+                    needed: Size::new(needed),
+                });
+            }
+
+            if pos < src.len() || (chars > 0 && self.padding) {
+                return Err(fatal(Base64Error::Truncated));
+            }
+
+            if chars == 0 {
+                return Err(DError::Eof);
+            }
+
+            Self::partial(&mut decoded, bits, chars)?;
+
+            bits = 0;
+            chars = 0;
+        }
+
+        *src = &src[pos..];
+        self.bits = bits;
+        self.chars = chars;
+
+        Ok(decoded)
+    }
+
+    /// Add the bytes of a partial quantum of `chars` characters, rejecting
+    /// any bits left over.
+    fn partial(decoded: &mut Decoded, bits: u32, chars: u8) -> Result<(), DError<Base64Error>> {
+        let (bytes, rest) = match chars {
+            2 => (&(bits >> 4).to_be_bytes()[3..], bits & 0xf),
+            3 => (&(bits >> 2).to_be_bytes()[2..], bits & 0x3),
+            _ => {
+                return Err(DError::Fatal {
+                    error: Base64Error::Truncated,
+                })
+            }
+        };
+
+        if rest != 0 {
+            return Err(DError::Fatal {
+                error: Base64Error::NonCanonical,
+            });
+        }
+
+        decoded.extend(bytes);
+
+        Ok(())
+    }
+}
+
+impl Decoder for Base64 {
+    type Item<'src> = Decoded;
+    type Error = Base64Error;
+
+    #[inline]
+    fn hint(&self) -> Size {
+        Size::new(4 - usize::from(self.chars))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> SizeHint {
+        SizeHint::at_least(self.hint())
+    }
+
+    #[inline]
+    fn decode<'s>(&mut self, src: &mut &'s [u8]) -> DResult<'s, Self> {
+        self.next(src, false)
+    }
+
+    #[inline]
+    fn decode_eof<'s>(&mut self, src: &mut &'s [u8]) -> DResult<'s, Self> {
+        self.next(src, true)
+    }
+}
+
+impl Encoder<[u8]> for Base64 {
+    type Error = ::core::convert::Infallible;
+
+    #[inline]
+    fn encode_hint(&self, item: &[u8]) -> Size {
+        Size::new((usize::from(self.pending_len) + item.len()) / 3 * 4)
+    }
+
+    fn encode(&mut self, item: &[u8], dst: &mut &mut [u8]) -> EResult<Self::Error> {
+        let pending = usize::from(self.pending_len);
+        let total = pending + item.len();
+        let len = total / 3 * 4;
+
+        if dst.len() < len {
+            return Err(EError::Full {
+                needed: Size::new(len - dst.len()),
+            });
+        }
+
+        let (out, rest) = core::mem::take(dst).split_at_mut(len);
+        let mut bytes = self.pending[..pending].iter().chain(item).copied();
+
+        for chars in out.chunks_exact_mut(4) {
+            let quantum = [0; 3].map(|_| bytes.next().unwrap_or_default());
+
+            chars.copy_from_slice(&self.quantum(quantum));
+        }
+
+        let mut carried = [0; 2];
+        let carried_len = total % 3;
+
+        for (slot, byte) in carried.iter_mut().zip(bytes) {
+            *slot = byte;
+        }
+
+        self.pending = carried;
+        self.pending_len = carried_len as u8;
+        *dst = rest;
+
+        Ok(())
+    }
+}
+
+/// Type for errors that may occur while decoding base64 text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Base64Error {
+    /// A byte is not in the alphabet.
+    InvalidByte(u8),
+    /// Padding is disabled, or does not end a partial quantum.
+    InvalidPadding,
+    /// The bits left over in a partial quantum are not zero.
+    NonCanonical,
+    /// The text ends with a partial quantum that is too short, or that is
+    /// missing its padding.
+    Truncated,
+}
+
+impl fmt::Display for Base64Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidByte(byte) => write!(f, "invalid base64 byte: {byte:#04x}"),
+            Self::InvalidPadding => f.write_str("invalid base64 padding"),
+            Self::NonCanonical => f.write_str("non-canonical base64"),
+            Self::Truncated => f.write_str("truncated base64"),
+        }
+    }
+}
+
+impl core::error::Error for Base64Error {}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use std::vec::Vec;
+
+    use super::*;
+
+    fn encode(mut base64: Base64, bytes: &[u8]) -> Vec<u8> {
+        let mut buf = [0; 64];
+        let mut dst = &mut buf[..];
+
+        base64.encode(bytes, &mut dst).unwrap();
+        base64.finish(&mut dst).unwrap();
+
+        let len = 64 - dst.len();
+
+        buf[..len].to_vec()
+    }
+
+    fn decode(mut base64: Base64, text: &[u8]) -> Result<Vec<u8>, DError<Base64Error>> {
+        let mut src = text;
+        let mut bytes = Vec::new();
+
+        loop {
+            match base64.decode_eof(&mut src) {
+                Ok(decoded) => bytes.extend_from_slice(&decoded),
+                Err(DError::Eof) => return Ok(bytes),
+                Err(error) => return Err(error),
+            }
+        }
+    }
+
+    fn fatal(error: Base64Error) -> Result<Vec<u8>, DError<Base64Error>> {
+        Err(DError::Fatal { error })
+    }
+
+    #[test]
+    fn rfc_4648_vectors() {
+        let cases: [(&[u8], &[u8]); 7] = [
+            (b"", b""),
+            (b"f", b"Zg=="),
+            (b"fo", b"Zm8="),
+            (b"foo", b"Zm9v"),
+            (b"foob", b"Zm9vYg=="),
+            (b"fooba", b"Zm9vYmE="),
+            (b"foobar", b"Zm9vYmFy"),
+        ];
+
+        let padded = Base64::default().with_padding(true);
+
+        for (bytes, text) in cases {
+            let unpadded = text.strip_suffix(b"==").or(text.strip_suffix(b"="));
+            let unpadded = unpadded.unwrap_or(text);
+
+            assert_eq!(encode(padded.clone(), bytes), text);
+            assert_eq!(decode(padded.clone(), text).as_deref(), Ok(bytes));
+            assert_eq!(encode(Base64::default(), bytes), unpadded);
+            assert_eq!(decode(Base64::default(), unpadded).as_deref(), Ok(bytes));
+        }
+    }
+
+    #[test]
+    fn alphabets() {
+        assert_eq!(encode(Base64::new(Alphabet::Standard), b"\xfb\xff"), b"+/8");
+        assert_eq!(encode(Base64::new(Alphabet::UrlSafe), b"\xfb\xff"), b"-_8");
+        assert_eq!(
+            decode(Base64::new(Alphabet::UrlSafe), b"-_8").as_deref(),
+            Ok(&b"\xfb\xff"[..])
+        );
+        assert_eq!(
+            decode(Base64::new(Alphabet::UrlSafe), b"+/8"),
+            fatal(Base64Error::InvalidByte(b'+'))
+        );
+    }
+
+    #[test]
+    fn streaming() {
+        let mut base64 = Base64::default();
+        let mut buf = [0; 16];
+        let mut dst = &mut buf[..];
+
+        // Bytes are carried over until a quantum is complete.
+        base64.encode(b"fo", &mut dst).unwrap();
+        assert_eq!(dst.len(), 16);
+        base64.encode(b"obar", &mut dst).unwrap();
+        base64.finish(&mut dst).unwrap();
+        assert_eq!(dst.len(), 8);
+        assert_eq!(&buf[..8], b"Zm9vYmFy");
+
+        let mut base64 = Base64::default();
+
+        assert_eq!(
+            base64.decode(&mut &b"Zm"[..]),
+            Err(DError::Incomplete {
+                needed: Size::new(2)
+            })
+        );
+
+        let mut src = &b"Zm9vYg"[..];
+
+        assert_eq!(base64.decode(&mut src).as_deref(), Ok(&b"foo"[..]));
+        assert!(src.is_empty());
+        assert_eq!(base64.hint(), Size::new(2));
+        assert_eq!(base64.decode_eof(&mut src).as_deref(), Ok(&b"b"[..]));
+        assert_eq!(base64.decode_eof(&mut src), Err(DError::Eof));
+
+        // Padded text may be concatenated.
+        let padded = Base64::default().with_padding(true);
+
+        assert_eq!(decode(padded, b"Zg==Zg==").as_deref(), Ok(&b"ff"[..]));
+    }
+
+    #[test]
+    fn malformed() {
+        let padded = Base64::default().with_padding(true);
+
+        assert_eq!(
+            decode(Base64::default(), b"Zm9v!"),
+            fatal(Base64Error::InvalidByte(b'!'))
+        );
+        assert_eq!(
+            decode(Base64::default(), b"Zg=="),
+            fatal(Base64Error::InvalidPadding)
+        );
+        assert_eq!(
+            decode(padded.clone(), b"Z==="),
+            fatal(Base64Error::InvalidPadding)
+        );
+        assert_eq!(
+            decode(padded.clone(), b"Zg=A"),
+            fatal(Base64Error::InvalidPadding)
+        );
+        assert_eq!(
+            decode(padded.clone(), b"Zh=="),
+            fatal(Base64Error::NonCanonical)
+        );
+        assert_eq!(decode(padded, b"Zg"), fatal(Base64Error::Truncated));
+        assert_eq!(
+            decode(Base64::default(), b"Z"),
+            fatal(Base64Error::Truncated)
+        );
+    }
+}
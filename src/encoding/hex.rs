@@ -0,0 +1,208 @@
+use core::fmt;
+
+use super::Decoded;
+use crate::decoder::{Decoder, Error as DError, Result as DResult};
+use crate::encoder::{Encoder, Error as EError, Result as EResult};
+use crate::{Size, SizeHint};
+
+/// The case of hexadecimal digits above 9.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Case {
+    /// Lowercase digits, `a` to `f`.
+    #[default]
+    Lower,
+    /// Uppercase digits, `A` to `F`.
+    Upper,
+}
+
+impl Case {
+    /// Returns the digit for the lowest 4 bits of `value`.
+    #[inline]
+    #[must_use]
+    pub const fn encode(self, value: u8) -> u8 {
+        let digits = match self {
+            Self::Lower => b"0123456789abcdef",
+            Self::Upper => b"0123456789ABCDEF",
+        };
+
+        digits[(value & 0xf) as usize]
+    }
+}
+
+/// A codec for hexadecimal text, with two digits for each byte.
+///
+/// Decoding accepts digits in either case, yielding as many bytes as are
+/// available, at most [`Decoded::CAPACITY`] at a time. An odd digit at the
+/// end of the source is carried over into the next call, once some bytes
+/// have been decoded; otherwise the source is left as is.
+///
+/// Encoding writes digits in [`case`](Hex::case).
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Hex {
+    /// The case of digits written when encoding.
+    pub case: Case,
+    /// Whether ASCII whitespace is skipped when decoding, including
+    /// between the digits of a byte.
+    ///
+    /// This is disabled by default.
+    pub whitespace: bool,
+    /// The first digit of the byte being decoded.
+    high: Option<u8>,
+}
+
+impl Hex {
+    /// Create a new codec for hexadecimal text, written in `case`.
+    #[inline]
+    #[must_use]
+    pub const fn new(case: Case) -> Hex {
+        Hex {
+            case,
+            whitespace: false,
+            high: None,
+        }
+    }
+
+    /// Set whether ASCII whitespace is skipped when decoding.
+    #[inline]
+    #[must_use]
+    pub const fn with_whitespace(mut self, whitespace: bool) -> Hex {
+        self.whitespace = whitespace;
+        self
+    }
+
+    /// Returns the value of the digit `byte`, in either case.
+    #[inline]
+    pub(super) const fn digit(byte: u8) -> Option<u8> {
+        match byte {
+            b'0'..=b'9' => Some(byte - b'0'),
+            b'a'..=b'f' => Some(byte - b'a' + 10),
+            b'A'..=b'F' => Some(byte - b'A' + 10),
+            _ => None,
+        }
+    }
+
+    fn next<'s>(&mut self, src: &mut &'s [u8], eof: bool) -> DResult<'s, Self> {
+        let mut high = self.high;
+        let mut decoded = Decoded::new();
+        let mut pos = 0;
+
+        while decoded.remaining_capacity() > 0 {
+            let Some(&byte) = src.get(pos) else {
+                break;
+            };
+
+            pos += 1;
+
+            if self.whitespace && byte.is_ascii_whitespace() {
+                continue;
+            }
+
+            let value = Self::digit(byte).ok_or(DError::Fatal {
+                error: HexError::InvalidByte(byte),
+            })?;
+
+            match high.take() {
+                Some(high) => decoded.extend(&[high << 4 | value]),
+                None => high = Some(value),
+            }
+        }
+
+        if decoded.is_empty() {
+            return Err(match (eof, high) {
+                (false, Some(_)) => DError::Incomplete {
+                    needed: Size::new(1),
+                },
+                (false, None) => DError::Incomplete {
+                    needed: Size::new(2),
+                },
+                (true, Some(_)) => DError::Fatal {
+                    error: HexError::Truncated,
+                },
+                (true, None) => DError::Eof,
+            });
+        }
+
+        *src = &src[pos..];
+        self.high = high;
+
+        Ok(decoded)
+    }
+}
+
+impl Decoder for Hex {
+    type Item<'src> = Decoded;
+    type Error = HexError;
+
+    #[inline]
+    fn hint(&self) -> Size {
+        Size::new(if self.high.is_some() { 1 } else { 2 })
+    }
+
+    #[inline]
+    fn size_hint(&self) -> SizeHint {
+        SizeHint::at_least(self.hint())
+    }
+
+    #[inline]
+    fn decode<'s>(&mut self, src: &mut &'s [u8]) -> DResult<'s, Self> {
+        self.next(src, false)
+    }
+
+    #[inline]
+    fn decode_eof<'s>(&mut self, src: &mut &'s [u8]) -> DResult<'s, Self> {
+        self.next(src, true)
+    }
+}
+
+impl Encoder<[u8]> for Hex {
+    type Error = ::core::convert::Infallible;
+
+    #[inline]
+    fn encode_hint(&self, item: &[u8]) -> Size {
+        Size::new(item.len().saturating_mul(2))
+    }
+
+    fn encode(&mut self, item: &[u8], dst: &mut &mut [u8]) -> EResult<Self::Error> {
+        let len = item.len().saturating_mul(2);
+
+        if dst.len() < len {
+            return Err(EError::Full {
+                needed: Size::new(len - dst.len()),
+            });
+        }
+
+        let (out, rest) = core::mem::take(dst).split_at_mut(len);
+
+        for (digits, &byte) in out.chunks_exact_mut(2).zip(item) {
+            digits[0] = self.case.encode(byte >> 4);
+            digits[1] = self.case.encode(byte);
+        }
+
+        *dst = rest;
+
+        Ok(())
+    }
+}
+
+/// Type for errors that may occur while decoding hexadecimal text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum HexError {
+    /// A byte is not a hexadecimal digit.
+    InvalidByte(u8),
+    /// The text ends with an odd digit.
+    Truncated,
+}
+
+impl fmt::Display for HexError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidByte(byte) => write!(f, "invalid hex digit: {byte:#04x}"),
+            Self::Truncated => f.write_str("odd number of hex digits"),
+        }
+    }
+}
+
+impl core::error::Error for HexError {}
@@ -0,0 +1,176 @@
+use core::fmt;
+
+use super::{hex::Hex, Decoded};
+use crate::decoder::{Decoder, Error as DError, Result as DResult};
+use crate::{Size, SizeHint};
+
+/// How to handle a `%` that does not start a valid escape.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Mode {
+    /// Reject it.
+    #[default]
+    Strict,
+    /// Keep it as is, as browsers do.
+    Lossy,
+}
+
+/// A [`Decoder`] for percent-encoded text, as in RFC 3986, yielding as
+/// many bytes as are available, at most [`Decoded::CAPACITY`] at a time.
+///
+/// An escape split at the end of the source is left there, so that it is
+/// carried into the next call along with the rest of it.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Percent {
+    /// How to handle invalid escapes.
+    pub mode: Mode,
+    /// Whether `+` decodes to a space, as in form data.
+    ///
+    /// This is disabled by default.
+    pub plus_as_space: bool,
+}
+
+impl Percent {
+    /// Create a new decoder for percent-encoded text.
+    #[inline]
+    #[must_use]
+    pub const fn new(mode: Mode) -> Percent {
+        Percent {
+            mode,
+            plus_as_space: false,
+        }
+    }
+
+    /// Set whether `+` decodes to a space.
+    #[inline]
+    #[must_use]
+    pub const fn with_plus_as_space(mut self, plus_as_space: bool) -> Percent {
+        self.plus_as_space = plus_as_space;
+        self
+    }
+
+    fn next<'s>(&mut self, src: &mut &'s [u8], eof: bool) -> DResult<'s, Self> {
+        let mut decoded = Decoded::new();
+        let mut pos = 0;
+
+        // The amount of bytes needed to finish a split escape.
+        let mut needed = 1;
+
+        while decoded.remaining_capacity() > 0 {
+            let Some(&byte) = src.get(pos) else {
+                break;
+            };
+
+            let (byte, len) = match byte {
+                b'%' => {
+                    let digits = &src[pos + 1..src.len().min(pos + 3)];
+                    let valid = digits.iter().all(|&digit| Hex::digit(digit).is_some());
+
+                    match digits {
+                        &[high, low] if valid => (
+                            Hex::digit(high).unwrap_or_default() << 4
+                                | Hex::digit(low).unwrap_or_default(),
+                            3,
+                        ),
+                        _ if valid && !eof => {
+                            needed = 2 - digits.len();
+                            break;
+                        }
+                        _ if self.mode == Mode::Lossy => (b'%', 1),
+                        _ => {
+                            return Err(DError::Fatal {
+                                error: PercentError {
+                                    offset: pos,
+                                    truncated: valid,
+                                },
+                            })
+                        }
+                    }
+                }
+                b'+' if self.plus_as_space => (b' ', 1),
+                byte => (byte, 1),
+            };
+
+            decoded.extend(&[byte]);
+            pos += len;
+        }
+
+        if decoded.is_empty() {
+            return Err(if eof {
+                DError::Eof
+            } else {
+                DError::Incomplete {
+                    needed: Size::new(needed),
+                }
+            });
+        }
+
+        *src = &src[pos..];
+
+        Ok(decoded)
+    }
+}
+
+impl Decoder for Percent {
+    type Item<'src> = Decoded;
+    type Error = PercentError;
+
+    #[inline]
+    fn hint(&self) -> Size {
+        Size::new(1)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> SizeHint {
+        SizeHint::at_least(self.hint())
+    }
+
+    #[inline]
+    fn decode<'s>(&mut self, src: &mut &'s [u8]) -> DResult<'s, Self> {
+        self.next(src, false)
+    }
+
+    #[inline]
+    fn decode_eof<'s>(&mut self, src: &mut &'s [u8]) -> DResult<'s, Self> {
+        self.next(src, true)
+    }
+}
+
+/// Type for errors that may occur while decoding percent-encoded text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct PercentError {
+    offset: usize,
+    truncated: bool,
+}
+
+impl PercentError {
+    /// Returns the offset into the source of the invalid escape.
+    #[inline]
+    #[must_use]
+    pub const fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// Returns whether the escape is cut short by the end of the text,
+    /// rather than having an invalid digit.
+    #[inline]
+    #[must_use]
+    pub const fn is_truncated(&self) -> bool {
+        self.truncated
+    }
+}
+
+impl fmt::Display for PercentError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.truncated {
+            write!(f, "truncated percent escape at byte {}", self.offset)
+        } else {
+            write!(f, "invalid percent escape at byte {}", self.offset)
+        }
+    }
+}
+
+impl core::error::Error for PercentError {}
@@ -10,6 +10,8 @@ pub const NE: NativeEndian = NativeEndian;
 ///
 /// Calling [`Default::default`] for [`Endian`] returns [`Endian::NATIVE`].
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[must_use]
 pub enum Endian {
     /// Little endian byte order.
@@ -52,7 +54,6 @@ impl Endian {
     /// - [`Endian::Little`] becomes [`Endian::Big`].
     /// - [`Endian::Big`] becomes [`Endian::Little`].
     #[inline]
-    #[must_use]
     pub const fn to_inverse(self) -> Endian {
         match self {
             Endian::Little => Endian::Big,
@@ -98,6 +99,7 @@ impl ByteOrder for Endian {
 
 /// Type for little endian byte order.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct LittleEndian;
 
 impl ByteOrder for LittleEndian {
@@ -109,6 +111,7 @@ impl ByteOrder for LittleEndian {
 
 /// Type for big endian byte order.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct BigEndian;
 
 impl ByteOrder for BigEndian {
@@ -120,6 +123,7 @@ impl ByteOrder for BigEndian {
 
 /// Type for native endian byte order.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct NativeEndian;
 
 impl ByteOrder for NativeEndian {
@@ -0,0 +1,301 @@
+use core::{fmt, marker::PhantomData};
+
+use crate::decoder::{Decoder, Error as DError, Result as DResult};
+use crate::encoder::{Encoder, Result as EResult};
+use crate::{Size, SizeHint};
+
+/// Trait for C-like enums with an integer discriminant for each variant.
+///
+/// This is usually implemented with the
+/// [`enumeration!`](crate::enumeration!) macro.
+pub trait Enumeration: Sized {
+    /// The integer type of the discriminants.
+    type Repr: Copy + Into<i128>;
+
+    /// The name of the enum, for errors.
+    const NAME: &'static str;
+
+    /// Returns the variant with the discriminant `repr`, if any.
+    fn from_discriminant(repr: Self::Repr) -> Option<Self>;
+
+    /// Returns the discriminant of the variant.
+    fn discriminant(&self) -> Self::Repr;
+
+    /// Returns the variant with the discriminant `repr`, or an error naming
+    /// the enum and the invalid discriminant.
+    #[inline]
+    fn try_from_discriminant(repr: Self::Repr) -> Result<Self, DiscriminantError> {
+        Self::from_discriminant(repr).ok_or(DiscriminantError {
+            name: Self::NAME,
+            value: repr.into(),
+        })
+    }
+}
+
+/// A [`Decoder`] and [`Encoder`] for an [`Enumeration`], reading its
+/// discriminant with another decoder.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Enum<T, D> {
+    /// The decoder for discriminants.
+    pub decoder: D,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T: Enumeration, D> Enum<T, D> {
+    /// Create a new decoder for `T`, reading its discriminant with
+    /// `decoder`.
+    #[inline]
+    #[must_use]
+    pub const fn new(decoder: D) -> Enum<T, D> {
+        Enum {
+            decoder,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<T: Enumeration, D: Default> Default for Enum<T, D> {
+    #[inline]
+    fn default() -> Self {
+        Enum::new(D::default())
+    }
+}
+
+impl<T, D> Enum<T, D>
+where
+    T: Enumeration,
+    D: for<'a> Decoder<Item<'a> = T::Repr>,
+{
+    fn next<'s>(&mut self, src: &mut &'s [u8], eof: bool) -> DResult<'s, Self> {
+        let mut rest = *src;
+        let repr = if eof {
+            self.decoder.decode_eof(&mut rest)
+        } else {
+            self.decoder.decode(&mut rest)
+        }
+        .map_err(|error| error.map(EnumError::Decoder))?;
+
+        let item = T::try_from_discriminant(repr).map_err(|error| DError::Fatal {
+            error: EnumError::Discriminant(error),
+        })?;
+
+        *src = rest;
+
+        Ok(item)
+    }
+}
+
+impl<T, D> Decoder for Enum<T, D>
+where
+    T: Enumeration,
+    D: for<'a> Decoder<Item<'a> = T::Repr>,
+{
+    type Item<'src> = T;
+    type Error = EnumError<D::Error>;
+
+    #[inline]
+    fn hint(&self) -> Size {
+        self.decoder.hint()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> SizeHint {
+        self.decoder.size_hint()
+    }
+
+    #[inline]
+    fn decode<'s>(&mut self, src: &mut &'s [u8]) -> DResult<'s, Self> {
+        self.next(src, false)
+    }
+
+    #[inline]
+    fn decode_eof<'s>(&mut self, src: &mut &'s [u8]) -> DResult<'s, Self> {
+        self.next(src, true)
+    }
+}
+
+impl<T, D> Encoder<T> for Enum<T, D>
+where
+    T: Enumeration,
+    D: Encoder<T::Repr>,
+{
+    type Error = D::Error;
+
+    #[inline]
+    fn encode_hint(&self, item: &T) -> Size {
+        self.decoder.encode_hint(&item.discriminant())
+    }
+
+    #[inline]
+    fn encode(&mut self, item: &T, dst: &mut &mut [u8]) -> EResult<Self::Error> {
+        self.decoder.encode(&item.discriminant(), dst)
+    }
+}
+
+/// Type for errors that may occur while converting a discriminant into an
+/// [`Enumeration`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct DiscriminantError {
+    name: &'static str,
+    value: i128,
+}
+
+impl DiscriminantError {
+    /// Returns the name of the enum.
+    #[inline]
+    #[must_use]
+    pub const fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Returns the invalid discriminant.
+    #[inline]
+    #[must_use]
+    pub const fn value(&self) -> i128 {
+        self.value
+    }
+}
+
+impl fmt::Display for DiscriminantError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid discriminant {} for `{}`", self.value, self.name)
+    }
+}
+
+impl core::error::Error for DiscriminantError {}
+
+/// Type for errors that may occur while decoding an [`Enumeration`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum EnumError<E> {
+    /// The decoder for discriminants failed.
+    Decoder(E),
+    /// The discriminant does not match any variant.
+    Discriminant(DiscriminantError),
+}
+
+impl<E: fmt::Display> fmt::Display for EnumError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Decoder(error) => fmt::Display::fmt(error, f),
+            Self::Discriminant(error) => fmt::Display::fmt(error, f),
+        }
+    }
+}
+
+impl<E: fmt::Display + fmt::Debug> core::error::Error for EnumError<E> {}
+
+/// Define C-like enums with an integer discriminant for each variant.
+///
+/// This implements [`Enumeration`], conversions to and from the integer
+/// type, with [`TryFrom`] failing with a [`DiscriminantError`], and
+/// [`Layout`](crate::frame::Layout), so enums can be fields of
+/// [`frame!`](crate::frame!) structs. The integer type must be one of the
+/// integers up to 64 bits.
+///
+/// ```
+/// kodek::enumeration! {
+///     #[derive(Debug, Clone, Copy, PartialEq)]
+///     pub enum Opcode: u8 {
+///         Read = 1,
+///         Write = 2,
+///     }
+/// }
+///
+/// use kodek::{endian::BE, enumeration::Enum, frame::Frame, Decoder};
+///
+/// let mut decoder = Enum::<Opcode, _>::new(Frame::<u8, _>::new(BE));
+///
+/// assert_eq!(decoder.decode(&mut &[2][..]).unwrap(), Opcode::Write);
+/// assert!(decoder.decode(&mut &[7][..]).is_err());
+///
+/// let error = Opcode::try_from(7).unwrap_err();
+///
+/// assert_eq!(error.to_string(), "invalid discriminant 7 for `Opcode`");
+/// ```
+#[macro_export]
+macro_rules! enumeration {
+    ($(
+        $(#[$attr:meta])*
+        $vis:vis enum $name:ident : $repr:ident {
+            $(
+                $(#[$variant_attr:meta])*
+                $variant:ident = $value:expr
+            ),* $(,)?
+        }
+    )*) => {
+        $(
+            $(#[$attr])*
+            #[repr($repr)]
+            $vis enum $name {
+                $(
+                    $(#[$variant_attr])*
+                    $variant = $value,
+                )*
+            }
+
+            impl $crate::enumeration::Enumeration for $name {
+                type Repr = $repr;
+
+                const NAME: &'static str = ::core::stringify!($name);
+
+                #[inline]
+                fn from_discriminant(repr: $repr) -> ::core::option::Option<Self> {
+                    $(
+                        if repr == $value {
+                            return ::core::option::Option::Some($name::$variant);
+                        }
+                    )*
+
+                    ::core::option::Option::None
+                }
+
+                #[inline]
+                fn discriminant(&self) -> $repr {
+                    match self {
+                        $($name::$variant => $value,)*
+                    }
+                }
+            }
+
+            impl ::core::convert::TryFrom<$repr> for $name {
+                type Error = $crate::enumeration::DiscriminantError;
+
+                #[inline]
+                fn try_from(repr: $repr) -> ::core::result::Result<Self, Self::Error> {
+                    <$name as $crate::enumeration::Enumeration>::try_from_discriminant(repr)
+                }
+            }
+
+            impl ::core::convert::From<$name> for $repr {
+                #[inline]
+                fn from(value: $name) -> $repr {
+                    $crate::enumeration::Enumeration::discriminant(&value)
+                }
+            }
+
+            impl $crate::frame::Layout for $name {
+                const SIZE: usize = <$repr as $crate::frame::Layout>::SIZE;
+
+                #[inline]
+                fn read(
+                    bytes: &[u8],
+                    endian: $crate::endian::Endian,
+                ) -> ::core::result::Result<Self, $crate::frame::LayoutError> {
+                    let repr = <$repr as $crate::frame::Layout>::read(bytes, endian)?;
+
+                    <$name as $crate::enumeration::Enumeration>::from_discriminant(repr)
+                        .ok_or($crate::frame::LayoutError::new("invalid discriminant"))
+                }
+
+                #[inline]
+                fn write(&self, bytes: &mut [u8], endian: $crate::endian::Endian) {
+                    let repr = $crate::enumeration::Enumeration::discriminant(self);
+
+                    <$repr as $crate::frame::Layout>::write(&repr, bytes, endian);
+                }
+            }
+        )*
+    };
+}
@@ -0,0 +1,379 @@
+use core::fmt;
+
+use crate::decoder::{Decoder, Error as DError, Result as DResult};
+use crate::encoder::{Encoder, Error as EError, Result as EResult};
+use crate::endian::{ByteOrder, Endian, NativeEndian};
+use crate::{Size, SizeHint};
+
+/// Trait for types with a fixed-size binary layout.
+///
+/// This is usually implemented with the [`frame!`](crate::frame!) macro.
+pub trait Layout: Sized {
+    /// The amount of bytes in the layout.
+    const SIZE: usize;
+
+    /// Read a value from exactly [`Layout::SIZE`] bytes.
+    ///
+    /// Implementations may panic if `bytes` is of any other length.
+    fn read(bytes: &[u8], endian: Endian) -> Result<Self, LayoutError>;
+
+    /// Write a value into exactly [`Layout::SIZE`] bytes.
+    ///
+    /// Implementations may panic if `bytes` is of any other length.
+    fn write(&self, bytes: &mut [u8], endian: Endian);
+}
+
+macro_rules! define {
+    ($($ty:ident)*) => {
+        $(
+            impl Layout for ::core::primitive::$ty {
+                const SIZE: usize = ::core::mem::size_of::<::core::primitive::$ty>();
+
+                #[inline]
+                fn read(bytes: &[u8], endian: Endian) -> Result<Self, LayoutError> {
+                    let bytes = bytes.try_into().unwrap();
+
+                    Ok(match endian {
+                        Endian::Little => ::core::primitive::$ty::from_le_bytes(bytes),
+                        Endian::Big => ::core::primitive::$ty::from_be_bytes(bytes),
+                    })
+                }
+
+                #[inline]
+                fn write(&self, bytes: &mut [u8], endian: Endian) {
+                    bytes.copy_from_slice(&match endian {
+                        Endian::Little => self.to_le_bytes(),
+                        Endian::Big => self.to_be_bytes(),
+                    });
+                }
+            }
+        )*
+    };
+}
+
+define! {
+    u8 u16 u32 u64 u128
+    i8 i16 i32 i64 i128
+    f32 f64
+}
+
+impl Layout for bool {
+    const SIZE: usize = 1;
+
+    #[inline]
+    fn read(bytes: &[u8], endian: Endian) -> Result<Self, LayoutError> {
+        match u8::read(bytes, endian)? {
+            0 => Ok(false),
+            1 => Ok(true),
+            _ => Err(LayoutError::new("invalid bits for a bool")),
+        }
+    }
+
+    #[inline]
+    fn write(&self, bytes: &mut [u8], endian: Endian) {
+        u8::from(*self).write(bytes, endian);
+    }
+}
+
+impl Layout for char {
+    const SIZE: usize = 4;
+
+    #[inline]
+    fn read(bytes: &[u8], endian: Endian) -> Result<Self, LayoutError> {
+        char::try_from(u32::read(bytes, endian)?)
+            .map_err(|_| LayoutError::new("invalid bits for a char"))
+    }
+
+    #[inline]
+    fn write(&self, bytes: &mut [u8], endian: Endian) {
+        u32::from(*self).write(bytes, endian);
+    }
+}
+
+impl<T: Layout + Copy + Default, const N: usize> Layout for [T; N] {
+    const SIZE: usize = T::SIZE * N;
+
+    #[inline]
+    fn read(bytes: &[u8], endian: Endian) -> Result<Self, LayoutError> {
+        let mut items = [T::default(); N];
+
+        for (i, item) in items.iter_mut().enumerate() {
+            let offset = i * T::SIZE;
+
+            *item = T::read(&bytes[offset..offset + T::SIZE], endian).map_err(|e| e.at(offset))?;
+        }
+
+        Ok(items)
+    }
+
+    #[inline]
+    fn write(&self, bytes: &mut [u8], endian: Endian) {
+        for (i, item) in self.iter().enumerate() {
+            let offset = i * T::SIZE;
+
+            item.write(&mut bytes[offset..offset + T::SIZE], endian);
+        }
+    }
+}
+
+/// A binary [`Decoder`] and [`Encoder`] for a type with a fixed-size
+/// layout, in a specified byte order.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Frame<T, B: ByteOrder = NativeEndian> {
+    /// The byte order for fields without one of their own.
+    pub byte_order: B,
+    _marker: core::marker::PhantomData<fn() -> T>,
+}
+
+impl<T: Layout, B: ByteOrder> Frame<T, B> {
+    /// Create a new binary decoder for `T`.
+    #[inline]
+    #[must_use]
+    pub const fn new(byte_order: B) -> Frame<T, B> {
+        Frame {
+            byte_order,
+            _marker: core::marker::PhantomData,
+        }
+    }
+}
+
+impl<T: Layout, B: ByteOrder + Default> Default for Frame<T, B> {
+    #[inline]
+    fn default() -> Self {
+        Frame::new(B::default())
+    }
+}
+
+impl<T: Layout, B: ByteOrder> Decoder for Frame<T, B> {
+    type Item<'src> = T;
+    type Error = LayoutError;
+
+    #[inline]
+    fn hint(&self) -> Size {
+        Size::new(T::SIZE)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> SizeHint {
+        SizeHint::exact(self.hint())
+    }
+
+    #[inline]
+    fn decode<'s>(&mut self, src: &mut &'s [u8]) -> DResult<'s, Self> {
+        let Some((bytes, rest)) = src.split_at_checked(T::SIZE) else {
+            return Err(DError::Incomplete {
+                needed: Size::new(T::SIZE - src.len()),
+            });
+        };
+
+        let item =
+            T::read(bytes, self.byte_order.endian()).map_err(|error| DError::Fatal { error })?;
+
+        *src = rest;
+
+        Ok(item)
+    }
+}
+
+impl<T: Layout, B: ByteOrder> Encoder<T> for Frame<T, B> {
+    type Error = ::core::convert::Infallible;
+
+    #[inline]
+    fn encode_hint(&self, _: &T) -> Size {
+        Size::new(T::SIZE)
+    }
+
+    #[inline]
+    fn encode(&mut self, item: &T, dst: &mut &mut [u8]) -> EResult<Self::Error> {
+        if dst.len() < T::SIZE {
+            return Err(EError::Full {
+                needed: Size::new(T::SIZE - dst.len()),
+            });
+        }
+
+        let (bytes, rest) = core::mem::take(dst).split_at_mut(T::SIZE);
+
+        item.write(bytes, self.byte_order.endian());
+
+        *dst = rest;
+
+        Ok(())
+    }
+}
+
+/// Type for errors that may occur while reading a fixed-size layout.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct LayoutError {
+    reason: &'static str,
+    field: Option<&'static str>,
+    offset: usize,
+}
+
+impl LayoutError {
+    /// Create a new [`LayoutError`] for an invalid value.
+    #[inline]
+    #[must_use]
+    pub const fn new(reason: &'static str) -> LayoutError {
+        LayoutError {
+            reason,
+            field: None,
+            offset: 0,
+        }
+    }
+
+    /// Returns why the value is invalid.
+    #[inline]
+    #[must_use]
+    pub const fn reason(&self) -> &'static str {
+        self.reason
+    }
+
+    /// Returns the name of the innermost field with the invalid value, if any.
+    #[inline]
+    #[must_use]
+    pub const fn field(&self) -> Option<&'static str> {
+        self.field
+    }
+
+    /// Returns the offset into the layout of the invalid value.
+    #[inline]
+    #[must_use]
+    pub const fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// Move the error `offset` bytes further into an enclosing layout.
+    #[inline]
+    #[must_use]
+    pub const fn at(mut self, offset: usize) -> LayoutError {
+        self.offset += offset;
+        self
+    }
+
+    /// Attribute the error to `field`, at `offset` bytes into an enclosing layout.
+    ///
+    /// The innermost field is kept.
+    #[inline]
+    #[must_use]
+    pub const fn within(mut self, field: &'static str, offset: usize) -> LayoutError {
+        if self.field.is_none() {
+            self.field = Some(field);
+        }
+
+        self.at(offset)
+    }
+}
+
+impl fmt::Display for LayoutError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.reason)?;
+
+        if let Some(field) = self.field {
+            write!(f, " in field `{field}`")?;
+        }
+
+        write!(f, " at byte {}", self.offset)
+    }
+}
+
+impl core::error::Error for LayoutError {}
+
+/// Define structs with a fixed-size binary layout.
+///
+/// Each field is read in the byte order of the [`Frame`] decoder,
+/// unless it is followed by `as` and a [`ByteOrder`] of its own.
+/// Field types must implement [`Layout`], which includes the primitives,
+/// arrays, and other structs defined by this macro.
+///
+/// ```
+/// kodek::frame! {
+///     #[derive(Debug, Clone, Copy, PartialEq)]
+///     pub struct Header {
+///         pub kind: u8,
+///         pub len: u16 as kodek::endian::BE,
+///         pub flags: [u8; 2],
+///     }
+/// }
+///
+/// use kodek::{frame::Frame, Decoder, endian::LE};
+///
+/// let mut src = &[1, 0, 5, 0xAA, 0xBB][..];
+/// let header = Frame::<Header, _>::new(LE).decode(&mut src).unwrap();
+///
+/// assert_eq!(header, Header { kind: 1, len: 5, flags: [0xAA, 0xBB] });
+/// ```
+#[macro_export]
+macro_rules! frame {
+    (@endian $endian:ident) => {
+        $endian
+    };
+    (@endian $endian:ident, $order:expr) => {
+        $crate::endian::ByteOrder::endian(&$order)
+    };
+    ($(
+        $(#[$attr:meta])*
+        $vis:vis struct $name:ident {
+            $(
+                $(#[$field_attr:meta])*
+                $field_vis:vis $field:ident : $ty:ty $(as $order:expr)?
+            ),* $(,)?
+        }
+    )*) => {
+        $(
+            $(#[$attr])*
+            $vis struct $name {
+                $(
+                    $(#[$field_attr])*
+                    $field_vis $field: $ty,
+                )*
+            }
+
+            impl $crate::frame::Layout for $name {
+                const SIZE: usize = 0 $(+ <$ty as $crate::frame::Layout>::SIZE)*;
+
+                #[inline]
+                fn read(
+                    bytes: &[u8],
+                    endian: $crate::endian::Endian,
+                ) -> ::core::result::Result<Self, $crate::frame::LayoutError> {
+                    let mut offset = 0;
+
+                    $(
+                        let size = <$ty as $crate::frame::Layout>::SIZE;
+                        let $field = <$ty as $crate::frame::Layout>::read(
+                            &bytes[offset..offset + size],
+                            $crate::frame!(@endian endian $(, $order)?),
+                        )
+                        .map_err(|e| e.within(::core::stringify!($field), offset))?;
+
+                        offset += size;
+                    )*
+
+                    let _ = (offset, endian);
+
+                    ::core::result::Result::Ok($name { $($field),* })
+                }
+
+                #[inline]
+                fn write(&self, bytes: &mut [u8], endian: $crate::endian::Endian) {
+                    let mut offset = 0;
+
+                    $(
+                        let size = <$ty as $crate::frame::Layout>::SIZE;
+
+                        <$ty as $crate::frame::Layout>::write(
+                            &self.$field,
+                            &mut bytes[offset..offset + size],
+                            $crate::frame!(@endian endian $(, $order)?),
+                        );
+
+                        offset += size;
+                    )*
+
+                    let _ = (offset, endian);
+                }
+            }
+        )*
+    };
+}
@@ -0,0 +1,2039 @@
+use core::{fmt, str::Utf8Error};
+
+use crate::decoder::{Decoder, Error as DError, Result as DResult};
+use crate::encoder::{Encoder, Error as EError, Result as EResult};
+use crate::endian::{BigEndian, ByteOrder, Endian};
+use crate::{Size, SizeHint};
+
+/// Returns the index of the first `byte` in `haystack`.
+#[inline]
+fn find_byte(haystack: &[u8], byte: u8) -> Option<usize> {
+    #[cfg(feature = "memchr")]
+    {
+        memchr::memchr(byte, haystack)
+    }
+
+    #[cfg(not(feature = "memchr"))]
+    {
+        haystack.iter().position(|&b| b == byte)
+    }
+}
+
+/// Returns the index of the first `needle` in `haystack`.
+#[inline]
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    match needle {
+        [] => Some(0),
+        [byte] => find_byte(haystack, *byte),
+        #[cfg(feature = "memchr")]
+        needle => memchr::memmem::find(haystack, needle),
+        #[cfg(not(feature = "memchr"))]
+        needle => haystack
+            .windows(needle.len())
+            .position(|window| window == needle),
+    }
+}
+
+/// A [`Decoder`] and [`Encoder`] for `\n` terminated lines of bytes.
+///
+/// The terminator is not part of decoded lines. At the end of a stream,
+/// any bytes after the last terminator are decoded as a final line.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Lines {
+    /// Whether lines are terminated by `\r\n` rather than `\n`.
+    ///
+    /// When decoding, a `\r` before the `\n` is stripped if present.
+    pub crlf: bool,
+    /// The maximum length of a line, excluding the terminator.
+    ///
+    /// Longer lines are a fatal error rather than incomplete.
+    pub max_len: usize,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    searched: usize,
+}
+
+impl Lines {
+    /// Create a new decoder for lines.
+    #[inline]
+    #[must_use]
+    pub const fn new() -> Lines {
+        Lines {
+            crlf: false,
+            max_len: usize::MAX,
+            searched: 0,
+        }
+    }
+
+    /// Set whether lines are terminated by `\r\n` rather than `\n`.
+    #[inline]
+    #[must_use]
+    pub const fn with_crlf(mut self, crlf: bool) -> Lines {
+        self.crlf = crlf;
+        self
+    }
+
+    /// Set the maximum length of a line.
+    #[inline]
+    #[must_use]
+    pub const fn with_max_len(mut self, max_len: usize) -> Lines {
+        self.max_len = max_len;
+        self
+    }
+
+    #[inline]
+    const fn terminator(&self) -> &'static [u8] {
+        if self.crlf {
+            b"\r\n"
+        } else {
+            b"\n"
+        }
+    }
+
+    #[inline]
+    fn strip<'s>(&self, line: &'s [u8]) -> Result<&'s [u8], LinesError> {
+        let line = match line {
+            [line @ .., b'\r'] if self.crlf => line,
+            line => line,
+        };
+
+        if line.len() > self.max_len {
+            Err(LinesError::TooLong)
+        } else {
+            Ok(line)
+        }
+    }
+
+    fn next<'s>(&mut self, src: &mut &'s [u8], eof: bool) -> DResult<'s, Self> {
+        let start = self.searched.min(src.len());
+
+        let (line, rest) = match find_byte(&src[start..], b'\n') {
+            Some(i) => (&src[..start + i], &src[start + i + 1..]),
+            None if eof && src.is_empty() => return Err(DError::Eof),
+            None if eof => (*src, &[][..]),
+            None => {
+                // A line that is already too long cannot become valid.
+                if src.len() > self.max_len.saturating_add(usize::from(self.crlf)) {
+                    self.searched = 0;
+
+                    return Err(DError::Fatal {
+                        error: LinesError::TooLong,
+                    });
+                }
+
+                self.searched = src.len();
+
+                return Err(DError::Incomplete {
+                    needed: Size::new(1),
+                });
+            }
+        };
+
+        self.searched = 0;
+
+        let line = self.strip(line).map_err(|error| DError::Fatal { error })?;
+
+        *src = rest;
+
+        Ok(line)
+    }
+}
+
+impl Default for Lines {
+    #[inline]
+    fn default() -> Self {
+        Lines::new()
+    }
+}
+
+impl Decoder for Lines {
+    type Item<'src> = &'src [u8];
+    type Error = LinesError;
+
+    #[inline]
+    fn hint(&self) -> Size {
+        Size::new(1)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> SizeHint {
+        let terminator = Size::new(self.terminator().len());
+
+        SizeHint::new(self.hint(), Size::new(self.max_len) + terminator)
+    }
+
+    #[inline]
+    fn decode<'s>(&mut self, src: &mut &'s [u8]) -> DResult<'s, Self> {
+        self.next(src, false)
+    }
+
+    #[inline]
+    fn decode_eof<'s>(&mut self, src: &mut &'s [u8]) -> DResult<'s, Self> {
+        self.next(src, true)
+    }
+}
+
+impl Encoder<[u8]> for Lines {
+    type Error = LinesError;
+
+    #[inline]
+    fn encode_hint(&self, item: &[u8]) -> Size {
+        Size::new(item.len() + self.terminator().len())
+    }
+
+    fn encode(&mut self, item: &[u8], dst: &mut &mut [u8]) -> EResult<Self::Error> {
+        if find_byte(item, b'\n').is_some() {
+            return Err(EError::Fatal {
+                error: LinesError::Newline,
+            });
+        }
+
+        if item.len() > self.max_len {
+            return Err(EError::Fatal {
+                error: LinesError::TooLong,
+            });
+        }
+
+        let terminator = self.terminator();
+        let len = item.len() + terminator.len();
+
+        if dst.len() < len {
+            return Err(EError::Full {
+                needed: Size::new(len - dst.len()),
+            });
+        }
+
+        let (bytes, rest) = core::mem::take(dst).split_at_mut(len);
+        let (line, end) = bytes.split_at_mut(item.len());
+
+        line.copy_from_slice(item);
+        end.copy_from_slice(terminator);
+
+        *dst = rest;
+
+        Ok(())
+    }
+}
+
+/// A [`Decoder`] and [`Encoder`] for `\n` terminated lines of UTF-8 text.
+///
+/// See [`Lines`] for details.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct StrLines {
+    /// The underlying decoder for lines of bytes.
+    pub lines: Lines,
+}
+
+impl StrLines {
+    /// Create a new decoder for lines of text.
+    #[inline]
+    #[must_use]
+    pub const fn new() -> StrLines {
+        StrLines::from_lines(Lines::new())
+    }
+
+    /// Create a new decoder for lines of text from a decoder for
+    /// lines of bytes.
+    #[inline]
+    #[must_use]
+    pub const fn from_lines(lines: Lines) -> StrLines {
+        StrLines { lines }
+    }
+
+    #[inline]
+    fn utf8<'s>(line: DResult<'s, Lines>) -> DResult<'s, Self> {
+        core::str::from_utf8(line?).map_err(|error| DError::Fatal {
+            error: LinesError::Utf8(error),
+        })
+    }
+}
+
+impl Decoder for StrLines {
+    type Item<'src> = &'src str;
+    type Error = LinesError;
+
+    #[inline]
+    fn hint(&self) -> Size {
+        self.lines.hint()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> SizeHint {
+        self.lines.size_hint()
+    }
+
+    #[inline]
+    fn decode<'s>(&mut self, src: &mut &'s [u8]) -> DResult<'s, Self> {
+        let mut rest = *src;
+        let line = Self::utf8(self.lines.decode(&mut rest))?;
+
+        *src = rest;
+
+        Ok(line)
+    }
+
+    #[inline]
+    fn decode_eof<'s>(&mut self, src: &mut &'s [u8]) -> DResult<'s, Self> {
+        let mut rest = *src;
+        let line = Self::utf8(self.lines.decode_eof(&mut rest))?;
+
+        *src = rest;
+
+        Ok(line)
+    }
+}
+
+impl Encoder<str> for StrLines {
+    type Error = LinesError;
+
+    #[inline]
+    fn encode_hint(&self, item: &str) -> Size {
+        self.lines.encode_hint(item.as_bytes())
+    }
+
+    #[inline]
+    fn encode(&mut self, item: &str, dst: &mut &mut [u8]) -> EResult<Self::Error> {
+        self.lines.encode(item.as_bytes(), dst)
+    }
+}
+
+/// A [`Decoder`] and [`Encoder`] for frames separated by a delimiter of
+/// one or more bytes.
+///
+/// The delimiter is not part of decoded frames. At the end of a stream,
+/// any bytes after the last delimiter are decoded as a final frame.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Delimited<T: AsRef<[u8]> = &'static [u8]> {
+    /// The delimiter.
+    pub delimiter: T,
+    /// The maximum length of a frame, excluding the delimiter.
+    ///
+    /// Longer frames are a fatal error rather than incomplete.
+    pub max_len: usize,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    searched: usize,
+}
+
+impl<T: AsRef<[u8]>> Delimited<T> {
+    /// Create a new decoder for frames separated by `delimiter`.
+    ///
+    /// An empty delimiter cannot separate anything, so decoding and
+    /// encoding with one is a fatal [`DelimitedError::EmptyDelimiter`].
+    #[inline]
+    #[must_use]
+    pub const fn new(delimiter: T) -> Delimited<T> {
+        Delimited {
+            delimiter,
+            max_len: usize::MAX,
+            searched: 0,
+        }
+    }
+
+    /// Set the maximum length of a frame.
+    #[inline]
+    #[must_use]
+    pub fn with_max_len(mut self, max_len: usize) -> Delimited<T> {
+        self.max_len = max_len;
+        self
+    }
+
+    fn next<'s>(&mut self, src: &mut &'s [u8], eof: bool) -> DResult<'s, Self> {
+        let delimiter = self.delimiter.as_ref();
+
+        if delimiter.is_empty() {
+            return Err(DError::Fatal {
+                error: DelimitedError::EmptyDelimiter,
+            });
+        }
+
+        // The delimiter may straddle where the last search ended.
+        let start = self
+            .searched
+            .saturating_sub(delimiter.len().saturating_sub(1))
+            .min(src.len());
+
+        let (frame, rest) = match find(&src[start..], delimiter) {
+            Some(i) => (&src[..start + i], &src[start + i + delimiter.len()..]),
+            None if eof && src.is_empty() => return Err(DError::Eof),
+            None if eof => (*src, &[][..]),
+            None => {
+                // A frame that is already too long cannot become valid.
+                let limit = self
+                    .max_len
+                    .saturating_add(delimiter.len().saturating_sub(1));
+
+                if src.len() > limit {
+                    self.searched = 0;
+
+                    return Err(DError::Fatal {
+                        error: DelimitedError::TooLong,
+                    });
+                }
+
+                self.searched = src.len();
+
+                return Err(DError::Incomplete {
+                    needed: Size::new(1),
+                });
+            }
+        };
+
+        self.searched = 0;
+
+        if frame.len() > self.max_len {
+            return Err(DError::Fatal {
+                error: DelimitedError::TooLong,
+            });
+        }
+
+        *src = rest;
+
+        Ok(frame)
+    }
+}
+
+impl<T: AsRef<[u8]>> Decoder for Delimited<T> {
+    type Item<'src> = &'src [u8];
+    type Error = DelimitedError;
+
+    #[inline]
+    fn hint(&self) -> Size {
+        Size::new(self.delimiter.as_ref().len())
+    }
+
+    #[inline]
+    fn size_hint(&self) -> SizeHint {
+        SizeHint::new(self.hint(), Size::new(self.max_len) + self.hint())
+    }
+
+    #[inline]
+    fn decode<'s>(&mut self, src: &mut &'s [u8]) -> DResult<'s, Self> {
+        self.next(src, false)
+    }
+
+    #[inline]
+    fn decode_eof<'s>(&mut self, src: &mut &'s [u8]) -> DResult<'s, Self> {
+        self.next(src, true)
+    }
+}
+
+impl<T: AsRef<[u8]>> Encoder<[u8]> for Delimited<T> {
+    type Error = DelimitedError;
+
+    #[inline]
+    fn encode_hint(&self, item: &[u8]) -> Size {
+        Size::new(item.len() + self.delimiter.as_ref().len())
+    }
+
+    fn encode(&mut self, item: &[u8], dst: &mut &mut [u8]) -> EResult<Self::Error> {
+        let delimiter = self.delimiter.as_ref();
+
+        if delimiter.is_empty() {
+            return Err(EError::Fatal {
+                error: DelimitedError::EmptyDelimiter,
+            });
+        }
+
+        if find(item, delimiter).is_some() {
+            return Err(EError::Fatal {
+                error: DelimitedError::Delimiter,
+            });
+        }
+
+        if item.len() > self.max_len {
+            return Err(EError::Fatal {
+                error: DelimitedError::TooLong,
+            });
+        }
+
+        let len = item.len() + delimiter.len();
+
+        if dst.len() < len {
+            return Err(EError::Full {
+                needed: Size::new(len - dst.len()),
+            });
+        }
+
+        let (bytes, rest) = core::mem::take(dst).split_at_mut(len);
+        let (frame, end) = bytes.split_at_mut(item.len());
+
+        frame.copy_from_slice(item);
+        end.copy_from_slice(delimiter);
+
+        *dst = rest;
+
+        Ok(())
+    }
+}
+
+/// Type for errors that may occur while reading or writing delimited frames.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum DelimitedError {
+    /// The frame is longer than the maximum length.
+    TooLong,
+    /// The frame to write contains the delimiter.
+    Delimiter,
+    /// The delimiter is empty.
+    EmptyDelimiter,
+}
+
+impl fmt::Display for DelimitedError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::TooLong => f.write_str("frame too long"),
+            Self::Delimiter => f.write_str("frame contains the delimiter"),
+            Self::EmptyDelimiter => f.write_str("empty delimiter"),
+        }
+    }
+}
+
+impl core::error::Error for DelimitedError {}
+
+/// Type for errors that may occur while reading or writing lines.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LinesError {
+    /// The line is longer than the maximum length.
+    TooLong,
+    /// The line to write contains a `\n`.
+    Newline,
+    /// The line is not valid UTF-8.
+    Utf8(Utf8Error),
+}
+
+impl fmt::Display for LinesError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::TooLong => f.write_str("line too long"),
+            Self::Newline => f.write_str("line contains a newline"),
+            Self::Utf8(error) => write!(f, "invalid utf-8 at byte {}", error.valid_up_to()),
+        }
+    }
+}
+
+impl core::error::Error for LinesError {}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for LinesError {
+    fn format(&self, f: defmt::Formatter<'_>) {
+        match self {
+            Self::TooLong => defmt::write!(f, "line too long"),
+            Self::Newline => defmt::write!(f, "line contains a newline"),
+            Self::Utf8(error) => {
+                defmt::write!(f, "invalid utf-8 at byte {=usize}", error.valid_up_to())
+            }
+        }
+    }
+}
+
+/// A [`Decoder`] and [`Encoder`] for netstrings, which are frames of the
+/// form `<len>:<payload>,` where `len` is the payload length in decimal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Netstring {
+    /// The maximum length of a payload.
+    ///
+    /// Longer payloads are a fatal error rather than incomplete.
+    pub max_len: usize,
+}
+
+impl Netstring {
+    /// Create a new decoder for netstrings.
+    #[inline]
+    #[must_use]
+    pub const fn new() -> Netstring {
+        Netstring {
+            max_len: usize::MAX,
+        }
+    }
+
+    /// Set the maximum length of a payload.
+    #[inline]
+    #[must_use]
+    pub const fn with_max_len(mut self, max_len: usize) -> Netstring {
+        self.max_len = max_len;
+        self
+    }
+
+    /// Returns the amount of digits in `len` written in decimal.
+    #[inline]
+    const fn digits(len: usize) -> usize {
+        match len.checked_ilog10() {
+            Some(log) => log as usize + 1,
+            None => 1,
+        }
+    }
+}
+
+impl Default for Netstring {
+    #[inline]
+    fn default() -> Self {
+        Netstring::new()
+    }
+}
+
+impl Decoder for Netstring {
+    type Item<'src> = &'src [u8];
+    type Error = NetstringError;
+
+    #[inline]
+    fn hint(&self) -> Size {
+        Size::new(3)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> SizeHint {
+        let digits = Size::new(Netstring::digits(self.max_len));
+
+        SizeHint::new(self.hint(), digits + Size::new(self.max_len) + Size::new(2))
+    }
+
+    fn decode<'s>(&mut self, src: &mut &'s [u8]) -> DResult<'s, Self> {
+        let mut len = 0usize;
+        let mut digits = 0;
+
+        loop {
+            let Some(&byte) = src.get(digits) else {
+                return Err(DError::Incomplete {
+                    needed: Size::new(1),
+                });
+            };
+
+            match byte {
+                b':' if digits > 0 => break,
+                // Leading zeros are not allowed.
+                b'0'..=b'9' if !(digits == 1 && len == 0) => {
+                    len = len
+                        .checked_mul(10)
+                        .and_then(|len| len.checked_add(usize::from(byte - b'0')))
+                        .filter(|&len| len <= self.max_len)
+                        .ok_or(DError::Fatal {
+                            error: NetstringError::TooLong,
+                        })?;
+
+                    digits += 1;
+                }
+                _ => {
+                    return Err(DError::Fatal {
+                        error: NetstringError::Length,
+                    })
+                }
+            }
+        }
+
+        let rest = &src[digits + 1..];
+
+        let Some((payload, rest)) = rest.split_at_checked(len) else {
+            return Err(DError::Incomplete {
+                needed: Size::new(len + 1 - rest.len()),
+            });
+        };
+
+        match rest.split_first() {
+            Some((b',', rest)) => {
+                *src = rest;
+
+                Ok(payload)
+            }
+            Some(_) => Err(DError::Fatal {
+                error: NetstringError::Comma,
+            }),
+            None => Err(DError::Incomplete {
+                needed: Size::new(1),
+            }),
+        }
+    }
+}
+
+impl Encoder<[u8]> for Netstring {
+    type Error = NetstringError;
+
+    #[inline]
+    fn encode_hint(&self, item: &[u8]) -> Size {
+        Size::new(Netstring::digits(item.len()) + item.len() + 2)
+    }
+
+    fn encode(&mut self, item: &[u8], dst: &mut &mut [u8]) -> EResult<Self::Error> {
+        if item.len() > self.max_len {
+            return Err(EError::Fatal {
+                error: NetstringError::TooLong,
+            });
+        }
+
+        let digits = Netstring::digits(item.len());
+        let len = digits + item.len() + 2;
+
+        if dst.len() < len {
+            return Err(EError::Full {
+                needed: Size::new(len - dst.len()),
+            });
+        }
+
+        let (bytes, rest) = core::mem::take(dst).split_at_mut(len);
+        let (prefix, bytes) = bytes.split_at_mut(digits);
+
+        let mut n = item.len();
+
+        for digit in prefix.iter_mut().rev() {
+            *digit = b'0' + (n % 10) as u8;
+            n /= 10;
+        }
+
+        bytes[0] = b':';
+        bytes[1..=item.len()].copy_from_slice(item);
+        bytes[item.len() + 1] = b',';
+
+        *dst = rest;
+
+        Ok(())
+    }
+}
+
+/// Type for errors that may occur while reading or writing netstrings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum NetstringError {
+    /// The length is not a valid decimal number.
+    Length,
+    /// The payload is longer than the maximum length.
+    TooLong,
+    /// The payload is not followed by a comma.
+    Comma,
+}
+
+impl fmt::Display for NetstringError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Length => f.write_str("invalid netstring length"),
+            Self::TooLong => f.write_str("netstring too long"),
+            Self::Comma => f.write_str("netstring missing trailing comma"),
+        }
+    }
+}
+
+impl core::error::Error for NetstringError {}
+
+/// A [`Decoder`] and [`Encoder`] for frames prefixed by a header
+/// containing their length.
+///
+/// The header is [`len_offset`](LengthDelimited::len_offset) arbitrary
+/// bytes followed by the length field. Decoded frames are the payload
+/// after the header, unless [`keep_header`](LengthDelimited::keep_header)
+/// is set. By default the header is a 4 byte big endian length of the
+/// payload.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LengthDelimited<B: ByteOrder = BigEndian> {
+    /// The byte order of the length field.
+    pub byte_order: B,
+    /// The amount of bytes before the length field.
+    ///
+    /// These are written as zeros when encoding.
+    pub len_offset: usize,
+    /// The size of the length field in bytes, from 1 to 8.
+    ///
+    /// Other sizes are a fatal [`LengthDelimitedError::FieldSize`].
+    pub len_size: usize,
+    /// The amount added to the length field to get the length of the
+    /// payload.
+    pub len_adjustment: isize,
+    /// Whether the length field includes the header itself.
+    pub len_includes_header: bool,
+    /// Whether decoded frames include the header.
+    pub keep_header: bool,
+    /// The maximum length of a payload.
+    ///
+    /// Longer payloads are a fatal error rather than incomplete.
+    pub max_len: usize,
+}
+
+impl<B: ByteOrder> LengthDelimited<B> {
+    /// Create a new decoder for length delimited frames.
+    #[inline]
+    #[must_use]
+    pub const fn new(byte_order: B) -> LengthDelimited<B> {
+        LengthDelimited {
+            byte_order,
+            len_offset: 0,
+            len_size: 4,
+            len_adjustment: 0,
+            len_includes_header: false,
+            keep_header: false,
+            max_len: 8 * 1024 * 1024,
+        }
+    }
+
+    /// Set the amount of bytes before the length field.
+    #[inline]
+    #[must_use]
+    pub const fn with_len_offset(mut self, len_offset: usize) -> LengthDelimited<B> {
+        self.len_offset = len_offset;
+        self
+    }
+
+    /// Set the size of the length field in bytes.
+    ///
+    /// Sizes other than 1 to 8 are a fatal
+    /// [`LengthDelimitedError::FieldSize`] when decoding or encoding.
+    #[inline]
+    #[must_use]
+    pub const fn with_len_size(mut self, len_size: usize) -> LengthDelimited<B> {
+        self.len_size = len_size;
+        self
+    }
+
+    /// Set the amount added to the length field to get the length of the
+    /// payload.
+    #[inline]
+    #[must_use]
+    pub const fn with_len_adjustment(mut self, len_adjustment: isize) -> LengthDelimited<B> {
+        self.len_adjustment = len_adjustment;
+        self
+    }
+
+    /// Set whether the length field includes the header itself.
+    #[inline]
+    #[must_use]
+    pub const fn with_len_includes_header(mut self, yes: bool) -> LengthDelimited<B> {
+        self.len_includes_header = yes;
+        self
+    }
+
+    /// Set whether decoded frames include the header.
+    #[inline]
+    #[must_use]
+    pub const fn with_keep_header(mut self, yes: bool) -> LengthDelimited<B> {
+        self.keep_header = yes;
+        self
+    }
+
+    /// Set the maximum length of a payload.
+    #[inline]
+    #[must_use]
+    pub const fn with_max_len(mut self, max_len: usize) -> LengthDelimited<B> {
+        self.max_len = max_len;
+        self
+    }
+
+    /// Returns the size of the header in bytes.
+    #[inline]
+    #[must_use]
+    pub const fn header_len(&self) -> usize {
+        self.len_offset + self.len_size
+    }
+
+    /// Returns the difference between the length field and the length of
+    /// the payload.
+    #[inline]
+    fn bias(&self) -> i128 {
+        let header = if self.len_includes_header {
+            self.header_len() as i128
+        } else {
+            0
+        };
+
+        self.len_adjustment as i128 - header
+    }
+}
+
+impl Default for LengthDelimited {
+    #[inline]
+    fn default() -> Self {
+        LengthDelimited::new(BigEndian)
+    }
+}
+
+impl<B: ByteOrder> Decoder for LengthDelimited<B> {
+    type Item<'src> = &'src [u8];
+    type Error = LengthDelimitedError;
+
+    #[inline]
+    fn hint(&self) -> Size {
+        Size::new(self.header_len())
+    }
+
+    #[inline]
+    fn size_hint(&self) -> SizeHint {
+        SizeHint::new(self.hint(), self.hint() + Size::new(self.max_len))
+    }
+
+    fn decode<'s>(&mut self, src: &mut &'s [u8]) -> DResult<'s, Self> {
+        if !(1..=8).contains(&self.len_size) {
+            return Err(DError::Fatal {
+                error: LengthDelimitedError::FieldSize,
+            });
+        }
+
+        let header_len = self.header_len();
+
+        let Some((header, rest)) = src.split_at_checked(header_len) else {
+            return Err(DError::Incomplete {
+                needed: Size::new(header_len - src.len()),
+            });
+        };
+
+        let field = &header[self.len_offset..];
+        let value = match self.byte_order.endian() {
+            Endian::Little => field.iter().rfold(0u64, |n, &b| n << 8 | u64::from(b)),
+            Endian::Big => field.iter().fold(0u64, |n, &b| n << 8 | u64::from(b)),
+        };
+
+        let len = match usize::try_from(i128::from(value) + self.bias()) {
+            Ok(len) if len <= self.max_len => len,
+            Ok(_) => {
+                return Err(DError::Fatal {
+                    error: LengthDelimitedError::TooLong,
+                })
+            }
+            Err(_) => {
+                return Err(DError::Fatal {
+                    error: LengthDelimitedError::Length,
+                })
+            }
+        };
+
+        let Some((payload, rest)) = rest.split_at_checked(len) else {
+            return Err(DError::Incomplete {
+                needed: Size::new(len - rest.len()),
+            });
+        };
+
+        let frame = if self.keep_header {
+            &src[..header_len + len]
+        } else {
+            payload
+        };
+
+        *src = rest;
+
+        Ok(frame)
+    }
+}
+
+impl<B: ByteOrder> Encoder<[u8]> for LengthDelimited<B> {
+    type Error = LengthDelimitedError;
+
+    #[inline]
+    fn encode_hint(&self, item: &[u8]) -> Size {
+        Size::new(self.header_len() + item.len())
+    }
+
+    fn encode(&mut self, item: &[u8], dst: &mut &mut [u8]) -> EResult<Self::Error> {
+        if !(1..=8).contains(&self.len_size) {
+            return Err(EError::Fatal {
+                error: LengthDelimitedError::FieldSize,
+            });
+        }
+
+        if item.len() > self.max_len {
+            return Err(EError::Fatal {
+                error: LengthDelimitedError::TooLong,
+            });
+        }
+
+        let value = item.len() as i128 - self.bias();
+        let max = u64::MAX >> (64 - 8 * self.len_size);
+
+        let value = match u64::try_from(value) {
+            Ok(value) if value <= max => value,
+            _ => {
+                return Err(EError::Fatal {
+                    error: LengthDelimitedError::Length,
+                })
+            }
+        };
+
+        let len = self.header_len() + item.len();
+
+        if dst.len() < len {
+            return Err(EError::Full {
+                needed: Size::new(len - dst.len()),
+            });
+        }
+
+        let (bytes, rest) = core::mem::take(dst).split_at_mut(len);
+        let (prefix, bytes) = bytes.split_at_mut(self.len_offset);
+        let (field, payload) = bytes.split_at_mut(self.len_size);
+
+        prefix.fill(0);
+
+        match self.byte_order.endian() {
+            Endian::Little => field.copy_from_slice(&value.to_le_bytes()[..self.len_size]),
+            Endian::Big => field.copy_from_slice(&value.to_be_bytes()[8 - self.len_size..]),
+        }
+
+        payload.copy_from_slice(item);
+
+        *dst = rest;
+
+        Ok(())
+    }
+}
+
+/// Type for errors that may occur while reading or writing length
+/// delimited frames.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum LengthDelimitedError {
+    /// The length field does not describe a valid length.
+    Length,
+    /// The payload is longer than the maximum length.
+    TooLong,
+    /// The size of the length field is not from 1 to 8.
+    FieldSize,
+}
+
+impl fmt::Display for LengthDelimitedError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Length => f.write_str("invalid length field"),
+            Self::TooLong => f.write_str("frame too long"),
+            Self::FieldSize => f.write_str("invalid length field size"),
+        }
+    }
+}
+
+impl core::error::Error for LengthDelimitedError {}
+
+/// A [`Decoder`] and [`Encoder`] for type-length-value records, yielding
+/// their tag and value.
+///
+/// A record is a [`tag_size`](Tlv::tag_size) byte tag, followed by a
+/// [`len_size`](Tlv::len_size) byte length field and the value. By default
+/// both fields are a single byte, and the length field is the length of the
+/// value.
+///
+/// Records in a byte slice can be read one after another with
+/// [`Tlv::iter`].
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Tlv<B: ByteOrder = BigEndian> {
+    /// The byte order of the tag and length fields.
+    pub byte_order: B,
+    /// The size of the tag field in bytes, from 1 to 8.
+    ///
+    /// Other sizes are a fatal [`TlvError::FieldSize`].
+    pub tag_size: usize,
+    /// The size of the length field in bytes, from 1 to 8.
+    ///
+    /// Other sizes are a fatal [`TlvError::FieldSize`].
+    pub len_size: usize,
+    /// Whether the length field includes the tag and length fields.
+    pub len_includes_header: bool,
+    /// The maximum length of a value.
+    ///
+    /// Longer values are a fatal error rather than incomplete.
+    pub max_len: usize,
+}
+
+impl<B: ByteOrder> Tlv<B> {
+    /// Create a new decoder for type-length-value records.
+    #[inline]
+    #[must_use]
+    pub const fn new(byte_order: B) -> Tlv<B> {
+        Tlv {
+            byte_order,
+            tag_size: 1,
+            len_size: 1,
+            len_includes_header: false,
+            max_len: 8 * 1024 * 1024,
+        }
+    }
+
+    /// Set the size of the tag field in bytes.
+    ///
+    /// Sizes other than 1 to 8 are a fatal [`TlvError::FieldSize`] when
+    /// decoding or encoding.
+    #[inline]
+    #[must_use]
+    pub const fn with_tag_size(mut self, tag_size: usize) -> Tlv<B> {
+        self.tag_size = tag_size;
+        self
+    }
+
+    /// Set the size of the length field in bytes.
+    ///
+    /// Sizes other than 1 to 8 are a fatal [`TlvError::FieldSize`] when
+    /// decoding or encoding.
+    #[inline]
+    #[must_use]
+    pub const fn with_len_size(mut self, len_size: usize) -> Tlv<B> {
+        self.len_size = len_size;
+        self
+    }
+
+    /// Set whether the length field includes the tag and length fields.
+    #[inline]
+    #[must_use]
+    pub const fn with_len_includes_header(mut self, yes: bool) -> Tlv<B> {
+        self.len_includes_header = yes;
+        self
+    }
+
+    /// Set the maximum length of a value.
+    #[inline]
+    #[must_use]
+    pub const fn with_max_len(mut self, max_len: usize) -> Tlv<B> {
+        self.max_len = max_len;
+        self
+    }
+
+    /// Returns the size of the tag and length fields in bytes.
+    #[inline]
+    #[must_use]
+    pub const fn header_len(&self) -> usize {
+        self.tag_size + self.len_size
+    }
+
+    /// Returns an iterator over the consecutive records in `src`.
+    #[inline]
+    pub fn iter<'s>(&self, src: &'s [u8]) -> Tlvs<'s, B> {
+        Tlvs {
+            tlv: self.clone(),
+            src,
+        }
+    }
+
+    #[inline]
+    fn check(&self) -> Result<(), TlvError> {
+        if (1..=8).contains(&self.tag_size) && (1..=8).contains(&self.len_size) {
+            Ok(())
+        } else {
+            Err(TlvError::FieldSize)
+        }
+    }
+
+    fn read_field(&self, field: &[u8]) -> u64 {
+        match self.byte_order.endian() {
+            Endian::Little => field.iter().rfold(0u64, |n, &b| n << 8 | u64::from(b)),
+            Endian::Big => field.iter().fold(0u64, |n, &b| n << 8 | u64::from(b)),
+        }
+    }
+
+    fn write_field(&self, field: &mut [u8], value: u64) {
+        let size = field.len();
+
+        match self.byte_order.endian() {
+            Endian::Little => field.copy_from_slice(&value.to_le_bytes()[..size]),
+            Endian::Big => field.copy_from_slice(&value.to_be_bytes()[8 - size..]),
+        }
+    }
+}
+
+impl Default for Tlv {
+    #[inline]
+    fn default() -> Self {
+        Tlv::new(BigEndian)
+    }
+}
+
+impl<B: ByteOrder> Decoder for Tlv<B> {
+    type Item<'src> = (u64, &'src [u8]);
+    type Error = TlvError;
+
+    #[inline]
+    fn hint(&self) -> Size {
+        Size::new(self.header_len())
+    }
+
+    #[inline]
+    fn size_hint(&self) -> SizeHint {
+        SizeHint::new(self.hint(), self.hint() + Size::new(self.max_len))
+    }
+
+    fn decode<'s>(&mut self, src: &mut &'s [u8]) -> DResult<'s, Self> {
+        self.check().map_err(|error| DError::Fatal { error })?;
+
+        let header_len = self.header_len();
+
+        let Some((header, rest)) = src.split_at_checked(header_len) else {
+            return Err(DError::Incomplete {
+                needed: Size::new(header_len - src.len()),
+            });
+        };
+
+        let (tag, field) = header.split_at(self.tag_size);
+        let tag = self.read_field(tag);
+        let value = self.read_field(field);
+
+        let value = if self.len_includes_header {
+            value.checked_sub(header_len as u64)
+        } else {
+            Some(value)
+        };
+
+        let len = match value.map(usize::try_from) {
+            Some(Ok(len)) if len <= self.max_len => len,
+            Some(Ok(_)) => {
+                return Err(DError::Fatal {
+                    error: TlvError::TooLong,
+                })
+            }
+            _ => {
+                return Err(DError::Fatal {
+                    error: TlvError::Length,
+                })
+            }
+        };
+
+        let Some((value, rest)) = rest.split_at_checked(len) else {
+            return Err(DError::Incomplete {
+                needed: Size::new(len - rest.len()),
+            });
+        };
+
+        *src = rest;
+
+        Ok((tag, value))
+    }
+}
+
+impl<'a, B: ByteOrder> Encoder<(u64, &'a [u8])> for Tlv<B> {
+    type Error = TlvError;
+
+    #[inline]
+    fn encode_hint(&self, &(_, value): &(u64, &'a [u8])) -> Size {
+        Size::new(self.header_len() + value.len())
+    }
+
+    fn encode(
+        &mut self,
+        &(tag, value): &(u64, &'a [u8]),
+        dst: &mut &mut [u8],
+    ) -> EResult<Self::Error> {
+        self.check().map_err(|error| EError::Fatal { error })?;
+
+        if value.len() > self.max_len {
+            return Err(EError::Fatal {
+                error: TlvError::TooLong,
+            });
+        }
+
+        let header_len = self.header_len();
+
+        if tag > u64::MAX >> (64 - 8 * self.tag_size) {
+            return Err(EError::Fatal {
+                error: TlvError::Tag,
+            });
+        }
+
+        let field = if self.len_includes_header {
+            header_len + value.len()
+        } else {
+            value.len()
+        };
+
+        match u64::try_from(field) {
+            Ok(field) if field <= u64::MAX >> (64 - 8 * self.len_size) => {}
+            _ => {
+                return Err(EError::Fatal {
+                    error: TlvError::Length,
+                })
+            }
+        }
+
+        let len = header_len + value.len();
+
+        if dst.len() < len {
+            return Err(EError::Full {
+                needed: Size::new(len - dst.len()),
+            });
+        }
+
+        let (bytes, rest) = core::mem::take(dst).split_at_mut(len);
+        let (header, payload) = bytes.split_at_mut(header_len);
+        let (tag_field, len_field) = header.split_at_mut(self.tag_size);
+
+        self.write_field(tag_field, tag);
+        self.write_field(len_field, field as u64);
+        payload.copy_from_slice(value);
+
+        *dst = rest;
+
+        Ok(())
+    }
+}
+
+/// An iterator over consecutive type-length-value records in a byte
+/// slice, see [`Tlv::iter`].
+///
+/// Once a record is invalid or truncated, the error is yielded and the
+/// iterator ends.
+#[derive(Debug, Clone)]
+pub struct Tlvs<'s, B: ByteOrder = BigEndian> {
+    tlv: Tlv<B>,
+    src: &'s [u8],
+}
+
+impl<'s, B: ByteOrder> Tlvs<'s, B> {
+    /// Returns the bytes that have not been read yet.
+    #[inline]
+    #[must_use]
+    pub const fn remaining(&self) -> &'s [u8] {
+        self.src
+    }
+}
+
+impl<'s, B: ByteOrder> Iterator for Tlvs<'s, B> {
+    type Item = Result<(u64, &'s [u8]), DError<TlvError>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.src.is_empty() {
+            return None;
+        }
+
+        let result = self.tlv.decode(&mut self.src);
+
+        if result.is_err() {
+            self.src = &[];
+        }
+
+        Some(result)
+    }
+}
+
+impl<B: ByteOrder> core::iter::FusedIterator for Tlvs<'_, B> {}
+
+/// Type for errors that may occur while reading or writing
+/// type-length-value records.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum TlvError {
+    /// The tag does not fit in the tag field.
+    Tag,
+    /// The length field does not describe a valid length.
+    Length,
+    /// The value is longer than the maximum length.
+    TooLong,
+    /// The size of the tag or length field is not from 1 to 8.
+    FieldSize,
+}
+
+impl fmt::Display for TlvError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Tag => f.write_str("tag too large for the tag field"),
+            Self::Length => f.write_str("invalid length field"),
+            Self::TooLong => f.write_str("value too long"),
+            Self::FieldSize => f.write_str("invalid tlv field size"),
+        }
+    }
+}
+
+impl core::error::Error for TlvError {}
+
+/// A part of an HTTP/1.1 chunked body, see [`Chunked`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Chunk<'src> {
+    /// Some of the payload of a chunk, which is never empty.
+    Data(&'src [u8]),
+    /// A trailer field line, without its line terminator.
+    Trailer(&'src [u8]),
+    /// The end of the body, after the zero-length chunk and any trailers.
+    End,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+enum ChunkedState {
+    #[default]
+    Size,
+    Data(u64),
+    DataEnd,
+    Trailers,
+}
+
+/// A [`Decoder`] for HTTP/1.1 bodies in the chunked transfer coding.
+///
+/// Chunk payloads are yielded as soon as any of them is available, so
+/// large chunks need not be buffered whole. Chunk extensions are ignored.
+/// After [`Chunk::End`] the decoder is ready for another body.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Chunked {
+    /// The maximum length of a chunk size or trailer line.
+    ///
+    /// Longer lines are a fatal error rather than incomplete.
+    pub max_line_len: usize,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    state: ChunkedState,
+}
+
+impl Chunked {
+    /// Create a new decoder for a chunked body.
+    #[inline]
+    #[must_use]
+    pub const fn new() -> Chunked {
+        Chunked {
+            max_line_len: 4096,
+            state: ChunkedState::Size,
+        }
+    }
+
+    /// Set the maximum length of a chunk size or trailer line.
+    #[inline]
+    #[must_use]
+    pub const fn with_max_line_len(mut self, max_line_len: usize) -> Chunked {
+        self.max_line_len = max_line_len;
+        self
+    }
+
+    /// Split a `\r\n` terminated line off the start of `src`.
+    fn line<'s>(&self, src: &mut &'s [u8]) -> Result<&'s [u8], DError<ChunkedError>> {
+        let limit = src.len().min(self.max_line_len.saturating_add(2));
+
+        let Some(i) = find_byte(&src[..limit], b'\n') else {
+            return Err(if src.len() > limit {
+                DError::Fatal {
+                    error: ChunkedError::TooLong,
+                }
+            } else {
+                DError::Incomplete {
+                    needed: Size::new(1),
+                }
+            });
+        };
+
+        match src[..i].strip_suffix(b"\r") {
+            Some(line) => {
+                *src = &src[i + 1..];
+
+                Ok(line)
+            }
+            None => Err(DError::Fatal {
+                error: ChunkedError::Crlf,
+            }),
+        }
+    }
+
+    /// Parse the size out of a chunk size line.
+    fn size(line: &[u8]) -> Result<u64, ChunkedError> {
+        let end = find_byte(line, b';').unwrap_or(line.len());
+        let digits = line[..end].trim_ascii_end();
+
+        if digits.is_empty() {
+            return Err(ChunkedError::Size);
+        }
+
+        digits.iter().try_fold(0u64, |size, &b| {
+            let digit = char::from(b).to_digit(16).ok_or(ChunkedError::Size)?;
+
+            size.checked_mul(16)
+                .map(|size| size | u64::from(digit))
+                .ok_or(ChunkedError::Size)
+        })
+    }
+}
+
+impl Default for Chunked {
+    #[inline]
+    fn default() -> Self {
+        Chunked::new()
+    }
+}
+
+impl Decoder for Chunked {
+    type Item<'src> = Chunk<'src>;
+    type Error = ChunkedError;
+
+    #[inline]
+    fn hint(&self) -> Size {
+        match self.state {
+            ChunkedState::Size | ChunkedState::DataEnd | ChunkedState::Trailers => Size::new(2),
+            ChunkedState::Data(remaining) => {
+                Size::new(usize::try_from(remaining).unwrap_or(usize::MAX))
+            }
+        }
+    }
+
+    fn decode<'s>(&mut self, src: &mut &'s [u8]) -> DResult<'s, Self> {
+        // Work on copies so that incomplete input leaves everything as is.
+        let mut rest = *src;
+        let mut state = self.state;
+
+        let chunk = loop {
+            match state {
+                ChunkedState::Size => {
+                    let line = self.line(&mut rest)?;
+
+                    state = match Chunked::size(line).map_err(|error| DError::Fatal { error })? {
+                        0 => ChunkedState::Trailers,
+                        size => ChunkedState::Data(size),
+                    };
+                }
+                ChunkedState::Data(remaining) => {
+                    // Any amount of the payload can be yielded.
+                    if rest.is_empty() {
+                        return Err(DError::Incomplete {
+                            needed: Size::new(1),
+                        });
+                    }
+
+                    let len = usize::try_from(remaining).map_or(rest.len(), |r| r.min(rest.len()));
+                    let (data, tail) = rest.split_at(len);
+
+                    rest = tail;
+                    state = match remaining - len as u64 {
+                        0 => ChunkedState::DataEnd,
+                        remaining => ChunkedState::Data(remaining),
+                    };
+
+                    break Chunk::Data(data);
+                }
+                ChunkedState::DataEnd => match rest.split_first_chunk() {
+                    Some((b"\r\n", tail)) => {
+                        rest = tail;
+                        state = ChunkedState::Size;
+                    }
+                    Some(_) => {
+                        return Err(DError::Fatal {
+                            error: ChunkedError::Crlf,
+                        })
+                    }
+                    None => {
+                        return Err(DError::Incomplete {
+                            needed: Size::new(2 - rest.len()),
+                        })
+                    }
+                },
+                ChunkedState::Trailers => {
+                    let line = self.line(&mut rest)?;
+
+                    if line.is_empty() {
+                        state = ChunkedState::Size;
+
+                        break Chunk::End;
+                    }
+
+                    break Chunk::Trailer(line);
+                }
+            }
+        };
+
+        self.state = state;
+        *src = rest;
+
+        Ok(chunk)
+    }
+}
+
+/// Type for errors that may occur while reading a chunked body.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum ChunkedError {
+    /// A chunk size is not a valid hexadecimal number.
+    Size,
+    /// A chunk size or trailer line is longer than the maximum length.
+    TooLong,
+    /// A line or chunk is not terminated by `\r\n`.
+    Crlf,
+}
+
+impl fmt::Display for ChunkedError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Size => f.write_str("invalid chunk size"),
+            Self::TooLong => f.write_str("chunk line too long"),
+            Self::Crlf => f.write_str("missing crlf in chunked body"),
+        }
+    }
+}
+
+impl core::error::Error for ChunkedError {}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use std::vec::Vec;
+
+    use super::*;
+    use crate::endian::LittleEndian;
+
+    fn encode<T: ?Sized, E: Encoder<T>>(mut encoder: E, item: &T) -> Vec<u8> {
+        let mut buf = [0; 32];
+        let mut dst = &mut buf[..];
+
+        encoder.encode(item, &mut dst).unwrap();
+
+        let len = 32 - dst.len();
+
+        buf[..len].to_vec()
+    }
+
+    fn encode_err<T: ?Sized, E: Encoder<T>>(mut encoder: E, item: &T) -> EError<E::Error> {
+        let mut buf = [0; 32];
+
+        encoder.encode(item, &mut &mut buf[..]).unwrap_err()
+    }
+
+    fn incomplete<T, E>(needed: usize) -> Result<T, DError<E>> {
+        Err(DError::Incomplete {
+            needed: Size::new(needed),
+        })
+    }
+
+    fn fatal<T, E>(error: E) -> Result<T, DError<E>> {
+        Err(DError::Fatal { error })
+    }
+
+    #[test]
+    fn lines() {
+        let mut lines = Lines::new();
+        let mut src = &b"a\nb\r\nc"[..];
+
+        assert_eq!(lines.decode(&mut src), Ok(&b"a"[..]));
+        assert_eq!(lines.decode(&mut src), Ok(&b"b\r"[..]));
+        assert_eq!(lines.decode(&mut src), incomplete(1));
+        assert_eq!(src, b"c");
+        assert_eq!(lines.decode_eof(&mut src), Ok(&b"c"[..]));
+        assert_eq!(lines.decode_eof(&mut src), Err(DError::Eof));
+
+        let mut lines = Lines::new().with_crlf(true);
+        let mut src = &b"a\nb\r\n"[..];
+
+        assert_eq!(lines.decode(&mut src), Ok(&b"a"[..]));
+        assert_eq!(lines.decode(&mut src), Ok(&b"b"[..]));
+
+        assert_eq!(encode(Lines::new(), &b"abc"[..]), b"abc\n");
+        assert_eq!(
+            encode(Lines::new().with_crlf(true), &b"abc"[..]),
+            b"abc\r\n"
+        );
+        assert_eq!(
+            encode_err(Lines::new(), &b"a\nb"[..]),
+            EError::Fatal {
+                error: LinesError::Newline
+            }
+        );
+    }
+
+    #[test]
+    fn lines_resume_search() {
+        let mut lines = Lines::new();
+        let buf = b"abc\ndef";
+
+        // Feed the stream a byte at a time, as a driver would.
+        for len in 0..4 {
+            assert_eq!(lines.decode(&mut &buf[..len]), incomplete(1));
+        }
+
+        let mut src = &buf[..];
+
+        assert_eq!(lines.decode(&mut src), Ok(&b"abc"[..]));
+        assert_eq!(src, b"def");
+    }
+
+    #[test]
+    fn lines_too_long() {
+        let mut lines = Lines::new().with_max_len(3);
+
+        assert_eq!(lines.decode(&mut &b"abc"[..]), incomplete(1));
+        assert_eq!(lines.decode(&mut &b"abcd"[..]), fatal(LinesError::TooLong));
+        assert_eq!(
+            lines.decode(&mut &b"abcd\n"[..]),
+            fatal(LinesError::TooLong)
+        );
+        assert_eq!(lines.decode(&mut &b"abc\n"[..]), Ok(&b"abc"[..]));
+
+        // The `\r` of a `\r\n` does not count towards the length.
+        let mut lines = Lines::new().with_crlf(true).with_max_len(3);
+
+        assert_eq!(lines.decode(&mut &b"abc\r"[..]), incomplete(1));
+        assert_eq!(lines.decode(&mut &b"abc\r\n"[..]), Ok(&b"abc"[..]));
+        assert_eq!(
+            encode_err(Lines::new().with_max_len(3), &b"abcd"[..]),
+            EError::Fatal {
+                error: LinesError::TooLong
+            }
+        );
+    }
+
+    #[test]
+    fn str_lines() {
+        let mut lines = StrLines::new();
+        let mut src = &b"h\xc3\xa9\n\xff\n"[..];
+
+        assert_eq!(lines.decode(&mut src), Ok("h\u{e9}"));
+        assert!(matches!(
+            lines.decode(&mut src),
+            Err(DError::Fatal {
+                error: LinesError::Utf8(_)
+            })
+        ));
+        assert_eq!(src, b"\xff\n");
+
+        assert_eq!(encode(StrLines::new(), "h\u{e9}"), b"h\xc3\xa9\n");
+    }
+
+    #[test]
+    fn delimited() {
+        let mut delimited = Delimited::new(&b"\r\n\r\n"[..]);
+        let buf = b"ab\r\n\r\ncd";
+
+        // The delimiter straddles where each search ends.
+        for len in 0..6 {
+            assert_eq!(delimited.decode(&mut &buf[..len]), incomplete(1));
+        }
+
+        let mut src = &buf[..];
+
+        assert_eq!(delimited.decode(&mut src), Ok(&b"ab"[..]));
+        assert_eq!(delimited.decode(&mut src), incomplete(1));
+        assert_eq!(delimited.decode_eof(&mut src), Ok(&b"cd"[..]));
+        assert_eq!(delimited.decode_eof(&mut src), Err(DError::Eof));
+
+        assert_eq!(encode(Delimited::new(&b"||"[..]), &b"abc"[..]), b"abc||");
+        assert_eq!(
+            encode_err(Delimited::new(&b"||"[..]), &b"a||b"[..]),
+            EError::Fatal {
+                error: DelimitedError::Delimiter
+            }
+        );
+    }
+
+    #[test]
+    fn delimited_edge_cases() {
+        let mut delimited = Delimited::new(&b""[..]);
+
+        assert_eq!(
+            delimited.decode(&mut &b"abc"[..]),
+            fatal(DelimitedError::EmptyDelimiter)
+        );
+        assert_eq!(
+            encode_err(delimited, &b"abc"[..]),
+            EError::Fatal {
+                error: DelimitedError::EmptyDelimiter
+            }
+        );
+
+        let mut delimited = Delimited::new(&b"||"[..]).with_max_len(2);
+
+        assert_eq!(delimited.decode(&mut &b"ab|"[..]), incomplete(1));
+        assert_eq!(
+            delimited.decode(&mut &b"abc|"[..]),
+            fatal(DelimitedError::TooLong)
+        );
+        assert_eq!(
+            delimited.decode(&mut &b"abc||"[..]),
+            fatal(DelimitedError::TooLong)
+        );
+        assert_eq!(delimited.decode(&mut &b"ab||"[..]), Ok(&b"ab"[..]));
+    }
+
+    #[test]
+    fn netstring_round_trip() {
+        let cases: [(&[u8], &[u8]); 3] = [
+            (b"", b"0:,"),
+            (b"hello", b"5:hello,"),
+            (b"0123456789", b"10:0123456789,"),
+        ];
+
+        for (payload, bytes) in cases {
+            assert_eq!(encode(Netstring::new(), payload), bytes);
+
+            let mut src = bytes;
+
+            assert_eq!(Netstring::new().decode(&mut src), Ok(payload));
+            assert!(src.is_empty());
+        }
+    }
+
+    #[test]
+    fn netstring_malformed() {
+        let mut netstring = Netstring::new();
+
+        assert_eq!(netstring.decode(&mut &b""[..]), incomplete(1));
+        assert_eq!(netstring.decode(&mut &b"5"[..]), incomplete(1));
+        assert_eq!(netstring.decode(&mut &b"5:hel"[..]), incomplete(3));
+        assert_eq!(netstring.decode(&mut &b"5:hello"[..]), incomplete(1));
+        assert_eq!(
+            netstring.decode(&mut &b"5:hello;"[..]),
+            fatal(NetstringError::Comma)
+        );
+        assert_eq!(
+            netstring.decode(&mut &b":,"[..]),
+            fatal(NetstringError::Length)
+        );
+        assert_eq!(
+            netstring.decode(&mut &b"01:a,"[..]),
+            fatal(NetstringError::Length)
+        );
+        assert_eq!(
+            netstring.decode(&mut &b"99999999999999999999999:"[..]),
+            fatal(NetstringError::TooLong)
+        );
+
+        let mut netstring = Netstring::new().with_max_len(4);
+
+        assert_eq!(
+            netstring.decode(&mut &b"5:"[..]),
+            fatal(NetstringError::TooLong)
+        );
+        assert_eq!(
+            encode_err(netstring, &b"hello"[..]),
+            EError::Fatal {
+                error: NetstringError::TooLong
+            }
+        );
+    }
+
+    #[test]
+    fn length_delimited_round_trip() {
+        let cases: [(LengthDelimited<BigEndian>, &[u8]); 4] = [
+            (LengthDelimited::default(), b"\0\0\0\x03abc"),
+            (
+                LengthDelimited::new(BigEndian).with_len_size(2),
+                b"\0\x03abc",
+            ),
+            (
+                LengthDelimited::new(BigEndian)
+                    .with_len_size(1)
+                    .with_len_includes_header(true),
+                b"\x04abc",
+            ),
+            (
+                LengthDelimited::new(BigEndian)
+                    .with_len_offset(1)
+                    .with_len_size(1)
+                    .with_len_adjustment(-1),
+                b"\0\x04abc",
+            ),
+        ];
+
+        for (codec, bytes) in cases {
+            assert_eq!(encode(codec.clone(), &b"abc"[..]), bytes);
+
+            let mut src = bytes;
+
+            assert_eq!(codec.clone().decode(&mut src), Ok(&b"abc"[..]));
+            assert!(src.is_empty());
+        }
+
+        let codec = LengthDelimited::new(LittleEndian).with_len_size(3);
+
+        assert_eq!(encode(codec.clone(), &b"abc"[..]), b"\x03\0\0abc");
+
+        let mut codec = codec.with_keep_header(true);
+
+        assert_eq!(
+            codec.decode(&mut &b"\x03\0\0abc"[..]),
+            Ok(&b"\x03\0\0abc"[..])
+        );
+    }
+
+    #[test]
+    fn length_delimited_malformed() {
+        let mut codec = LengthDelimited::default();
+
+        assert_eq!(codec.decode(&mut &b"\0\0"[..]), incomplete(2));
+        assert_eq!(codec.decode(&mut &b"\0\0\0\x03a"[..]), incomplete(2));
+
+        let mut codec = LengthDelimited::default().with_max_len(2);
+
+        assert_eq!(
+            codec.decode(&mut &b"\0\0\0\x03"[..]),
+            fatal(LengthDelimitedError::TooLong)
+        );
+        assert_eq!(
+            encode_err(codec, &b"abc"[..]),
+            EError::Fatal {
+                error: LengthDelimitedError::TooLong
+            }
+        );
+
+        // The length field is smaller than the header it includes.
+        let mut codec = LengthDelimited::default().with_len_includes_header(true);
+
+        assert_eq!(
+            codec.decode(&mut &b"\0\0\0\x03"[..]),
+            fatal(LengthDelimitedError::Length)
+        );
+
+        // The length does not fit in the length field.
+        let codec = LengthDelimited::default().with_len_size(1);
+
+        assert_eq!(
+            encode_err(codec, &[0; 256][..]),
+            EError::Fatal {
+                error: LengthDelimitedError::Length
+            }
+        );
+
+        let mut buf = [0; 4];
+
+        assert_eq!(
+            LengthDelimited::default().encode(&b"abc"[..], &mut &mut buf[..]),
+            Err(EError::Full {
+                needed: Size::new(3)
+            })
+        );
+    }
+
+    #[test]
+    fn length_delimited_field_size() {
+        for len_size in [0, 9] {
+            let mut codec = LengthDelimited::default().with_len_size(len_size);
+
+            assert_eq!(
+                codec.decode(&mut &[0; 16][..]),
+                fatal(LengthDelimitedError::FieldSize)
+            );
+            assert_eq!(
+                encode_err(codec, &b"abc"[..]),
+                EError::Fatal {
+                    error: LengthDelimitedError::FieldSize
+                }
+            );
+        }
+    }
+
+    #[test]
+    fn tlv_round_trip() {
+        assert_eq!(encode(Tlv::default(), &(5, &b"hi"[..])), b"\x05\x02hi");
+        assert_eq!(
+            encode(
+                Tlv::new(LittleEndian).with_tag_size(2).with_len_size(2),
+                &(0x0102, &b"hi"[..])
+            ),
+            b"\x02\x01\x02\0hi"
+        );
+        assert_eq!(
+            encode(
+                Tlv::default().with_len_includes_header(true),
+                &(5, &b"hi"[..])
+            ),
+            b"\x05\x04hi"
+        );
+
+        let mut src = &b"\x05\x04hi"[..];
+
+        assert_eq!(
+            Tlv::default()
+                .with_len_includes_header(true)
+                .decode(&mut src),
+            Ok((5, &b"hi"[..]))
+        );
+        assert!(src.is_empty());
+
+        let tlv = Tlv::default();
+        let mut records = tlv.iter(b"\x01\x00\x02\x01a\x03\x02b");
+
+        assert_eq!(records.next(), Some(Ok((1, &b""[..]))));
+        assert_eq!(records.next(), Some(Ok((2, &b"a"[..]))));
+        assert_eq!(records.remaining(), b"\x03\x02b");
+        assert_eq!(records.next(), Some(incomplete(1)));
+        assert_eq!(records.next(), None);
+    }
+
+    #[test]
+    fn tlv_malformed() {
+        let mut tlv = Tlv::default();
+
+        assert_eq!(tlv.decode(&mut &b"\x05"[..]), incomplete(1));
+        assert_eq!(tlv.decode(&mut &b"\x05\x03h"[..]), incomplete(2));
+        assert_eq!(
+            encode_err(Tlv::default(), &(256, &b""[..])),
+            EError::Fatal {
+                error: TlvError::Tag
+            }
+        );
+        assert_eq!(
+            encode_err(Tlv::default(), &(1, &[0; 256][..])),
+            EError::Fatal {
+                error: TlvError::Length
+            }
+        );
+
+        let mut tlv = Tlv::default().with_len_includes_header(true);
+
+        assert_eq!(tlv.decode(&mut &b"\x05\x01"[..]), fatal(TlvError::Length));
+
+        let mut tlv = Tlv::default().with_max_len(1);
+
+        assert_eq!(tlv.decode(&mut &b"\x05\x02"[..]), fatal(TlvError::TooLong));
+
+        for tlv in [
+            Tlv::default().with_tag_size(0),
+            Tlv::default().with_len_size(9),
+        ] {
+            assert_eq!(
+                tlv.clone().decode(&mut &[0; 16][..]),
+                fatal(TlvError::FieldSize)
+            );
+            assert_eq!(
+                encode_err(tlv, &(1, &b""[..])),
+                EError::Fatal {
+                    error: TlvError::FieldSize
+                }
+            );
+        }
+    }
+
+    #[test]
+    fn chunked() {
+        let mut chunked = Chunked::new();
+        let mut src = &b"4\r\nWiki\r\n5;ext=1\r\npedia\r\n0\r\nX: y\r\n\r\n"[..];
+
+        assert_eq!(chunked.decode(&mut src), Ok(Chunk::Data(b"Wiki")));
+        assert_eq!(chunked.decode(&mut src), Ok(Chunk::Data(b"pedia")));
+        assert_eq!(chunked.decode(&mut src), Ok(Chunk::Trailer(b"X: y")));
+        assert_eq!(chunked.decode(&mut src), Ok(Chunk::End));
+        assert!(src.is_empty());
+
+        // Payloads are yielded as they arrive.
+        let mut src = &b"A\r\n01234"[..];
+
+        assert_eq!(chunked.decode(&mut src), Ok(Chunk::Data(b"01234")));
+        assert_eq!(chunked.hint(), Size::new(5));
+        assert_eq!(chunked.decode(&mut src), incomplete(1));
+
+        let mut src = &b"56789\r"[..];
+
+        assert_eq!(chunked.decode(&mut src), Ok(Chunk::Data(b"56789")));
+        assert_eq!(chunked.decode(&mut src), incomplete(1));
+        assert_eq!(src, b"\r");
+
+        let mut src = &b"\r\n0\r\n\r\n"[..];
+
+        assert_eq!(chunked.decode(&mut src), Ok(Chunk::End));
+        assert!(src.is_empty());
+    }
+
+    #[test]
+    fn chunked_malformed() {
+        let mut chunked = Chunked::new();
+        let mut src = &b"4\r"[..];
+
+        assert_eq!(chunked.decode(&mut src), incomplete(1));
+        assert_eq!(src, b"4\r");
+
+        assert_eq!(
+            chunked.decode(&mut &b"g\r\n"[..]),
+            fatal(ChunkedError::Size)
+        );
+        assert_eq!(
+            chunked.decode(&mut &b";x\r\n"[..]),
+            fatal(ChunkedError::Size)
+        );
+        assert_eq!(
+            chunked.decode(&mut &b"11111111111111111\r\n"[..]),
+            fatal(ChunkedError::Size)
+        );
+        assert_eq!(chunked.decode(&mut &b"4\n"[..]), fatal(ChunkedError::Crlf));
+
+        let mut src = &b"4\r\nWikiXX"[..];
+
+        assert_eq!(chunked.decode(&mut src), Ok(Chunk::Data(b"Wiki")));
+        assert_eq!(chunked.decode(&mut src), fatal(ChunkedError::Crlf));
+
+        let mut chunked = Chunked::new().with_max_line_len(2);
+
+        assert_eq!(chunked.decode(&mut &b"12"[..]), incomplete(1));
+        assert_eq!(
+            chunked.decode(&mut &b"12345"[..]),
+            fatal(ChunkedError::TooLong)
+        );
+    }
+}
@@ -0,0 +1,191 @@
+use core::fmt;
+
+use crate::reader::At;
+
+/// The amount of bytes on each row.
+const ROW_LEN: usize = 16;
+
+/// A [`Display`](fmt::Display) adapter rendering bytes as a hexdump, with
+/// an optional caret pointing at a single byte.
+///
+/// Each row shows the offset, the bytes in hex, and the bytes as ASCII:
+///
+/// ```text
+/// 00000000  6b 6f 64 65 6b 00 ff 0a                           |kodek...|
+///                             ^^
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Hexdump<'a> {
+    bytes: &'a [u8],
+    /// The offset of the first byte, as shown in the first row.
+    pub base: u64,
+    /// The offset into the bytes to point a caret at.
+    pub caret: Option<usize>,
+    /// The amount of rows to show before and after the row with the caret.
+    ///
+    /// Every row is shown when there is no caret.
+    pub context: usize,
+}
+
+impl<'a> Hexdump<'a> {
+    /// Create a new [`Hexdump`] of `bytes`.
+    #[inline]
+    #[must_use]
+    pub const fn new(bytes: &'a [u8]) -> Hexdump<'a> {
+        Hexdump {
+            bytes,
+            base: 0,
+            caret: None,
+            context: 2,
+        }
+    }
+
+    /// Set the offset of the first byte, as shown in the first row.
+    #[inline]
+    #[must_use]
+    pub const fn with_base(mut self, base: u64) -> Hexdump<'a> {
+        self.base = base;
+        self
+    }
+
+    /// Point a caret at the byte at `offset`, which may be just past the
+    /// last byte.
+    #[inline]
+    #[must_use]
+    pub const fn with_caret(mut self, offset: usize) -> Hexdump<'a> {
+        self.caret = Some(offset);
+        self
+    }
+
+    /// Set the amount of rows to show before and after the row with the
+    /// caret.
+    #[inline]
+    #[must_use]
+    pub const fn with_context(mut self, context: usize) -> Hexdump<'a> {
+        self.context = context;
+        self
+    }
+
+    /// Returns the column of the hex digits of the `i`th byte of a row.
+    const fn column(i: usize) -> usize {
+        10 + 3 * i + if i >= ROW_LEN / 2 { 1 } else { 0 }
+    }
+
+    fn row(&self, f: &mut fmt::Formatter<'_>, row: usize) -> fmt::Result {
+        let start = row * ROW_LEN;
+        let bytes =
+            &self.bytes[start.min(self.bytes.len())..(start + ROW_LEN).min(self.bytes.len())];
+
+        write!(f, "{:08x} ", self.base + start as u64)?;
+
+        for i in 0..ROW_LEN {
+            if i == ROW_LEN / 2 {
+                f.write_str(" ")?;
+            }
+
+            match bytes.get(i) {
+                Some(byte) => write!(f, " {byte:02x}")?,
+                None => f.write_str("   ")?,
+            }
+        }
+
+        f.write_str("  |")?;
+
+        for &byte in bytes {
+            let ch = if byte.is_ascii_graphic() || byte == b' ' {
+                char::from(byte)
+            } else {
+                '.'
+            };
+
+            write!(f, "{ch}")?;
+        }
+
+        f.write_str("|")
+    }
+}
+
+impl fmt::Display for Hexdump<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let rows = self.bytes.len().div_ceil(ROW_LEN);
+
+        let Some(caret) = self.caret else {
+            for row in 0..rows {
+                if row > 0 {
+                    f.write_str("\n")?;
+                }
+
+                self.row(f, row)?;
+            }
+
+            return Ok(());
+        };
+
+        let caret_row = caret / ROW_LEN;
+        let first = caret_row.saturating_sub(self.context);
+        let last = caret_row
+            .saturating_add(self.context)
+            .min(rows.max(caret_row + 1) - 1);
+
+        for row in first..=last {
+            if row > first {
+                f.write_str("\n")?;
+            }
+
+            self.row(f, row)?;
+
+            if row == caret_row {
+                let column = Self::column(caret % ROW_LEN);
+
+                write!(f, "\n{:column$}^^", "")?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// A [`Display`](fmt::Display) adapter rendering an error, followed by a
+/// [`Hexdump`] of the frame it occurred in with a caret at the failing
+/// byte.
+///
+/// This is usually created with [`At::diagnose`].
+#[derive(Debug, Clone, Copy)]
+pub struct Diagnostic<'a, E> {
+    error: &'a At<E>,
+    hexdump: Hexdump<'a>,
+}
+
+impl<'a, E> Diagnostic<'a, E> {
+    /// Create a new [`Diagnostic`] for `error`, which occurred in `frame`.
+    #[inline]
+    #[must_use]
+    pub const fn new(error: &'a At<E>, frame: &'a [u8]) -> Diagnostic<'a, E> {
+        Diagnostic {
+            error,
+            hexdump: Hexdump::new(frame).with_caret(error.offset),
+        }
+    }
+
+    /// Set the amount of rows to show before and after the failing byte.
+    #[inline]
+    #[must_use]
+    pub const fn with_context(mut self, context: usize) -> Diagnostic<'a, E> {
+        self.hexdump.context = context;
+        self
+    }
+
+    /// Set the offset of the frame in the stream, as shown in the hexdump.
+    #[inline]
+    #[must_use]
+    pub const fn with_base(mut self, base: u64) -> Diagnostic<'a, E> {
+        self.hexdump.base = base;
+        self
+    }
+}
+
+impl<E: fmt::Display> fmt::Display for Diagnostic<'_, E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}\n{}", self.error, self.hexdump)
+    }
+}
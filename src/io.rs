@@ -0,0 +1,613 @@
+use core::{convert::Infallible, fmt, num::NonZeroUsize};
+
+#[cfg(any(feature = "std", feature = "embedded-io"))]
+use core::task::Poll;
+
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+use crate::decoder::{Decoder, Error as DError};
+use crate::reader::At;
+use crate::Size;
+
+#[cfg(any(
+    feature = "std",
+    feature = "embedded-io",
+    feature = "embedded-io-async"
+))]
+use crate::encoder::{Encoder, Error as EError};
+
+#[cfg(feature = "std")]
+mod blocking;
+
+#[cfg(feature = "std")]
+pub use blocking::{Framed, FramedRead, FramedWrite};
+
+/// Drivers over the [`embedded_io`] traits, for `no_std` targets.
+#[cfg(feature = "embedded-io")]
+pub mod embedded;
+
+/// Drivers over the async [`embedded_io_async`] traits, for `no_std`
+/// targets.
+#[cfg(feature = "embedded-io-async")]
+pub mod embedded_async;
+
+/// Drivers over the async [`futures_io`] traits.
+#[cfg(feature = "futures")]
+pub mod futures;
+
+/// The default amount of bytes to read at once.
+const READ_SIZE: usize = 8 * 1024;
+
+/// The default amount of buffered bytes at which frames are written out.
+#[cfg(any(
+    feature = "std",
+    feature = "embedded-io",
+    feature = "embedded-io-async"
+))]
+const HIGH_WATERMARK: usize = 8 * 1024;
+
+/// Trait for the storage of the bytes buffered by a driver.
+///
+/// Fixed-size storage is never grown, so frames that do not fit in it
+/// result in [`Error::Full`].
+pub trait Storage: AsRef<[u8]> + AsMut<[u8]> {
+    /// Try to grow the storage to at least `len` bytes, returning whether
+    /// it is now at least that large.
+    #[inline]
+    fn grow(&mut self, len: usize) -> bool {
+        self.as_ref().len() >= len
+    }
+}
+
+impl<const N: usize> Storage for [u8; N] {}
+
+impl Storage for &mut [u8] {}
+
+#[cfg(feature = "alloc")]
+impl Storage for Vec<u8> {
+    #[inline]
+    fn grow(&mut self, len: usize) -> bool {
+        if self.len() < len {
+            self.resize(len, 0);
+        }
+
+        true
+    }
+}
+
+/// The result of reading the next frame.
+type NextFrame<'a, D, I> =
+    Result<Option<<D as Decoder>::Item<'a>>, Error<I, <D as Decoder>::Error>>;
+
+/// The read half of a driver: a buffer of bytes that are yet to be decoded.
+#[derive(Debug)]
+struct ReadState<B> {
+    buf: B,
+    start: usize,
+    end: usize,
+    read_size: usize,
+    eof: bool,
+    /// The offset into the stream of the start of the buffer.
+    position: u64,
+}
+
+impl<B: Storage> ReadState<B> {
+    const fn new(buf: B) -> ReadState<B> {
+        ReadState {
+            buf,
+            start: 0,
+            end: 0,
+            read_size: READ_SIZE,
+            eof: false,
+            position: 0,
+        }
+    }
+
+    fn buffer(&self) -> &[u8] {
+        &self.buf.as_ref()[self.start..self.end]
+    }
+
+    /// Try to decode the next frame from the buffered bytes, returning the
+    /// amount of bytes to read if more are needed.
+    ///
+    /// # Safety
+    ///
+    /// The buffer must not be modified for as long as `'a`, which must not
+    /// outlive the borrow of `self`. This only exists to work around the
+    /// borrow checker rejecting the conditional return of a borrow within
+    /// a loop.
+    unsafe fn decode<'a, D: Decoder, I>(
+        &mut self,
+        decoder: &mut D,
+    ) -> Result<NextFrame<'a, D, I>, usize> {
+        // SAFETY: Upheld by the caller.
+        let mut src: &'a [u8] = unsafe { &*core::ptr::from_ref(self.buffer()) };
+        let len = src.len();
+
+        let result = if self.eof {
+            decoder.decode_eof(&mut src)
+        } else {
+            decoder.decode(&mut src)
+        };
+
+        let needed = match result {
+            Ok(item) => {
+                self.start += len - src.len();
+                self.position += (len - src.len()) as u64;
+
+                return Ok(Ok(Some(item)));
+            }
+            Err(DError::Eof) => return Ok(Ok(None)),
+            Err(DError::Fatal { error }) => {
+                return Ok(Err(Error::Codec {
+                    error,
+                    position: self.position,
+                }))
+            }
+            Err(DError::DataRemains | DError::Incomplete { .. }) if self.eof => {
+                return Ok(Err(Error::Truncated))
+            }
+            Err(DError::DataRemains) => Size::Unknown,
+            Err(DError::Incomplete { needed }) => needed,
+        };
+
+        Err(needed
+            .get()
+            .or_else(|| decoder.size_hint().min.get())
+            .map_or(1, NonZeroUsize::get))
+    }
+
+    /// Returns the unfilled part of the buffer, with room for at least
+    /// `needed` bytes.
+    fn spare<I, E>(&mut self, needed: usize) -> Result<&mut [u8], Error<I, E>> {
+        if self.start > 0 {
+            self.buf.as_mut().copy_within(self.start..self.end, 0);
+            self.end -= self.start;
+            self.start = 0;
+        }
+
+        if !self.buf.grow(self.end + needed.max(self.read_size))
+            && self.buf.as_ref().len() < self.end + needed
+        {
+            return Err(Error::Full);
+        }
+
+        Ok(&mut self.buf.as_mut()[self.end..])
+    }
+
+    /// Mark `n` bytes of the unfilled part of the buffer as filled, where
+    /// zero bytes marks the end of the stream.
+    #[cfg(any(
+        feature = "std",
+        feature = "embedded-io",
+        feature = "embedded-io-async"
+    ))]
+    fn fill(&mut self, n: usize) {
+        self.end += n;
+        self.eof |= n == 0;
+    }
+}
+
+#[cfg(any(feature = "std", feature = "embedded-io"))]
+impl<B: Storage> ReadState<B> {
+    /// Decode the next frame, using `read` to read more bytes when needed.
+    fn poll_next_frame<'a, D: Decoder, I>(
+        &'a mut self,
+        decoder: &mut D,
+        mut read: impl FnMut(&mut [u8]) -> Poll<Result<usize, I>>,
+    ) -> Poll<NextFrame<'a, D, I>> {
+        loop {
+            // SAFETY: Frames borrow the buffer for as long as `self` is
+            // borrowed, so it cannot be modified while they are alive.
+            let needed = match unsafe { self.decode(decoder) } {
+                Ok(result) => return Poll::Ready(result),
+                Err(needed) => needed,
+            };
+
+            let spare = match self.spare(needed) {
+                Ok(spare) => spare,
+                Err(error) => return Poll::Ready(Err(error)),
+            };
+
+            match read(spare) {
+                Poll::Ready(Ok(n)) => self.fill(n),
+                Poll::Ready(Err(error)) => return Poll::Ready(Err(Error::Io(error))),
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+
+    /// Decode the next frame, using `read` to read more bytes when needed.
+    fn next_frame<'a, D: Decoder, I>(
+        &'a mut self,
+        decoder: &mut D,
+        mut read: impl FnMut(&mut [u8]) -> Result<usize, I>,
+    ) -> NextFrame<'a, D, I> {
+        ready(self.poll_next_frame(decoder, |buf| Poll::Ready(read(buf))))
+    }
+}
+
+/// A sans-IO driver that accumulates chunks of bytes and decodes frames
+/// from them using a [`Decoder`].
+///
+/// Bytes are either copied in with [`Accumulator::push`], or read directly
+/// into [`Accumulator::spare`] and marked as such with
+/// [`Accumulator::fill`]. Frames are then taken out with
+/// [`Accumulator::next_frame`] until it returns `None`, at which point more
+/// bytes are needed. Once the stream has ended, [`Accumulator::finish`]
+/// makes the remaining frames be decoded with [`Decoder::decode_eof`].
+///
+/// ```
+/// use kodek::{framing::Lines, io::Accumulator};
+///
+/// let mut lines = Accumulator::with_storage(Lines::new(), [0; 64]);
+///
+/// lines.push(b"hello\nwor").unwrap();
+/// assert_eq!(lines.next_frame().unwrap(), Some(&b"hello"[..]));
+/// assert_eq!(lines.next_frame().unwrap(), None);
+///
+/// lines.push(b"ld").unwrap();
+/// lines.finish();
+/// assert_eq!(lines.next_frame().unwrap(), Some(&b"world"[..]));
+/// assert_eq!(lines.next_frame().unwrap(), None);
+/// ```
+#[derive(Debug)]
+pub struct Accumulator<D, B> {
+    decoder: D,
+    state: ReadState<B>,
+    needed: usize,
+}
+
+#[cfg(feature = "alloc")]
+impl<D: Decoder> Accumulator<D, Vec<u8>> {
+    /// Create a new [`Accumulator`] with a growable buffer.
+    #[inline]
+    #[must_use]
+    pub const fn new(decoder: D) -> Accumulator<D, Vec<u8>> {
+        Accumulator::with_storage(decoder, Vec::new())
+    }
+}
+
+impl<D: Decoder, B: Storage> Accumulator<D, B> {
+    /// Create a new [`Accumulator`] buffering into `storage`.
+    #[inline]
+    #[must_use]
+    pub const fn with_storage(decoder: D, storage: B) -> Accumulator<D, B> {
+        Accumulator {
+            decoder,
+            state: ReadState::new(storage),
+            needed: 1,
+        }
+    }
+
+    /// Append `bytes` to the buffer.
+    ///
+    /// Returns [`Error::Full`] without appending anything if they do not fit
+    /// in the storage.
+    pub fn push(&mut self, bytes: &[u8]) -> Result<(), Error<Infallible, D::Error>> {
+        if !bytes.is_empty() {
+            self.state.spare(bytes.len())?[..bytes.len()].copy_from_slice(bytes);
+            self.fill(bytes.len());
+        }
+
+        Ok(())
+    }
+
+    /// Returns the unfilled part of the buffer, with room for at least the
+    /// amount of bytes needed to decode the next frame.
+    ///
+    /// Bytes written to it must be marked as filled with
+    /// [`Accumulator::fill`].
+    #[inline]
+    pub fn spare(&mut self) -> Result<&mut [u8], Error<Infallible, D::Error>> {
+        self.state.spare(self.needed)
+    }
+
+    /// Mark the first `n` bytes of [`Accumulator::spare`] as filled.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is larger than the unfilled part of the buffer.
+    #[inline]
+    pub fn fill(&mut self, n: usize) {
+        assert!(
+            n <= self.state.buf.as_ref().len() - self.state.end,
+            "filled more bytes than are available"
+        );
+
+        self.state.end += n;
+    }
+
+    /// Mark the end of the stream.
+    #[inline]
+    pub fn finish(&mut self) {
+        self.state.eof = true;
+    }
+
+    /// Try to decode the next frame.
+    ///
+    /// Returns `None` if more bytes are needed or, once the stream has been
+    /// finished, if every frame has been decoded.
+    #[inline]
+    pub fn next_frame(&mut self) -> NextFrame<'_, D, Infallible> {
+        // SAFETY: Frames borrow the buffer for as long as `self` is borrowed,
+        // so it cannot be modified while they are alive.
+        match unsafe { self.state.decode(&mut self.decoder) } {
+            Ok(result) => result,
+            Err(needed) => {
+                self.needed = needed;
+
+                Ok(None)
+            }
+        }
+    }
+}
+
+impl<D, B: Storage> Accumulator<D, B> {
+    /// Returns whether the end of the stream has been marked.
+    #[inline]
+    #[must_use]
+    pub const fn is_finished(&self) -> bool {
+        self.state.eof
+    }
+
+    /// Returns the amount of bytes needed to decode the next frame, as of
+    /// the last call to [`Accumulator::next_frame`].
+    ///
+    /// This is a lower bound, and is `1` if the decoder gave no better
+    /// estimate.
+    #[inline]
+    #[must_use]
+    pub const fn needed(&self) -> usize {
+        self.needed
+    }
+
+    /// Returns a reference to the decoder.
+    #[inline]
+    #[must_use]
+    pub const fn decoder(&self) -> &D {
+        &self.decoder
+    }
+
+    /// Returns a mutable reference to the decoder.
+    #[inline]
+    #[must_use]
+    pub fn decoder_mut(&mut self) -> &mut D {
+        &mut self.decoder
+    }
+
+    /// Returns the bytes that have been accumulated but not yet decoded.
+    #[inline]
+    #[must_use]
+    pub fn buffer(&self) -> &[u8] {
+        self.state.buffer()
+    }
+
+    /// Returns the decoder, discarding any buffered bytes.
+    #[inline]
+    #[must_use]
+    pub fn into_decoder(self) -> D {
+        self.decoder
+    }
+}
+
+/// The write half of a driver: a buffer of bytes that are yet to be written.
+#[cfg(any(
+    feature = "std",
+    feature = "embedded-io",
+    feature = "embedded-io-async"
+))]
+#[derive(Debug)]
+struct WriteState<B> {
+    buf: B,
+    len: usize,
+    high_watermark: usize,
+    /// The offset into the stream of the end of the buffer.
+    position: u64,
+}
+
+#[cfg(any(
+    feature = "std",
+    feature = "embedded-io",
+    feature = "embedded-io-async"
+))]
+impl<B: Storage> WriteState<B> {
+    const fn new(buf: B) -> WriteState<B> {
+        WriteState {
+            buf,
+            len: 0,
+            high_watermark: HIGH_WATERMARK,
+            position: 0,
+        }
+    }
+
+    fn buffer(&self) -> &[u8] {
+        &self.buf.as_ref()[..self.len]
+    }
+
+    /// Returns whether the buffer has reached the high watermark.
+    fn is_full(&self) -> bool {
+        self.len >= self.high_watermark
+    }
+
+    /// Encode a frame into the buffer.
+    ///
+    /// Returns [`EError::Full`] if the frame does not fit in the storage
+    /// along with what is already buffered.
+    fn encode<E, Item>(&mut self, encoder: &mut E, item: &Item) -> Result<(), EError<E::Error>>
+    where
+        E: Encoder<Item>,
+        Item: ?Sized,
+    {
+        let mut reserve = encoder.encode_hint(item).get_or_one().get();
+
+        loop {
+            self.buf.grow(self.len + reserve);
+
+            let mut dst = &mut self.buf.as_mut()[self.len..];
+            let available = dst.len();
+
+            match encoder.encode(item, &mut dst) {
+                Ok(()) => {
+                    self.len += available - dst.len();
+                    self.position += (available - dst.len()) as u64;
+
+                    return Ok(());
+                }
+                Err(EError::Full { needed }) => {
+                    reserve = available + needed.get().map_or(available.max(1), NonZeroUsize::get);
+
+                    if !self.buf.grow(self.len + reserve) {
+                        return Err(EError::Full { needed });
+                    }
+                }
+                Err(error) => return Err(error),
+            }
+        }
+    }
+
+    /// Remove the first `n` buffered bytes, once they have been written.
+    fn consume(&mut self, n: usize) {
+        self.buf.as_mut().copy_within(n..self.len, 0);
+        self.len -= n;
+    }
+}
+
+#[cfg(any(feature = "std", feature = "embedded-io"))]
+impl<B: Storage> WriteState<B> {
+    /// Write out the whole buffer using `write`.
+    ///
+    /// `write` must not return `Ok(0)`, but an error instead.
+    fn poll_write_buf<I>(
+        &mut self,
+        mut write: impl FnMut(&[u8]) -> Poll<Result<usize, I>>,
+    ) -> Poll<Result<(), I>> {
+        let mut written = 0;
+
+        let result = loop {
+            if written == self.len {
+                break Poll::Ready(Ok(()));
+            }
+
+            match write(&self.buf.as_ref()[written..self.len]) {
+                Poll::Ready(Ok(n)) => written += n,
+                result => break result.map_ok(|_| ()),
+            }
+        };
+
+        self.consume(written);
+
+        result
+    }
+
+    /// Write out the whole buffer using `write`.
+    fn write_buf<I>(&mut self, mut write: impl FnMut(&[u8]) -> Result<usize, I>) -> Result<(), I> {
+        ready(self.poll_write_buf(|buf| Poll::Ready(write(buf))))
+    }
+
+    /// Encode a frame into the buffer, using `write` to write out the buffer
+    /// when it is full or reaches the high watermark.
+    fn write_frame<E, Item, I>(
+        &mut self,
+        encoder: &mut E,
+        item: &Item,
+        mut write: impl FnMut(&[u8]) -> Result<usize, Error<I, E::Error>>,
+    ) -> Result<(), Error<I, E::Error>>
+    where
+        E: Encoder<Item>,
+        Item: ?Sized,
+    {
+        loop {
+            match self.encode(encoder, item) {
+                Ok(()) => break,
+                Err(EError::Fatal { error }) => {
+                    return Err(Error::Codec {
+                        error,
+                        position: self.position,
+                    })
+                }
+                Err(EError::Full { .. }) if self.len == 0 => return Err(Error::Full),
+                Err(EError::Full { .. }) => self.write_buf(&mut write)?,
+            }
+        }
+
+        if self.is_full() {
+            self.write_buf(write)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Unwrap the result of polling blocking IO, which is always ready.
+#[cfg(any(feature = "std", feature = "embedded-io"))]
+fn ready<T>(poll: Poll<T>) -> T {
+    match poll {
+        Poll::Ready(output) => output,
+        Poll::Pending => unreachable!("blocking io is always ready"),
+    }
+}
+
+/// Type for errors that may occur while framing a byte stream.
+#[derive(Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Error<I, E> {
+    /// The underlying IO failed.
+    Io(I),
+    /// The stream ended in the middle of a frame.
+    Truncated,
+    /// The frame does not fit in the storage of the driver.
+    Full,
+    /// The writer accepted no bytes.
+    WriteZero,
+    /// The codec failed.
+    ///
+    /// Drivers only know where the failing frame starts. The offset within
+    /// the frame is part of `error` when the codec reports it, as codecs
+    /// built on [`Reader`](crate::Reader) do with [`At`], in which case
+    /// [`Error::offset`] combines the two.
+    Codec {
+        /// The error.
+        error: E,
+        /// The offset into the stream of the start of the frame that failed
+        /// to be decoded or encoded.
+        position: u64,
+    },
+}
+
+impl<I, E> Error<I, At<E>> {
+    /// Returns the offset into the stream at which a codec built on
+    /// [`Reader`](crate::Reader) failed, which is the offset of the failing
+    /// frame plus the offset within it.
+    ///
+    /// Errors of other codecs have no offset within the frame, only the
+    /// [`position`](Error::Codec::position) of the frame.
+    #[inline]
+    #[must_use]
+    pub const fn offset(&self) -> Option<u64> {
+        match self {
+            Self::Codec { error, position } => Some(*position + error.offset as u64),
+            _ => None,
+        }
+    }
+}
+
+impl<I: fmt::Display, E: fmt::Display> fmt::Display for Error<I, E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(error) => write!(f, "io error: {error}"),
+            Self::Truncated => f.write_str("stream ended in the middle of a frame"),
+            Self::Full => f.write_str("frame does not fit in the buffer"),
+            Self::WriteZero => f.write_str("writer accepted no bytes"),
+            Self::Codec { error, position } => {
+                write!(f, "codec error in frame at byte {position}: {error}")
+            }
+        }
+    }
+}
+
+impl<I: fmt::Display + fmt::Debug, E: fmt::Display + fmt::Debug> core::error::Error
+    for Error<I, E>
+{
+}
@@ -0,0 +1,490 @@
+use std::{
+    io::{self, ErrorKind, Read, Write},
+    vec::Vec,
+};
+
+use super::{Error, ReadState, WriteState};
+use crate::decoder::Decoder;
+use crate::encoder::Encoder;
+
+/// Read from `reader`, retrying when interrupted.
+fn read<R: Read>(reader: &mut R, buf: &mut [u8]) -> io::Result<usize> {
+    loop {
+        match reader.read(buf) {
+            Err(error) if error.kind() == ErrorKind::Interrupted => {}
+            result => return result,
+        }
+    }
+}
+
+/// Write to `writer`, retrying when interrupted.
+fn write<W: Write>(writer: &mut W, buf: &[u8]) -> io::Result<usize> {
+    loop {
+        match writer.write(buf) {
+            Ok(0) => return Err(ErrorKind::WriteZero.into()),
+            Err(error) if error.kind() == ErrorKind::Interrupted => {}
+            result => return result,
+        }
+    }
+}
+
+/// A driver that reads frames from a [`Read`] using a [`Decoder`].
+///
+/// Bytes are accumulated in an internal buffer until the decoder can
+/// produce a frame. Reads request at least as many bytes as the decoder
+/// reports are needed, falling back to [`Decoder::hint`].
+#[derive(Debug)]
+pub struct FramedRead<R, D> {
+    reader: R,
+    decoder: D,
+    state: ReadState<Vec<u8>>,
+}
+
+impl<R: Read, D: Decoder> FramedRead<R, D> {
+    /// Create a new [`FramedRead`] reading from `reader`.
+    #[inline]
+    #[must_use]
+    pub const fn new(reader: R, decoder: D) -> FramedRead<R, D> {
+        FramedRead {
+            reader,
+            decoder,
+            state: ReadState::new(Vec::new()),
+        }
+    }
+
+    /// Set the minimum amount of bytes to request from each read.
+    #[inline]
+    #[must_use]
+    pub fn with_read_size(mut self, read_size: usize) -> FramedRead<R, D> {
+        self.state.read_size = read_size;
+        self
+    }
+
+    /// Read the next frame.
+    ///
+    /// Returns `None` once the reader has reached the end of the stream
+    /// and every frame has been read.
+    ///
+    /// If the reader fails, buffered bytes are kept and the call may be
+    /// retried, such as after [`ErrorKind::WouldBlock`].
+    #[inline]
+    pub fn next_frame(&mut self) -> Result<Option<D::Item<'_>>, Error<io::Error, D::Error>> {
+        let reader = &mut self.reader;
+
+        self.state
+            .next_frame(&mut self.decoder, |buf| read(reader, buf))
+    }
+}
+
+impl<R, D> FramedRead<R, D> {
+    /// Returns a reference to the underlying reader.
+    #[inline]
+    #[must_use]
+    pub const fn get_ref(&self) -> &R {
+        &self.reader
+    }
+
+    /// Returns a mutable reference to the underlying reader.
+    ///
+    /// Reading from it directly may corrupt the stream of frames.
+    #[inline]
+    #[must_use]
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.reader
+    }
+
+    /// Returns a reference to the decoder.
+    #[inline]
+    #[must_use]
+    pub const fn decoder(&self) -> &D {
+        &self.decoder
+    }
+
+    /// Returns a mutable reference to the decoder.
+    #[inline]
+    #[must_use]
+    pub fn decoder_mut(&mut self) -> &mut D {
+        &mut self.decoder
+    }
+
+    /// Returns the bytes that have been read but not yet decoded.
+    #[inline]
+    #[must_use]
+    pub fn buffer(&self) -> &[u8] {
+        self.state.buffer()
+    }
+
+    /// Returns the underlying reader, discarding any buffered bytes.
+    #[inline]
+    #[must_use]
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
+
+/// A driver that writes frames to a [`Write`] using an [`Encoder`].
+///
+/// Frames are encoded into an internal buffer, which is written out once
+/// it reaches the high watermark, or when [`FramedWrite::flush`] is called.
+#[derive(Debug)]
+pub struct FramedWrite<W, E> {
+    writer: W,
+    encoder: E,
+    state: WriteState<Vec<u8>>,
+}
+
+impl<W: Write, E> FramedWrite<W, E> {
+    /// Create a new [`FramedWrite`] writing to `writer`.
+    #[inline]
+    #[must_use]
+    pub const fn new(writer: W, encoder: E) -> FramedWrite<W, E> {
+        FramedWrite {
+            writer,
+            encoder,
+            state: WriteState::new(Vec::new()),
+        }
+    }
+
+    /// Set the amount of buffered bytes at which frames are written out.
+    ///
+    /// A high watermark of zero writes out every frame immediately.
+    #[inline]
+    #[must_use]
+    pub fn with_high_watermark(mut self, high_watermark: usize) -> FramedWrite<W, E> {
+        self.state.high_watermark = high_watermark;
+        self
+    }
+
+    /// Encode a frame into the buffer, writing the buffer out if it has
+    /// reached the high watermark.
+    ///
+    /// If the buffer is too full for the frame, it is written out first to
+    /// make room. If the writer fails then, the frame has not been encoded,
+    /// but if it fails once the frame has been encoded, the frame stays
+    /// buffered.
+    #[inline]
+    pub fn write_frame<Item>(&mut self, item: &Item) -> Result<(), Error<io::Error, E::Error>>
+    where
+        Item: ?Sized,
+        E: Encoder<Item>,
+    {
+        let writer = &mut self.writer;
+
+        self.state.write_frame(&mut self.encoder, item, |buf| {
+            write(writer, buf).map_err(Error::Io)
+        })
+    }
+
+    /// Write out all buffered frames and flush the writer.
+    #[inline]
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.flush_buf()?;
+        self.writer.flush()
+    }
+
+    fn flush_buf(&mut self) -> io::Result<()> {
+        let writer = &mut self.writer;
+
+        self.state.write_buf(|buf| write(writer, buf))
+    }
+}
+
+impl<W, E> FramedWrite<W, E> {
+    /// Returns a reference to the underlying writer.
+    #[inline]
+    #[must_use]
+    pub const fn get_ref(&self) -> &W {
+        &self.writer
+    }
+
+    /// Returns a mutable reference to the underlying writer.
+    ///
+    /// Writing to it directly may corrupt the stream of frames.
+    #[inline]
+    #[must_use]
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.writer
+    }
+
+    /// Returns a reference to the encoder.
+    #[inline]
+    #[must_use]
+    pub const fn encoder(&self) -> &E {
+        &self.encoder
+    }
+
+    /// Returns a mutable reference to the encoder.
+    #[inline]
+    #[must_use]
+    pub fn encoder_mut(&mut self) -> &mut E {
+        &mut self.encoder
+    }
+
+    /// Returns the bytes that have been encoded but not yet written.
+    #[inline]
+    #[must_use]
+    pub fn buffer(&self) -> &[u8] {
+        self.state.buffer()
+    }
+
+    /// Returns the underlying writer, discarding any buffered bytes.
+    #[inline]
+    #[must_use]
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+/// A driver that both reads and writes frames over a [`Read`] and
+/// [`Write`] using a [`Codec`](crate::Codec).
+///
+/// This combines [`FramedRead`] and [`FramedWrite`] over a single IO
+/// object, such as a socket or serial port.
+#[derive(Debug)]
+pub struct Framed<T, C> {
+    io: T,
+    codec: C,
+    read: ReadState<Vec<u8>>,
+    write: WriteState<Vec<u8>>,
+}
+
+impl<T: Read + Write, C: Decoder> Framed<T, C> {
+    /// Create a new [`Framed`] over `io`.
+    #[inline]
+    #[must_use]
+    pub const fn new(io: T, codec: C) -> Framed<T, C> {
+        Framed {
+            io,
+            codec,
+            read: ReadState::new(Vec::new()),
+            write: WriteState::new(Vec::new()),
+        }
+    }
+
+    /// Set the minimum amount of bytes to request from each read.
+    #[inline]
+    #[must_use]
+    pub fn with_read_size(mut self, read_size: usize) -> Framed<T, C> {
+        self.read.read_size = read_size;
+        self
+    }
+
+    /// Set the amount of buffered bytes at which frames are written out.
+    ///
+    /// A high watermark of zero writes out every frame immediately.
+    #[inline]
+    #[must_use]
+    pub fn with_high_watermark(mut self, high_watermark: usize) -> Framed<T, C> {
+        self.write.high_watermark = high_watermark;
+        self
+    }
+
+    /// Read the next frame, see [`FramedRead::next_frame`].
+    ///
+    /// Buffered frames are not written out first; call [`Framed::flush`]
+    /// before waiting for a response.
+    #[inline]
+    pub fn next_frame(&mut self) -> Result<Option<C::Item<'_>>, Error<io::Error, C::Error>> {
+        let io = &mut self.io;
+
+        self.read.next_frame(&mut self.codec, |buf| read(io, buf))
+    }
+
+    /// Encode a frame into the buffer, see [`FramedWrite::write_frame`].
+    #[inline]
+    pub fn write_frame<Item>(
+        &mut self,
+        item: &Item,
+    ) -> Result<(), Error<io::Error, <C as Encoder<Item>>::Error>>
+    where
+        Item: ?Sized,
+        C: Encoder<Item>,
+    {
+        let io = &mut self.io;
+
+        self.write.write_frame(&mut self.codec, item, |buf| {
+            write(io, buf).map_err(Error::Io)
+        })
+    }
+
+    /// Write out all buffered frames and flush the underlying IO.
+    #[inline]
+    pub fn flush(&mut self) -> io::Result<()> {
+        let io = &mut self.io;
+
+        self.write.write_buf(|buf| write(io, buf))?;
+        self.io.flush()
+    }
+}
+
+impl<T, C> Framed<T, C> {
+    /// Returns a reference to the underlying IO.
+    #[inline]
+    #[must_use]
+    pub const fn get_ref(&self) -> &T {
+        &self.io
+    }
+
+    /// Returns a mutable reference to the underlying IO.
+    ///
+    /// Using it directly may corrupt the stream of frames.
+    #[inline]
+    #[must_use]
+    pub fn get_mut(&mut self) -> &mut T {
+        &mut self.io
+    }
+
+    /// Returns a reference to the codec.
+    #[inline]
+    #[must_use]
+    pub const fn codec(&self) -> &C {
+        &self.codec
+    }
+
+    /// Returns a mutable reference to the codec.
+    #[inline]
+    #[must_use]
+    pub fn codec_mut(&mut self) -> &mut C {
+        &mut self.codec
+    }
+
+    /// Returns the bytes that have been read but not yet decoded.
+    #[inline]
+    #[must_use]
+    pub fn read_buffer(&self) -> &[u8] {
+        self.read.buffer()
+    }
+
+    /// Returns the bytes that have been encoded but not yet written.
+    #[inline]
+    #[must_use]
+    pub fn write_buffer(&self) -> &[u8] {
+        self.write.buffer()
+    }
+
+    /// Returns the underlying IO, discarding any buffered bytes.
+    #[inline]
+    #[must_use]
+    pub fn into_inner(self) -> T {
+        self.io
+    }
+}
+
+#[cfg(all(test, feature = "sim"))]
+mod tests {
+    use super::*;
+    use crate::framing::{LengthDelimited, Lines, LinesError, Netstring, NetstringError};
+    use crate::sim::SimIo;
+
+    fn is_io<T, E>(result: Result<T, Error<io::Error, E>>, kind: ErrorKind) -> bool {
+        matches!(result, Err(Error::Io(error)) if error.kind() == kind)
+    }
+
+    #[test]
+    fn partial_reads() {
+        let io = SimIo::new().then_read_bytewise(b"hello\nworld");
+        let mut lines = FramedRead::new(io, Lines::new());
+
+        assert_eq!(lines.next_frame().unwrap(), Some(&b"hello"[..]));
+        assert_eq!(lines.next_frame().unwrap(), Some(&b"world"[..]));
+        assert_eq!(lines.next_frame().unwrap(), None);
+        assert!(lines.get_ref().is_read_done());
+    }
+
+    #[test]
+    fn read_errors() {
+        let io = SimIo::new()
+            .then_read(b"hel")
+            .then_read_pending()
+            .then_read_error(ErrorKind::Interrupted)
+            .then_read(b"lo\nwor")
+            .then_read_error(ErrorKind::ConnectionReset)
+            .then_read(b"ld\n");
+        let mut lines = FramedRead::new(io, Lines::new());
+
+        // Bytes read before an error are kept, and the call can be retried.
+        assert!(is_io(lines.next_frame(), ErrorKind::WouldBlock));
+        assert_eq!(lines.buffer(), b"hel");
+        assert_eq!(lines.next_frame().unwrap(), Some(&b"hello"[..]));
+        assert!(is_io(lines.next_frame(), ErrorKind::ConnectionReset));
+        assert_eq!(lines.next_frame().unwrap(), Some(&b"world"[..]));
+        assert_eq!(lines.next_frame().unwrap(), None);
+    }
+
+    #[test]
+    fn truncated_and_codec_errors() {
+        let io = SimIo::new().then_read(b"1:a,5:ab");
+        let mut netstrings = FramedRead::new(io, Netstring::new());
+
+        assert_eq!(netstrings.next_frame().unwrap(), Some(&b"a"[..]));
+        assert!(matches!(netstrings.next_frame(), Err(Error::Truncated)));
+
+        let io = SimIo::new().then_read_bytewise(b"1:a,x:");
+        let mut netstrings = FramedRead::new(io, Netstring::new());
+
+        assert_eq!(netstrings.next_frame().unwrap(), Some(&b"a"[..]));
+        assert!(matches!(
+            netstrings.next_frame(),
+            Err(Error::Codec {
+                error: NetstringError::Length,
+                position: 4
+            })
+        ));
+    }
+
+    #[test]
+    fn partial_writes() {
+        let io = SimIo::new()
+            .then_write_limit(2)
+            .then_write_error(ErrorKind::Interrupted)
+            .then_write_limit(3);
+        let mut frames = FramedWrite::new(io, LengthDelimited::default());
+
+        frames.write_frame(&b"abc"[..]).unwrap();
+        frames.write_frame(&b"de"[..]).unwrap();
+        assert_eq!(frames.get_ref().written(), b"");
+
+        frames.flush().unwrap();
+        assert_eq!(frames.get_ref().written(), b"\0\0\0\x03abc\0\0\0\x02de");
+        assert_eq!(frames.get_ref().flushes(), 1);
+        assert!(frames.buffer().is_empty());
+    }
+
+    #[test]
+    fn write_errors() {
+        let io = SimIo::new()
+            .then_write_limit(2)
+            .then_write_pending()
+            .then_write_limit(0);
+        let mut lines = FramedWrite::new(io, Lines::new()).with_high_watermark(0);
+
+        // The frame stays buffered once encoded, and is written on flush.
+        assert!(is_io(lines.write_frame(&b"abc"[..]), ErrorKind::WouldBlock));
+        assert_eq!(lines.buffer(), b"c\n");
+        assert_eq!(lines.flush().unwrap_err().kind(), ErrorKind::WriteZero);
+        lines.flush().unwrap();
+        assert_eq!(lines.get_ref().written(), b"abc\n");
+
+        assert!(matches!(
+            lines.write_frame(&b"a\nb"[..]),
+            Err(Error::Codec {
+                error: LinesError::Newline,
+                position: 4
+            })
+        ));
+    }
+
+    #[test]
+    fn framed() {
+        let io = SimIo::new().then_read_bytewise(b"ping\n");
+        let mut framed = Framed::new(io, Lines::new());
+
+        assert_eq!(framed.next_frame().unwrap(), Some(&b"ping"[..]));
+
+        framed.write_frame(&b"pong"[..]).unwrap();
+        framed.flush().unwrap();
+
+        assert_eq!(framed.get_ref().written(), b"pong\n");
+        assert_eq!(framed.next_frame().unwrap(), None);
+    }
+}
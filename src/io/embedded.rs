@@ -0,0 +1,223 @@
+use core::convert::Infallible;
+
+use embedded_io::{Error as _, ErrorKind, Read, Write};
+
+use super::{Error, NextFrame, ReadState, Storage, WriteState};
+use crate::decoder::Decoder;
+use crate::encoder::Encoder;
+
+/// Read from `reader`, retrying when interrupted.
+fn read<R: Read>(reader: &mut R, buf: &mut [u8]) -> Result<usize, R::Error> {
+    loop {
+        match reader.read(buf) {
+            Err(error) if error.kind() == ErrorKind::Interrupted => {}
+            result => return result,
+        }
+    }
+}
+
+/// Write to `writer`, retrying when interrupted.
+fn write<W: Write, E>(writer: &mut W, buf: &[u8]) -> Result<usize, Error<W::Error, E>> {
+    loop {
+        match writer.write(buf) {
+            Ok(0) => return Err(Error::WriteZero),
+            Err(error) if error.kind() == ErrorKind::Interrupted => {}
+            result => return result.map_err(Error::Io),
+        }
+    }
+}
+
+/// A driver that reads frames from an [`embedded_io::Read`] using a
+/// [`Decoder`].
+///
+/// This is the `no_std` equivalent of [`super::FramedRead`]. Bytes are
+/// accumulated in caller-provided [`Storage`], such as an array, which
+/// must be large enough to hold the largest frame.
+#[derive(Debug)]
+pub struct FramedRead<R, D, B> {
+    reader: R,
+    decoder: D,
+    state: ReadState<B>,
+}
+
+impl<R: Read, D: Decoder, B: Storage> FramedRead<R, D, B> {
+    /// Create a new [`FramedRead`] reading from `reader` into `storage`.
+    #[inline]
+    #[must_use]
+    pub const fn new(reader: R, decoder: D, storage: B) -> FramedRead<R, D, B> {
+        FramedRead {
+            reader,
+            decoder,
+            state: ReadState::new(storage),
+        }
+    }
+
+    /// Read the next frame.
+    ///
+    /// Returns `None` once the reader has reached the end of the stream
+    /// and every frame has been read.
+    #[inline]
+    pub fn next_frame(&mut self) -> NextFrame<'_, D, R::Error> {
+        let reader = &mut self.reader;
+
+        self.state
+            .next_frame(&mut self.decoder, |buf| read(reader, buf))
+    }
+
+    /// Returns the bytes that have been read but not yet decoded.
+    #[inline]
+    #[must_use]
+    pub fn buffer(&self) -> &[u8] {
+        self.state.buffer()
+    }
+}
+
+impl<R, D, B> FramedRead<R, D, B> {
+    /// Returns a reference to the underlying reader.
+    #[inline]
+    #[must_use]
+    pub const fn get_ref(&self) -> &R {
+        &self.reader
+    }
+
+    /// Returns a mutable reference to the underlying reader.
+    ///
+    /// Reading from it directly may corrupt the stream of frames.
+    #[inline]
+    #[must_use]
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.reader
+    }
+
+    /// Returns a reference to the decoder.
+    #[inline]
+    #[must_use]
+    pub const fn decoder(&self) -> &D {
+        &self.decoder
+    }
+
+    /// Returns a mutable reference to the decoder.
+    #[inline]
+    #[must_use]
+    pub fn decoder_mut(&mut self) -> &mut D {
+        &mut self.decoder
+    }
+
+    /// Returns the underlying reader, discarding any buffered bytes.
+    #[inline]
+    #[must_use]
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
+
+/// A driver that writes frames to an [`embedded_io::Write`] using an
+/// [`Encoder`].
+///
+/// This is the `no_std` equivalent of [`super::FramedWrite`]. Frames are
+/// encoded into caller-provided [`Storage`], which is written out when it
+/// reaches the high watermark or cannot fit the next frame.
+#[derive(Debug)]
+pub struct FramedWrite<W, E, B> {
+    writer: W,
+    encoder: E,
+    state: WriteState<B>,
+}
+
+impl<W: Write, E, B: Storage> FramedWrite<W, E, B> {
+    /// Create a new [`FramedWrite`] writing to `writer` through `storage`.
+    ///
+    /// The high watermark defaults to the size of `storage`.
+    #[inline]
+    #[must_use]
+    pub fn new(writer: W, encoder: E, storage: B) -> FramedWrite<W, E, B> {
+        let mut state = WriteState::new(storage);
+
+        state.high_watermark = state.buf.as_ref().len();
+
+        FramedWrite {
+            writer,
+            encoder,
+            state,
+        }
+    }
+
+    /// Set the amount of buffered bytes at which frames are written out.
+    ///
+    /// A high watermark of zero writes out every frame immediately.
+    #[inline]
+    #[must_use]
+    pub fn with_high_watermark(mut self, high_watermark: usize) -> FramedWrite<W, E, B> {
+        self.state.high_watermark = high_watermark;
+        self
+    }
+
+    /// Encode a frame into the buffer, writing the buffer out if it is
+    /// full or has reached the high watermark.
+    #[inline]
+    pub fn write_frame<Item>(&mut self, item: &Item) -> Result<(), Error<W::Error, E::Error>>
+    where
+        Item: ?Sized,
+        E: Encoder<Item>,
+    {
+        let writer = &mut self.writer;
+
+        self.state
+            .write_frame(&mut self.encoder, item, |buf| write(writer, buf))
+    }
+
+    /// Write out all buffered frames and flush the writer.
+    #[inline]
+    pub fn flush(&mut self) -> Result<(), Error<W::Error, Infallible>> {
+        let writer = &mut self.writer;
+
+        self.state.write_buf(|buf| write(writer, buf))?;
+        self.writer.flush().map_err(Error::Io)
+    }
+
+    /// Returns the bytes that have been encoded but not yet written.
+    #[inline]
+    #[must_use]
+    pub fn buffer(&self) -> &[u8] {
+        self.state.buffer()
+    }
+}
+
+impl<W, E, B> FramedWrite<W, E, B> {
+    /// Returns a reference to the underlying writer.
+    #[inline]
+    #[must_use]
+    pub const fn get_ref(&self) -> &W {
+        &self.writer
+    }
+
+    /// Returns a mutable reference to the underlying writer.
+    ///
+    /// Writing to it directly may corrupt the stream of frames.
+    #[inline]
+    #[must_use]
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.writer
+    }
+
+    /// Returns a reference to the encoder.
+    #[inline]
+    #[must_use]
+    pub const fn encoder(&self) -> &E {
+        &self.encoder
+    }
+
+    /// Returns a mutable reference to the encoder.
+    #[inline]
+    #[must_use]
+    pub fn encoder_mut(&mut self) -> &mut E {
+        &mut self.encoder
+    }
+
+    /// Returns the underlying writer, discarding any buffered bytes.
+    #[inline]
+    #[must_use]
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
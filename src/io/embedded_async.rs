@@ -0,0 +1,248 @@
+use core::convert::Infallible;
+
+use embedded_io_async::{Error as _, ErrorKind, Read, Write};
+
+use super::{Error, NextFrame, ReadState, Storage, WriteState};
+use crate::decoder::Decoder;
+use crate::encoder::{Encoder, Error as EError};
+
+/// Read from `reader`, retrying when interrupted.
+async fn read<R: Read>(reader: &mut R, buf: &mut [u8]) -> Result<usize, R::Error> {
+    loop {
+        match reader.read(buf).await {
+            Err(error) if error.kind() == ErrorKind::Interrupted => {}
+            result => return result,
+        }
+    }
+}
+
+/// A driver that reads frames from an [`embedded_io_async::Read`] using a
+/// [`Decoder`].
+///
+/// This is the async equivalent of [`super::embedded::FramedRead`], and
+/// likewise accumulates bytes in caller-provided [`Storage`] without
+/// allocating.
+#[derive(Debug)]
+pub struct FramedRead<R, D, B> {
+    reader: R,
+    decoder: D,
+    state: ReadState<B>,
+}
+
+impl<R: Read, D: Decoder, B: Storage> FramedRead<R, D, B> {
+    /// Create a new [`FramedRead`] reading from `reader` into `storage`.
+    #[inline]
+    #[must_use]
+    pub const fn new(reader: R, decoder: D, storage: B) -> FramedRead<R, D, B> {
+        FramedRead {
+            reader,
+            decoder,
+            state: ReadState::new(storage),
+        }
+    }
+
+    /// Read the next frame.
+    ///
+    /// Returns `None` once the reader has reached the end of the stream
+    /// and every frame has been read.
+    pub async fn next_frame(&mut self) -> NextFrame<'_, D, R::Error> {
+        loop {
+            // SAFETY: Frames borrow the buffer for as long as `self` is
+            // borrowed, so it cannot be modified while they are alive.
+            let needed = match unsafe { self.state.decode(&mut self.decoder) } {
+                Ok(result) => return result,
+                Err(needed) => needed,
+            };
+
+            let spare = self.state.spare(needed)?;
+            let n = read(&mut self.reader, spare).await.map_err(Error::Io)?;
+
+            self.state.fill(n);
+        }
+    }
+
+    /// Returns the bytes that have been read but not yet decoded.
+    #[inline]
+    #[must_use]
+    pub fn buffer(&self) -> &[u8] {
+        self.state.buffer()
+    }
+}
+
+impl<R, D, B> FramedRead<R, D, B> {
+    /// Returns a reference to the underlying reader.
+    #[inline]
+    #[must_use]
+    pub const fn get_ref(&self) -> &R {
+        &self.reader
+    }
+
+    /// Returns a mutable reference to the underlying reader.
+    ///
+    /// Reading from it directly may corrupt the stream of frames.
+    #[inline]
+    #[must_use]
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.reader
+    }
+
+    /// Returns a reference to the decoder.
+    #[inline]
+    #[must_use]
+    pub const fn decoder(&self) -> &D {
+        &self.decoder
+    }
+
+    /// Returns a mutable reference to the decoder.
+    #[inline]
+    #[must_use]
+    pub fn decoder_mut(&mut self) -> &mut D {
+        &mut self.decoder
+    }
+
+    /// Returns the underlying reader, discarding any buffered bytes.
+    #[inline]
+    #[must_use]
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
+
+/// A driver that writes frames to an [`embedded_io_async::Write`] using
+/// an [`Encoder`].
+///
+/// This is the async equivalent of [`super::embedded::FramedWrite`], and
+/// likewise encodes frames into caller-provided [`Storage`] without
+/// allocating.
+#[derive(Debug)]
+pub struct FramedWrite<W, E, B> {
+    writer: W,
+    encoder: E,
+    state: WriteState<B>,
+}
+
+impl<W: Write, E, B: Storage> FramedWrite<W, E, B> {
+    /// Create a new [`FramedWrite`] writing to `writer` through `storage`.
+    ///
+    /// The high watermark defaults to the size of `storage`.
+    #[inline]
+    #[must_use]
+    pub fn new(writer: W, encoder: E, storage: B) -> FramedWrite<W, E, B> {
+        let mut state = WriteState::new(storage);
+
+        state.high_watermark = state.buf.as_ref().len();
+
+        FramedWrite {
+            writer,
+            encoder,
+            state,
+        }
+    }
+
+    /// Set the amount of buffered bytes at which frames are written out.
+    ///
+    /// A high watermark of zero writes out every frame immediately.
+    #[inline]
+    #[must_use]
+    pub fn with_high_watermark(mut self, high_watermark: usize) -> FramedWrite<W, E, B> {
+        self.state.high_watermark = high_watermark;
+        self
+    }
+
+    /// Encode a frame into the buffer, writing the buffer out if it is
+    /// full or has reached the high watermark.
+    pub async fn write_frame<Item>(&mut self, item: &Item) -> Result<(), Error<W::Error, E::Error>>
+    where
+        Item: ?Sized,
+        E: Encoder<Item>,
+    {
+        loop {
+            match self.state.encode(&mut self.encoder, item) {
+                Ok(()) => break,
+                Err(EError::Fatal { error }) => {
+                    return Err(Error::Codec {
+                        error,
+                        position: self.state.position,
+                    })
+                }
+                Err(EError::Full { .. }) if self.state.len == 0 => return Err(Error::Full),
+                Err(EError::Full { .. }) => self.write_buf().await?,
+            }
+        }
+
+        if self.state.is_full() {
+            self.write_buf().await?;
+        }
+
+        Ok(())
+    }
+
+    /// Write out all buffered frames and flush the writer.
+    pub async fn flush(&mut self) -> Result<(), Error<W::Error, Infallible>> {
+        self.write_buf().await?;
+        self.writer.flush().await.map_err(Error::Io)
+    }
+
+    /// Write out the whole buffer.
+    async fn write_buf<X>(&mut self) -> Result<(), Error<W::Error, X>> {
+        while self.state.len > 0 {
+            let n = loop {
+                match self.writer.write(self.state.buffer()).await {
+                    Ok(0) => return Err(Error::WriteZero),
+                    Err(error) if error.kind() == ErrorKind::Interrupted => {}
+                    result => break result.map_err(Error::Io)?,
+                }
+            };
+
+            self.state.consume(n);
+        }
+
+        Ok(())
+    }
+
+    /// Returns the bytes that have been encoded but not yet written.
+    #[inline]
+    #[must_use]
+    pub fn buffer(&self) -> &[u8] {
+        self.state.buffer()
+    }
+}
+
+impl<W, E, B> FramedWrite<W, E, B> {
+    /// Returns a reference to the underlying writer.
+    #[inline]
+    #[must_use]
+    pub const fn get_ref(&self) -> &W {
+        &self.writer
+    }
+
+    /// Returns a mutable reference to the underlying writer.
+    ///
+    /// Writing to it directly may corrupt the stream of frames.
+    #[inline]
+    #[must_use]
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.writer
+    }
+
+    /// Returns a reference to the encoder.
+    #[inline]
+    #[must_use]
+    pub const fn encoder(&self) -> &E {
+        &self.encoder
+    }
+
+    /// Returns a mutable reference to the encoder.
+    #[inline]
+    #[must_use]
+    pub fn encoder_mut(&mut self) -> &mut E {
+        &mut self.encoder
+    }
+
+    /// Returns the underlying writer, discarding any buffered bytes.
+    #[inline]
+    #[must_use]
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
@@ -0,0 +1,411 @@
+use core::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use std::{
+    io::{self, ErrorKind},
+    vec::Vec,
+};
+
+use futures_core::Stream;
+use futures_io::{AsyncRead, AsyncWrite};
+use futures_sink::Sink;
+
+use super::{Error, NextFrame, ReadState, WriteState};
+use crate::decoder::Decoder;
+use crate::encoder::{Encoder, Error as EError};
+
+/// Read from `reader`, retrying when interrupted.
+fn poll_read<R: AsyncRead + Unpin>(
+    reader: &mut R,
+    cx: &mut Context<'_>,
+    buf: &mut [u8],
+) -> Poll<io::Result<usize>> {
+    loop {
+        match Pin::new(&mut *reader).poll_read(cx, buf) {
+            Poll::Ready(Err(error)) if error.kind() == ErrorKind::Interrupted => {}
+            poll => return poll,
+        }
+    }
+}
+
+/// Write to `writer`, retrying when interrupted.
+fn poll_write<W: AsyncWrite + Unpin>(
+    writer: &mut W,
+    cx: &mut Context<'_>,
+    buf: &[u8],
+) -> Poll<io::Result<usize>> {
+    loop {
+        match Pin::new(&mut *writer).poll_write(cx, buf) {
+            Poll::Ready(Ok(0)) => return Poll::Ready(Err(ErrorKind::WriteZero.into())),
+            Poll::Ready(Err(error)) if error.kind() == ErrorKind::Interrupted => {}
+            poll => return poll,
+        }
+    }
+}
+
+/// A driver that reads frames from an [`AsyncRead`] using a [`Decoder`].
+///
+/// This is the async equivalent of [`super::FramedRead`]. It implements
+/// [`Stream`] for decoders with owned frames; decoders with borrowed
+/// frames can be used with [`FramedRead::poll_next_frame`], or be made to
+/// yield owned frames with [`Map`](crate::combinator::Map).
+#[derive(Debug)]
+pub struct FramedRead<R, D> {
+    reader: R,
+    decoder: D,
+    state: ReadState<Vec<u8>>,
+}
+
+impl<R: AsyncRead + Unpin, D: Decoder> FramedRead<R, D> {
+    /// Create a new [`FramedRead`] reading from `reader`.
+    #[inline]
+    #[must_use]
+    pub const fn new(reader: R, decoder: D) -> FramedRead<R, D> {
+        FramedRead {
+            reader,
+            decoder,
+            state: ReadState::new(Vec::new()),
+        }
+    }
+
+    /// Set the minimum amount of bytes to request from each read.
+    #[inline]
+    #[must_use]
+    pub fn with_read_size(mut self, read_size: usize) -> FramedRead<R, D> {
+        self.state.read_size = read_size;
+        self
+    }
+
+    /// Try to read the next frame.
+    ///
+    /// Returns `None` once the reader has reached the end of the stream
+    /// and every frame has been read.
+    #[inline]
+    pub fn poll_next_frame(&mut self, cx: &mut Context<'_>) -> Poll<NextFrame<'_, D, io::Error>> {
+        let reader = &mut self.reader;
+
+        self.state
+            .poll_next_frame(&mut self.decoder, |buf| poll_read(reader, cx, buf))
+    }
+}
+
+impl<R, D> FramedRead<R, D> {
+    /// Returns a reference to the underlying reader.
+    #[inline]
+    #[must_use]
+    pub const fn get_ref(&self) -> &R {
+        &self.reader
+    }
+
+    /// Returns a mutable reference to the underlying reader.
+    ///
+    /// Reading from it directly may corrupt the stream of frames.
+    #[inline]
+    #[must_use]
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.reader
+    }
+
+    /// Returns a reference to the decoder.
+    #[inline]
+    #[must_use]
+    pub const fn decoder(&self) -> &D {
+        &self.decoder
+    }
+
+    /// Returns a mutable reference to the decoder.
+    #[inline]
+    #[must_use]
+    pub fn decoder_mut(&mut self) -> &mut D {
+        &mut self.decoder
+    }
+
+    /// Returns the bytes that have been read but not yet decoded.
+    #[inline]
+    #[must_use]
+    pub fn buffer(&self) -> &[u8] {
+        self.state.buffer()
+    }
+
+    /// Returns the underlying reader, discarding any buffered bytes.
+    #[inline]
+    #[must_use]
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
+
+impl<R, D, T> Stream for FramedRead<R, D>
+where
+    R: AsyncRead + Unpin,
+    D: for<'a> Decoder<Item<'a> = T> + Unpin,
+{
+    type Item = Result<T, Error<io::Error, D::Error>>;
+
+    #[inline]
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.get_mut().poll_next_frame(cx).map(Result::transpose)
+    }
+}
+
+/// A driver that writes frames to an [`AsyncWrite`] using an [`Encoder`].
+///
+/// This is the async equivalent of [`super::FramedWrite`], and implements
+/// [`Sink`] for references to anything the encoder can encode.
+#[derive(Debug)]
+pub struct FramedWrite<W, E> {
+    writer: W,
+    encoder: E,
+    state: WriteState<Vec<u8>>,
+}
+
+impl<W: AsyncWrite + Unpin, E> FramedWrite<W, E> {
+    /// Create a new [`FramedWrite`] writing to `writer`.
+    #[inline]
+    #[must_use]
+    pub const fn new(writer: W, encoder: E) -> FramedWrite<W, E> {
+        FramedWrite {
+            writer,
+            encoder,
+            state: WriteState::new(Vec::new()),
+        }
+    }
+
+    /// Set the amount of buffered bytes at which the sink stops accepting
+    /// frames until they are written out.
+    #[inline]
+    #[must_use]
+    pub fn with_high_watermark(mut self, high_watermark: usize) -> FramedWrite<W, E> {
+        self.state.high_watermark = high_watermark;
+        self
+    }
+
+    /// Write out the whole buffer.
+    fn poll_write_buf(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        let writer = &mut self.writer;
+
+        self.state.poll_write_buf(|buf| poll_write(writer, cx, buf))
+    }
+}
+
+impl<W, E> FramedWrite<W, E> {
+    /// Returns a reference to the underlying writer.
+    #[inline]
+    #[must_use]
+    pub const fn get_ref(&self) -> &W {
+        &self.writer
+    }
+
+    /// Returns a mutable reference to the underlying writer.
+    ///
+    /// Writing to it directly may corrupt the stream of frames.
+    #[inline]
+    #[must_use]
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.writer
+    }
+
+    /// Returns a reference to the encoder.
+    #[inline]
+    #[must_use]
+    pub const fn encoder(&self) -> &E {
+        &self.encoder
+    }
+
+    /// Returns a mutable reference to the encoder.
+    #[inline]
+    #[must_use]
+    pub fn encoder_mut(&mut self) -> &mut E {
+        &mut self.encoder
+    }
+
+    /// Returns the bytes that have been encoded but not yet written.
+    #[inline]
+    #[must_use]
+    pub fn buffer(&self) -> &[u8] {
+        self.state.buffer()
+    }
+
+    /// Returns the underlying writer, discarding any buffered bytes.
+    #[inline]
+    #[must_use]
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+impl<Item, W, E> Sink<&Item> for FramedWrite<W, E>
+where
+    Item: ?Sized,
+    W: AsyncWrite + Unpin,
+    E: Encoder<Item> + Unpin,
+{
+    type Error = Error<io::Error, E::Error>;
+
+    fn poll_ready(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        let this = self.get_mut();
+
+        if this.state.is_full() {
+            return this.poll_write_buf(cx).map_err(Error::Io);
+        }
+
+        Poll::Ready(Ok(()))
+    }
+
+    #[inline]
+    fn start_send(self: Pin<&mut Self>, item: &Item) -> Result<(), Self::Error> {
+        let this = self.get_mut();
+
+        this.state
+            .encode(&mut this.encoder, item)
+            .map_err(|error| match error {
+                EError::Full { .. } => Error::Full,
+                EError::Fatal { error } => Error::Codec {
+                    error,
+                    position: this.state.position,
+                },
+            })
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        let this = self.get_mut();
+
+        match this.poll_write_buf(cx) {
+            Poll::Ready(Ok(())) => Pin::new(&mut this.writer).poll_flush(cx).map_err(Error::Io),
+            poll => poll.map_err(Error::Io),
+        }
+    }
+
+    fn poll_close(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        match self.as_mut().poll_flush(cx) {
+            Poll::Ready(Ok(())) => Pin::new(&mut self.writer).poll_close(cx).map_err(Error::Io),
+            poll => poll,
+        }
+    }
+}
+
+#[cfg(all(test, feature = "sim"))]
+mod tests {
+    use core::future::poll_fn;
+
+    use super::*;
+    use crate::combinator::Map;
+    use crate::framing::{Lines, LinesError, Netstring};
+    use crate::sim::{block_on, poll_once, SimIo};
+
+    fn is_io<T, E>(result: Result<T, Error<io::Error, E>>, kind: ErrorKind) -> bool {
+        matches!(result, Err(Error::Io(error)) if error.kind() == kind)
+    }
+
+    type NextLine = Result<Option<Vec<u8>>, Error<io::Error, LinesError>>;
+
+    /// Read the next line as an owned frame, along with how many times the
+    /// read was pending.
+    fn next_line<R: AsyncRead + Unpin>(lines: &mut FramedRead<R, Lines>) -> (NextLine, usize) {
+        block_on(poll_fn(|cx| {
+            lines
+                .poll_next_frame(cx)
+                .map_ok(|line| line.map(<[u8]>::to_vec))
+        }))
+    }
+
+    #[test]
+    fn partial_and_pending_reads() {
+        let io = SimIo::new()
+            .then_read_bytewise(b"hel")
+            .then_read_pending()
+            .then_read_error(ErrorKind::Interrupted)
+            .then_read(b"lo\nworld");
+        let mut lines = FramedRead::new(io, Lines::new());
+
+        let (line, pending) = next_line(&mut lines);
+
+        assert_eq!(line.unwrap(), Some(b"hello".to_vec()));
+        assert_eq!(pending, 1);
+        assert_eq!(next_line(&mut lines).0.unwrap(), Some(b"world".to_vec()));
+        assert_eq!(next_line(&mut lines).0.unwrap(), None);
+    }
+
+    #[test]
+    fn read_errors() {
+        let io = SimIo::new()
+            .then_read(b"1:a,")
+            .then_read_pending()
+            .then_read_error(ErrorKind::ConnectionReset)
+            .then_read(b"5:ab");
+        let mut netstrings = FramedRead::new(io, Map::new(Netstring::new(), <[u8]>::to_vec));
+
+        let mut next = || block_on(poll_fn(|cx| Pin::new(&mut netstrings).poll_next(cx))).0;
+
+        assert_eq!(next().unwrap().unwrap(), b"a");
+        assert!(is_io(next().unwrap(), ErrorKind::ConnectionReset));
+        assert!(matches!(next(), Some(Err(Error::Truncated))));
+    }
+
+    #[test]
+    fn cancelled_reads() {
+        let io = SimIo::new()
+            .then_read(b"hel")
+            .then_read_pending()
+            .then_read(b"lo\n");
+        let mut lines = FramedRead::new(io, Lines::new());
+
+        // Dropping a pending read keeps the bytes read so far.
+        let mut read = poll_fn(|cx| lines.poll_next_frame(cx).map_ok(|line| line.is_some()));
+
+        assert!(poll_once(&mut read).is_pending());
+        assert_eq!(lines.buffer(), b"hel");
+        assert_eq!(next_line(&mut lines).0.unwrap(), Some(b"hello".to_vec()));
+    }
+
+    #[test]
+    fn sink() {
+        let io = SimIo::new()
+            .then_write_limit(2)
+            .then_write_pending()
+            .then_write_error(ErrorKind::Interrupted)
+            .then_write_limit(3);
+        let mut lines = FramedWrite::new(io, Lines::new());
+
+        for line in [&b"abc"[..], b"de"] {
+            block_on(poll_fn(|cx| Pin::new(&mut lines).poll_ready(cx)))
+                .0
+                .unwrap();
+            Pin::new(&mut lines).start_send(line).unwrap();
+        }
+
+        let (result, pending) = block_on(poll_fn(|cx| Pin::new(&mut lines).poll_close(cx)));
+
+        result.unwrap();
+        assert_eq!(pending, 1);
+        assert_eq!(lines.get_ref().written(), b"abc\nde\n");
+        assert!(lines.get_ref().is_closed());
+    }
+
+    #[test]
+    fn write_errors() {
+        let io = SimIo::new()
+            .then_write_error(ErrorKind::BrokenPipe)
+            .then_write_limit(0);
+        let mut lines = FramedWrite::new(io, Lines::new());
+
+        Pin::new(&mut lines).start_send(&b"abc"[..]).unwrap();
+
+        let mut flush = || block_on(poll_fn(|cx| Pin::new(&mut lines).poll_flush(cx))).0;
+
+        assert!(is_io(flush(), ErrorKind::BrokenPipe));
+        assert!(is_io(flush(), ErrorKind::WriteZero));
+        flush().unwrap();
+        assert_eq!(lines.get_ref().written(), b"abc\n");
+
+        assert!(matches!(
+            Pin::new(&mut lines).start_send(&b"a\nb"[..]),
+            Err(Error::Codec {
+                error: LinesError::Newline,
+                position: 4
+            })
+        ));
+    }
+}
@@ -14,14 +14,98 @@ pub mod endian;
 /// Types and traits relating to decoders.
 pub mod decoder;
 
+/// A typed cursor for reading frames.
+pub mod reader;
+
+/// A cursor and decoders for fields that are not whole bytes.
+pub mod bits;
+
+/// Hexdumps for diagnosing malformed input.
+pub mod hexdump;
+
+/// Types and traits relating to encoders.
+pub mod encoder;
+
+/// Types and traits relating to codecs.
+pub mod codec;
+
 /// Encoders and decoders for binary data.
 pub mod binary;
 
+/// Encoders and decoders for fixed-size layouts.
+pub mod frame;
+
+/// Decoders for C-like enums from their discriminants.
+pub mod enumeration;
+
+/// Encoders and decoders for variable-length integers.
+pub mod varint;
+
+/// Encoders and decoders for text.
+pub mod text;
+
+/// Encoders and decoders for binary data as text, such as base64.
+pub mod encoding;
+
+/// Encoders and decoders for framing byte streams.
+pub mod framing;
+
+/// Decoders that wrap other decoders.
+pub mod combinator;
+
+/// Checksums for verifying frames.
+pub mod checksum;
+
+/// Decoders for the DNS wire format.
+pub mod dns;
+
+/// A decoder for Ethereum's recursive length prefix encoding.
+pub mod rlp;
+
+/// Decoders for the headers of MessagePack values.
+pub mod msgpack;
+
+/// Decoders for the headers of CBOR data items.
+pub mod cbor;
+
+/// Encoders and decoders for IP addresses and socket addresses.
+pub mod net;
+
+/// Encoders and decoders for timestamps.
+pub mod time;
+
+/// Decoders for RIFF chunks.
+pub mod riff;
+
+/// Decoders for frames within compressed streams.
+#[cfg(feature = "alloc")]
+pub mod compress;
+
+/// Drivers for framing byte streams over IO.
+pub mod io;
+
+/// A compact binary format for types implementing serde's traits.
+#[cfg(feature = "serde")]
+pub mod serde;
+
+/// Scripted IO for deterministically testing codecs and framing drivers.
+#[cfg(feature = "sim")]
+pub mod sim;
+
 #[doc(inline)]
 pub use decoder::Decoder;
 
 #[doc(inline)]
-pub use size::Size;
+pub use encoder::Encoder;
+
+#[doc(inline)]
+pub use codec::Codec;
+
+#[doc(inline)]
+pub use reader::Reader;
+
+#[doc(inline)]
+pub use size::{Size, SizeHint};
 
 #[doc(inline)]
 pub use endian::Endian;
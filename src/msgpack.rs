@@ -0,0 +1,431 @@
+use core::fmt;
+
+use crate::decoder::{Decoder, Error as DError, Result as DResult};
+use crate::{Size, SizeHint};
+
+/// The format marker at the start of every MessagePack value.
+///
+/// The fixed formats carry their value or length in the marker itself.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Marker {
+    /// An integer from 0 to 127.
+    PositiveFixint(u8),
+    /// A map of up to 15 pairs.
+    FixMap(u8),
+    /// An array of up to 15 elements.
+    FixArray(u8),
+    /// A string of up to 31 bytes.
+    FixStr(u8),
+    /// `nil`.
+    Nil,
+    /// The reserved marker `0xc1`.
+    NeverUsed,
+    /// `false`.
+    False,
+    /// `true`.
+    True,
+    /// Binary data with an 8 bit length.
+    Bin8,
+    /// Binary data with a 16 bit length.
+    Bin16,
+    /// Binary data with a 32 bit length.
+    Bin32,
+    /// An extension with an 8 bit length.
+    Ext8,
+    /// An extension with a 16 bit length.
+    Ext16,
+    /// An extension with a 32 bit length.
+    Ext32,
+    /// A single precision float.
+    Float32,
+    /// A double precision float.
+    Float64,
+    /// An 8 bit unsigned integer.
+    Uint8,
+    /// A 16 bit unsigned integer.
+    Uint16,
+    /// A 32 bit unsigned integer.
+    Uint32,
+    /// A 64 bit unsigned integer.
+    Uint64,
+    /// An 8 bit signed integer.
+    Int8,
+    /// A 16 bit signed integer.
+    Int16,
+    /// A 32 bit signed integer.
+    Int32,
+    /// A 64 bit signed integer.
+    Int64,
+    /// An extension of 1 byte.
+    FixExt1,
+    /// An extension of 2 bytes.
+    FixExt2,
+    /// An extension of 4 bytes.
+    FixExt4,
+    /// An extension of 8 bytes.
+    FixExt8,
+    /// An extension of 16 bytes.
+    FixExt16,
+    /// A string with an 8 bit length.
+    Str8,
+    /// A string with a 16 bit length.
+    Str16,
+    /// A string with a 32 bit length.
+    Str32,
+    /// An array with a 16 bit length.
+    Array16,
+    /// An array with a 32 bit length.
+    Array32,
+    /// A map with a 16 bit length.
+    Map16,
+    /// A map with a 32 bit length.
+    Map32,
+    /// An integer from -32 to -1.
+    NegativeFixint(i8),
+}
+
+impl Marker {
+    /// Returns the marker for `byte`.
+    #[must_use]
+    pub const fn from_u8(byte: u8) -> Marker {
+        match byte {
+            0x00..=0x7f => Self::PositiveFixint(byte),
+            0x80..=0x8f => Self::FixMap(byte & 0x0f),
+            0x90..=0x9f => Self::FixArray(byte & 0x0f),
+            0xa0..=0xbf => Self::FixStr(byte & 0x1f),
+            0xc0 => Self::Nil,
+            0xc1 => Self::NeverUsed,
+            0xc2 => Self::False,
+            0xc3 => Self::True,
+            0xc4 => Self::Bin8,
+            0xc5 => Self::Bin16,
+            0xc6 => Self::Bin32,
+            0xc7 => Self::Ext8,
+            0xc8 => Self::Ext16,
+            0xc9 => Self::Ext32,
+            0xca => Self::Float32,
+            0xcb => Self::Float64,
+            0xcc => Self::Uint8,
+            0xcd => Self::Uint16,
+            0xce => Self::Uint32,
+            0xcf => Self::Uint64,
+            0xd0 => Self::Int8,
+            0xd1 => Self::Int16,
+            0xd2 => Self::Int32,
+            0xd3 => Self::Int64,
+            0xd4 => Self::FixExt1,
+            0xd5 => Self::FixExt2,
+            0xd6 => Self::FixExt4,
+            0xd7 => Self::FixExt8,
+            0xd8 => Self::FixExt16,
+            0xd9 => Self::Str8,
+            0xda => Self::Str16,
+            0xdb => Self::Str32,
+            0xdc => Self::Array16,
+            0xdd => Self::Array32,
+            0xde => Self::Map16,
+            0xdf => Self::Map32,
+            0xe0..=0xff => Self::NegativeFixint(byte as i8),
+        }
+    }
+
+    /// Returns the byte for this marker.
+    ///
+    /// Values carried by the fixed formats are truncated to fit.
+    #[must_use]
+    pub const fn to_u8(self) -> u8 {
+        match self {
+            Self::PositiveFixint(value) => value & 0x7f,
+            Self::FixMap(len) => 0x80 | (len & 0x0f),
+            Self::FixArray(len) => 0x90 | (len & 0x0f),
+            Self::FixStr(len) => 0xa0 | (len & 0x1f),
+            Self::Nil => 0xc0,
+            Self::NeverUsed => 0xc1,
+            Self::False => 0xc2,
+            Self::True => 0xc3,
+            Self::Bin8 => 0xc4,
+            Self::Bin16 => 0xc5,
+            Self::Bin32 => 0xc6,
+            Self::Ext8 => 0xc7,
+            Self::Ext16 => 0xc8,
+            Self::Ext32 => 0xc9,
+            Self::Float32 => 0xca,
+            Self::Float64 => 0xcb,
+            Self::Uint8 => 0xcc,
+            Self::Uint16 => 0xcd,
+            Self::Uint32 => 0xce,
+            Self::Uint64 => 0xcf,
+            Self::Int8 => 0xd0,
+            Self::Int16 => 0xd1,
+            Self::Int32 => 0xd2,
+            Self::Int64 => 0xd3,
+            Self::FixExt1 => 0xd4,
+            Self::FixExt2 => 0xd5,
+            Self::FixExt4 => 0xd6,
+            Self::FixExt8 => 0xd7,
+            Self::FixExt16 => 0xd8,
+            Self::Str8 => 0xd9,
+            Self::Str16 => 0xda,
+            Self::Str32 => 0xdb,
+            Self::Array16 => 0xdc,
+            Self::Array32 => 0xdd,
+            Self::Map16 => 0xde,
+            Self::Map32 => 0xdf,
+            Self::NegativeFixint(value) => 0xe0 | (value as u8 & 0x1f),
+        }
+    }
+
+    /// Returns the length of a [`Header`] starting with this marker,
+    /// including the marker itself.
+    #[must_use]
+    pub const fn header_len(self) -> usize {
+        1 + match self {
+            Self::Bin8 | Self::Uint8 | Self::Int8 | Self::Str8 => 1,
+            Self::Bin16 | Self::Uint16 | Self::Int16 | Self::Str16 => 2,
+            Self::Array16 | Self::Map16 => 2,
+            Self::Bin32 | Self::Uint32 | Self::Int32 | Self::Str32 => 4,
+            Self::Array32 | Self::Map32 | Self::Float32 => 4,
+            Self::Uint64 | Self::Int64 | Self::Float64 => 8,
+            Self::FixExt1 | Self::FixExt2 | Self::FixExt4 => 1,
+            Self::FixExt8 | Self::FixExt16 => 1,
+            Self::Ext8 => 2,
+            Self::Ext16 => 3,
+            Self::Ext32 => 5,
+            _ => 0,
+        }
+    }
+}
+
+impl From<u8> for Marker {
+    #[inline]
+    fn from(byte: u8) -> Marker {
+        Marker::from_u8(byte)
+    }
+}
+
+impl From<Marker> for u8 {
+    #[inline]
+    fn from(marker: Marker) -> u8 {
+        marker.to_u8()
+    }
+}
+
+/// A [`Decoder`] for MessagePack format markers.
+///
+/// Only the marker is read, the rest of the value is left in the source.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Markers;
+
+impl Markers {
+    /// Create a new decoder for format markers.
+    #[inline]
+    #[must_use]
+    pub const fn new() -> Markers {
+        Markers
+    }
+}
+
+impl Decoder for Markers {
+    type Item<'src> = Marker;
+    type Error = ::core::convert::Infallible;
+
+    #[inline]
+    fn hint(&self) -> Size {
+        Size::new(1)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> SizeHint {
+        SizeHint::exact(self.hint())
+    }
+
+    #[inline]
+    fn decode<'s>(&mut self, src: &mut &'s [u8]) -> DResult<'s, Self> {
+        let Some((&byte, rest)) = src.split_first() else {
+            return Err(DError::Incomplete {
+                needed: Size::new(1),
+            });
+        };
+
+        *src = rest;
+
+        Ok(Marker::from_u8(byte))
+    }
+}
+
+/// The header of a MessagePack value, which is its format marker and the
+/// bytes immediately following it.
+///
+/// Scalars are complete, while strings, binary data and extensions are
+/// followed by their payload, and arrays and maps by their elements.
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Header {
+    /// `nil`.
+    Nil,
+    /// A boolean.
+    Bool(bool),
+    /// An integer in one of the unsigned formats, or a positive fixint.
+    Uint(u64),
+    /// An integer in one of the signed formats, or a negative fixint.
+    Int(i64),
+    /// A single precision float.
+    F32(f32),
+    /// A double precision float.
+    F64(f64),
+    /// A string, followed by this many bytes of UTF-8.
+    Str(u32),
+    /// Binary data, followed by this many bytes.
+    Bin(u32),
+    /// An array, followed by this many elements.
+    Array(u32),
+    /// A map, followed by this many pairs of keys and values.
+    Map(u32),
+    /// An extension, followed by `len` bytes.
+    Ext {
+        /// The application defined type of the extension.
+        ty: i8,
+        /// The length of the extension data.
+        len: u32,
+    },
+}
+
+impl Header {
+    /// Returns the amount of bytes following the header that belong to
+    /// this value directly, which excludes the elements of arrays and
+    /// maps.
+    #[inline]
+    #[must_use]
+    pub const fn payload_len(&self) -> usize {
+        match *self {
+            Self::Str(len) | Self::Bin(len) | Self::Ext { len, .. } => len as usize,
+            _ => 0,
+        }
+    }
+}
+
+/// A [`Decoder`] for the headers of MessagePack values.
+///
+/// Headers are at most 9 bytes, and are only yielded once complete. The
+/// payloads of strings, binary data and extensions are left in the
+/// source, so they can be read incrementally or skipped.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Headers;
+
+impl Headers {
+    /// Create a new decoder for headers.
+    #[inline]
+    #[must_use]
+    pub const fn new() -> Headers {
+        Headers
+    }
+}
+
+impl Decoder for Headers {
+    type Item<'src> = Header;
+    type Error = HeaderError;
+
+    #[inline]
+    fn hint(&self) -> Size {
+        Size::new(1)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> SizeHint {
+        SizeHint::new(self.hint(), Size::new(9))
+    }
+
+    fn decode<'s>(&mut self, src: &mut &'s [u8]) -> DResult<'s, Self> {
+        let Some(&byte) = src.first() else {
+            return Err(DError::Incomplete {
+                needed: Size::new(1),
+            });
+        };
+
+        let marker = Marker::from_u8(byte);
+        let len = marker.header_len();
+
+        let Some((bytes, rest)) = src.split_at_checked(len) else {
+            return Err(DError::Incomplete {
+                needed: Size::new(len - src.len()),
+            });
+        };
+
+        let bytes = &bytes[1..];
+        let be = |n: usize| bytes[..n].iter().fold(0u64, |n, &b| n << 8 | u64::from(b));
+        let len = |n: usize| be(n) as u32;
+        let ext = |len| Header::Ext {
+            ty: bytes[bytes.len() - 1] as i8,
+            len,
+        };
+
+        let header = match marker {
+            Marker::PositiveFixint(value) => Header::Uint(value.into()),
+            Marker::NegativeFixint(value) => Header::Int(value.into()),
+            Marker::FixMap(len) => Header::Map(len.into()),
+            Marker::FixArray(len) => Header::Array(len.into()),
+            Marker::FixStr(len) => Header::Str(len.into()),
+            Marker::Nil => Header::Nil,
+            Marker::NeverUsed => {
+                return Err(DError::Fatal {
+                    error: HeaderError::NeverUsed,
+                })
+            }
+            Marker::False => Header::Bool(false),
+            Marker::True => Header::Bool(true),
+            Marker::Bin8 => Header::Bin(len(1)),
+            Marker::Bin16 => Header::Bin(len(2)),
+            Marker::Bin32 => Header::Bin(len(4)),
+            Marker::Ext8 => ext(len(1)),
+            Marker::Ext16 => ext(len(2)),
+            Marker::Ext32 => ext(len(4)),
+            Marker::Float32 => Header::F32(f32::from_bits(len(4))),
+            Marker::Float64 => Header::F64(f64::from_bits(be(8))),
+            Marker::Uint8 => Header::Uint(be(1)),
+            Marker::Uint16 => Header::Uint(be(2)),
+            Marker::Uint32 => Header::Uint(be(4)),
+            Marker::Uint64 => Header::Uint(be(8)),
+            Marker::Int8 => Header::Int((be(1) as u8 as i8).into()),
+            Marker::Int16 => Header::Int((be(2) as u16 as i16).into()),
+            Marker::Int32 => Header::Int((be(4) as u32 as i32).into()),
+            Marker::Int64 => Header::Int(be(8) as i64),
+            Marker::FixExt1 => ext(1),
+            Marker::FixExt2 => ext(2),
+            Marker::FixExt4 => ext(4),
+            Marker::FixExt8 => ext(8),
+            Marker::FixExt16 => ext(16),
+            Marker::Str8 => Header::Str(len(1)),
+            Marker::Str16 => Header::Str(len(2)),
+            Marker::Str32 => Header::Str(len(4)),
+            Marker::Array16 => Header::Array(len(2)),
+            Marker::Array32 => Header::Array(len(4)),
+            Marker::Map16 => Header::Map(len(2)),
+            Marker::Map32 => Header::Map(len(4)),
+        };
+
+        *src = rest;
+
+        Ok(header)
+    }
+}
+
+/// Type for errors that may occur while decoding MessagePack headers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum HeaderError {
+    /// The reserved marker `0xc1` was found.
+    NeverUsed,
+}
+
+impl fmt::Display for HeaderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NeverUsed => f.write_str("reserved format marker 0xc1"),
+        }
+    }
+}
+
+impl core::error::Error for HeaderError {}
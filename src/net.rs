@@ -0,0 +1,219 @@
+use core::net::{Ipv4Addr, Ipv6Addr, SocketAddrV4, SocketAddrV6};
+
+use crate::decoder::{Decoder, Error as DError, Result as DResult};
+use crate::encoder::{Encoder, Error as EError, Result as EResult};
+use crate::endian::{BigEndian, ByteOrder, Endian};
+use crate::{Size, SizeHint};
+
+/// Split off the first `N` bytes of `src`.
+#[inline]
+fn take<'s, const N: usize, E>(src: &mut &'s [u8]) -> Result<&'s [u8; N], DError<E>> {
+    let Some((bytes, rest)) = src.split_first_chunk::<N>() else {
+        return Err(DError::Incomplete {
+            needed: Size::new(N - src.len()),
+        });
+    };
+
+    *src = rest;
+
+    Ok(bytes)
+}
+
+/// Split off the first `N` bytes of `dst`.
+#[inline]
+fn reserve<'d, const N: usize, E>(dst: &mut &'d mut [u8]) -> Result<&'d mut [u8; N], EError<E>> {
+    if dst.len() < N {
+        return Err(EError::Full {
+            needed: Size::new(N - dst.len()),
+        });
+    }
+
+    let (bytes, rest) = core::mem::take(dst).split_at_mut(N);
+
+    *dst = rest;
+
+    Ok(bytes.try_into().unwrap())
+}
+
+macro_rules! define {
+    ($(
+        $(#[$attr:meta])*
+        $vis:vis struct $name:ident<$addr:ident; $size:literal> {}
+    )*) => {
+        $(
+            $(#[$attr])*
+            #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+            #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+            $vis struct $name;
+
+            impl $name {
+                #[doc = ::core::concat!(
+                    "Create a new decoder for an [`",
+                    ::core::stringify!($addr),
+                    "`].",
+                )]
+                #[inline]
+                #[must_use]
+                pub const fn new() -> $name {
+                    $name
+                }
+            }
+
+            impl Decoder for $name {
+                type Item<'src> = $addr;
+                type Error = ::core::convert::Infallible;
+
+                #[inline]
+                fn hint(&self) -> Size {
+                    Size::new($size)
+                }
+
+                #[inline]
+                fn size_hint(&self) -> SizeHint {
+                    SizeHint::exact(self.hint())
+                }
+
+                #[inline]
+                fn decode<'s>(&mut self, src: &mut &'s [u8]) -> DResult<'s, Self> {
+                    take::<$size, _>(src).map(|&bytes| $addr::from(bytes))
+                }
+            }
+
+            impl Encoder<$addr> for $name {
+                type Error = ::core::convert::Infallible;
+
+                #[inline]
+                fn encode_hint(&self, _: &$addr) -> Size {
+                    Size::new($size)
+                }
+
+                #[inline]
+                fn encode(&mut self, item: &$addr, dst: &mut &mut [u8]) -> EResult<Self::Error> {
+                    *reserve::<$size, _>(dst)? = item.octets();
+
+                    Ok(())
+                }
+            }
+        )*
+    };
+}
+
+define! {
+    /// A [`Decoder`] and [`Encoder`] for an [`Ipv4Addr`], as 4 bytes in
+    /// network order.
+    pub struct Ipv4<Ipv4Addr; 4> {}
+
+    /// A [`Decoder`] and [`Encoder`] for an [`Ipv6Addr`], as 16 bytes in
+    /// network order.
+    pub struct Ipv6<Ipv6Addr; 16> {}
+}
+
+macro_rules! socket {
+    ($(
+        $(#[$attr:meta])*
+        $vis:vis struct $name:ident<$socket:ident($addr:ident; $size:literal)> {}
+    )*) => {
+        $(
+            $(#[$attr])*
+            #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+            #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+            $vis struct $name<B: ByteOrder = BigEndian> {
+                /// The byte order of the port.
+                pub byte_order: B,
+            }
+
+            impl<B: ByteOrder> $name<B> {
+                #[doc = ::core::concat!(
+                    "Create a new decoder for a [`",
+                    ::core::stringify!($socket),
+                    "`].",
+                )]
+                #[inline]
+                #[must_use]
+                pub const fn new(byte_order: B) -> $name<B> {
+                    $name { byte_order }
+                }
+            }
+
+            impl<B: ByteOrder + Default> Default for $name<B> {
+                #[inline]
+                fn default() -> Self {
+                    Self::new(B::default())
+                }
+            }
+
+            impl<B: ByteOrder> Decoder for $name<B> {
+                type Item<'src> = $socket;
+                type Error = ::core::convert::Infallible;
+
+                #[inline]
+                fn hint(&self) -> Size {
+                    Size::new($size + 2)
+                }
+
+                #[inline]
+                fn size_hint(&self) -> SizeHint {
+                    SizeHint::exact(self.hint())
+                }
+
+                #[inline]
+                fn decode<'s>(&mut self, src: &mut &'s [u8]) -> DResult<'s, Self> {
+                    let bytes = take::<{ $size + 2 }, _>(src)?;
+                    let (addr, port) = bytes.split_at($size);
+                    let addr: [u8; $size] = addr.try_into().unwrap();
+                    let port = [port[0], port[1]];
+
+                    let port = match self.byte_order.endian() {
+                        Endian::Little => u16::from_le_bytes(port),
+                        Endian::Big => u16::from_be_bytes(port),
+                    };
+
+                    Ok(socket!(@new $socket($addr::from(addr), port)))
+                }
+            }
+
+            impl<B: ByteOrder> Encoder<$socket> for $name<B> {
+                type Error = ::core::convert::Infallible;
+
+                #[inline]
+                fn encode_hint(&self, _: &$socket) -> Size {
+                    Size::new($size + 2)
+                }
+
+                #[inline]
+                fn encode(&mut self, item: &$socket, dst: &mut &mut [u8]) -> EResult<Self::Error> {
+                    let bytes = reserve::<{ $size + 2 }, _>(dst)?;
+                    let (addr, port) = bytes.split_at_mut($size);
+
+                    addr.copy_from_slice(&item.ip().octets());
+
+                    port.copy_from_slice(&match self.byte_order.endian() {
+                        Endian::Little => item.port().to_le_bytes(),
+                        Endian::Big => item.port().to_be_bytes(),
+                    });
+
+                    Ok(())
+                }
+            }
+        )*
+    };
+    (@new SocketAddrV4($addr:expr, $port:expr)) => {
+        SocketAddrV4::new($addr, $port)
+    };
+    (@new SocketAddrV6($addr:expr, $port:expr)) => {
+        SocketAddrV6::new($addr, $port, 0, 0)
+    };
+}
+
+socket! {
+    /// A [`Decoder`] and [`Encoder`] for a [`SocketAddrV4`], as the address
+    /// in network order followed by the port.
+    pub struct SocketV4<SocketAddrV4(Ipv4Addr; 4)> {}
+
+    /// A [`Decoder`] and [`Encoder`] for a [`SocketAddrV6`], as the address
+    /// in network order followed by the port.
+    ///
+    /// The flow information and scope ID are not encoded, and are decoded
+    /// as zero.
+    pub struct SocketV6<SocketAddrV6(Ipv6Addr; 16)> {}
+}
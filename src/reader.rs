@@ -0,0 +1,253 @@
+use core::fmt;
+
+use crate::decoder::{Decoder, Error as DError, Incomplete, InfallibleDecoderExt};
+use crate::hexdump::Diagnostic;
+use crate::Size;
+
+/// A typed cursor over a byte slice, for writing [`Decoder::decode`]
+/// bodies.
+///
+/// Reading never touches the source; call [`Reader::commit`] once the
+/// whole frame has been read, so that incomplete frames leave the source
+/// untouched.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Reader<'s> {
+    start: &'s [u8],
+    rest: &'s [u8],
+}
+
+impl<'s> Reader<'s> {
+    /// Create a new [`Reader`] positioned at the start of `src`.
+    #[inline]
+    #[must_use]
+    pub const fn new(src: &'s [u8]) -> Reader<'s> {
+        Reader {
+            start: src,
+            rest: src,
+        }
+    }
+
+    /// Advance `src` past everything read so far.
+    #[inline]
+    pub fn commit(self, src: &mut &'s [u8]) {
+        *src = self.rest;
+    }
+
+    /// Returns the amount of bytes read so far.
+    #[inline]
+    #[must_use]
+    pub const fn position(&self) -> usize {
+        self.start.len() - self.rest.len()
+    }
+
+    /// Returns the amount of bytes left to read.
+    #[inline]
+    #[must_use]
+    pub const fn remaining(&self) -> usize {
+        self.rest.len()
+    }
+
+    /// Returns whether there are no bytes left to read.
+    #[inline]
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.rest.is_empty()
+    }
+
+    /// Returns the bytes read so far.
+    #[inline]
+    #[must_use]
+    pub fn consumed(&self) -> &'s [u8] {
+        &self.start[..self.position()]
+    }
+
+    /// Returns the bytes left to read.
+    #[inline]
+    #[must_use]
+    pub const fn rest(&self) -> &'s [u8] {
+        self.rest
+    }
+
+    #[inline]
+    fn incomplete(&self, len: usize) -> Incomplete {
+        Incomplete::new(Size::new(len - self.rest.len()))
+    }
+
+    /// Read the next `len` bytes.
+    #[inline]
+    pub fn bytes(&mut self, len: usize) -> Result<&'s [u8], Incomplete> {
+        let Some((bytes, rest)) = self.rest.split_at_checked(len) else {
+            return Err(self.incomplete(len));
+        };
+
+        self.rest = rest;
+
+        Ok(bytes)
+    }
+
+    /// Read the next `N` bytes as an array.
+    #[inline]
+    pub fn array<const N: usize>(&mut self) -> Result<[u8; N], Incomplete> {
+        let Some((bytes, rest)) = self.rest.split_first_chunk() else {
+            return Err(self.incomplete(N));
+        };
+
+        self.rest = rest;
+
+        Ok(*bytes)
+    }
+
+    /// Skip the next `len` bytes.
+    #[inline]
+    pub fn skip(&mut self, len: usize) -> Result<(), Incomplete> {
+        self.bytes(len).map(|_| ())
+    }
+
+    /// Returns the next byte without reading it.
+    #[inline]
+    pub fn peek_u8(&self) -> Result<u8, Incomplete> {
+        match self.rest.first() {
+            Some(&byte) => Ok(byte),
+            None => Err(self.incomplete(1)),
+        }
+    }
+
+    /// Read a [`prim@u8`].
+    #[inline]
+    pub fn u8(&mut self) -> Result<u8, Incomplete> {
+        self.array().map(u8::from_ne_bytes)
+    }
+
+    /// Read an [`prim@i8`].
+    #[inline]
+    pub fn i8(&mut self) -> Result<i8, Incomplete> {
+        self.array().map(i8::from_ne_bytes)
+    }
+
+    /// Read a frame using `decoder`.
+    ///
+    /// Fatal errors are annotated with the position the frame started at.
+    #[inline]
+    pub fn read<D: Decoder>(
+        &mut self,
+        decoder: &mut D,
+    ) -> Result<D::Item<'s>, DError<At<D::Error>>> {
+        let offset = self.position();
+
+        decoder
+            .decode(&mut self.rest)
+            .map_err(|error| error.map(|error| At::new(offset, error)))
+    }
+
+    /// Read a frame using `decoder`, which cannot fail fatally.
+    #[inline]
+    pub fn read_infallible<D>(&mut self, decoder: &mut D) -> Result<D::Item<'s>, Incomplete>
+    where
+        D: Decoder<Error = core::convert::Infallible>,
+    {
+        decoder.decode_infallible(&mut self.rest)
+    }
+
+    /// Annotate `error` with the current position, as a fatal error.
+    #[inline]
+    #[must_use]
+    pub const fn fail<E>(&self, error: E) -> DError<At<E>> {
+        DError::Fatal {
+            error: At::new(self.position(), error),
+        }
+    }
+}
+
+macro_rules! define {
+    ($(
+        $ty:ident {
+            $le:ident,
+            $be:ident,
+            $ne:ident $(,)?
+        }
+    )*) => {
+        impl<'s> Reader<'s> {
+            $(
+                #[doc = ::core::concat!(
+                    "Read a little endian [`prim@",
+                    ::core::stringify!($ty),
+                    "`].",
+                )]
+                #[inline]
+                pub fn $le(&mut self) -> Result<$ty, Incomplete> {
+                    self.array().map(<$ty>::from_le_bytes)
+                }
+
+                #[doc = ::core::concat!(
+                    "Read a big endian [`prim@",
+                    ::core::stringify!($ty),
+                    "`].",
+                )]
+                #[inline]
+                pub fn $be(&mut self) -> Result<$ty, Incomplete> {
+                    self.array().map(<$ty>::from_be_bytes)
+                }
+
+                #[doc = ::core::concat!(
+                    "Read a native endian [`prim@",
+                    ::core::stringify!($ty),
+                    "`].",
+                )]
+                #[inline]
+                pub fn $ne(&mut self) -> Result<$ty, Incomplete> {
+                    self.array().map(<$ty>::from_ne_bytes)
+                }
+            )*
+        }
+    };
+}
+
+define! {
+    u16 { u16_le, u16_be, u16_ne }
+    u32 { u32_le, u32_be, u32_ne }
+    u64 { u64_le, u64_be, u64_ne }
+    u128 { u128_le, u128_be, u128_ne }
+
+    i16 { i16_le, i16_be, i16_ne }
+    i32 { i32_le, i32_be, i32_ne }
+    i64 { i64_le, i64_be, i64_ne }
+    i128 { i128_le, i128_be, i128_ne }
+
+    f32 { f32_le, f32_be, f32_ne }
+    f64 { f64_le, f64_be, f64_ne }
+}
+
+/// An error annotated with the offset into the frame at which it occurred.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct At<E> {
+    /// The offset into the frame.
+    pub offset: usize,
+    /// The error.
+    pub error: E,
+}
+
+impl<E> At<E> {
+    /// Create a new [`At`].
+    #[inline]
+    #[must_use]
+    pub const fn new(offset: usize, error: E) -> At<E> {
+        At { offset, error }
+    }
+
+    /// Render the error along with a hexdump of `frame`, the bytes it
+    /// occurred in, pointing at the failing byte.
+    #[inline]
+    #[must_use]
+    pub const fn diagnose<'a>(&'a self, frame: &'a [u8]) -> Diagnostic<'a, E> {
+        Diagnostic::new(self, frame)
+    }
+}
+
+impl<E: fmt::Display> fmt::Display for At<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "at byte {}: {}", self.offset, self.error)
+    }
+}
+
+impl<E: fmt::Display + fmt::Debug> core::error::Error for At<E> {}
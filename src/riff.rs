@@ -0,0 +1,231 @@
+use core::fmt;
+
+use crate::decoder::{Decoder, Error as DError, Result as DResult};
+use crate::{Size, SizeHint};
+
+/// A [`Decoder`] for RIFF chunks, as used by WAV, AVI and WebP files.
+///
+/// A chunk is a four character code, a little endian [`prim@u32`] size,
+/// and the data, followed by a padding byte if the size is odd. The
+/// padding byte may be missing at the end of the stream, as some writers
+/// omit it.
+///
+/// The chunks inside of `RIFF` and `LIST` chunks can be walked with
+/// [`Chunk::children`].
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Chunks {
+    /// The maximum size of a chunk.
+    ///
+    /// Larger chunks are a fatal error rather than incomplete.
+    pub max_len: usize,
+}
+
+impl Chunks {
+    /// Create a new decoder for RIFF chunks.
+    #[inline]
+    #[must_use]
+    pub const fn new() -> Chunks {
+        Chunks {
+            max_len: u32::MAX as usize,
+        }
+    }
+
+    /// Set the maximum size of a chunk.
+    #[inline]
+    #[must_use]
+    pub const fn with_max_len(mut self, max_len: usize) -> Chunks {
+        self.max_len = max_len;
+        self
+    }
+
+    fn next<'s>(&mut self, src: &mut &'s [u8], eof: bool) -> DResult<'s, Self> {
+        let (chunk, rest) = split(src, self.max_len, eof)?;
+
+        *src = rest;
+
+        Ok(chunk)
+    }
+}
+
+impl Default for Chunks {
+    #[inline]
+    fn default() -> Self {
+        Chunks::new()
+    }
+}
+
+impl Decoder for Chunks {
+    type Item<'src> = Chunk<'src>;
+    type Error = RiffError;
+
+    #[inline]
+    fn hint(&self) -> Size {
+        Size::new(8)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> SizeHint {
+        SizeHint::new(self.hint(), Size::new(9) + Size::new(self.max_len))
+    }
+
+    #[inline]
+    fn decode<'s>(&mut self, src: &mut &'s [u8]) -> DResult<'s, Self> {
+        self.next(src, false)
+    }
+
+    #[inline]
+    fn decode_eof<'s>(&mut self, src: &mut &'s [u8]) -> DResult<'s, Self> {
+        match self.next(src, true) {
+            Err(DError::Incomplete { .. }) if src.is_empty() => Err(DError::Eof),
+            Err(DError::Incomplete { .. }) => Err(DError::DataRemains),
+            result => result,
+        }
+    }
+}
+
+/// Split the chunk at the start of `src` from the rest, where the padding
+/// byte may be missing if `eof` is set.
+fn split(src: &[u8], max_len: usize, eof: bool) -> Result<(Chunk<'_>, &[u8]), DError<RiffError>> {
+    let Some((header, rest)) = src.split_first_chunk::<8>() else {
+        return Err(DError::Incomplete {
+            needed: Size::new(8 - src.len()),
+        });
+    };
+
+    let (id, size) = header.split_at(4);
+    let id = [id[0], id[1], id[2], id[3]];
+    let len = u32::from_le_bytes([size[0], size[1], size[2], size[3]]) as usize;
+
+    if len > max_len {
+        return Err(DError::Fatal {
+            error: RiffError::TooLong,
+        });
+    }
+
+    let padded = len.checked_add(len & 1).ok_or(DError::Fatal {
+        error: RiffError::TooLong,
+    })?;
+
+    let after = match rest.get(padded..) {
+        Some(after) => after,
+        None if eof && rest.len() == len => &[],
+        None => {
+            return Err(DError::Incomplete {
+                needed: Size::new(padded - rest.len()),
+            })
+        }
+    };
+
+    let data = &rest[..len];
+
+    Ok((Chunk { id, data }, after))
+}
+
+/// A RIFF chunk.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Chunk<'src> {
+    /// The four character code identifying the chunk.
+    pub id: [u8; 4],
+    /// The data of the chunk, without the padding byte.
+    pub data: &'src [u8],
+}
+
+impl<'src> Chunk<'src> {
+    /// Returns whether this is a `RIFF` or `LIST` chunk, which contains
+    /// other chunks.
+    #[inline]
+    #[must_use]
+    pub fn is_list(&self) -> bool {
+        matches!(&self.id, b"RIFF" | b"LIST") && self.data.len() >= 4
+    }
+
+    /// Returns the form type of a `RIFF` chunk, or the list type of a
+    /// `LIST` chunk, such as `WAVE` or `INFO`.
+    #[inline]
+    #[must_use]
+    pub fn form(&self) -> Option<[u8; 4]> {
+        match self.data {
+            [a, b, c, d, ..] if self.is_list() => Some([*a, *b, *c, *d]),
+            _ => None,
+        }
+    }
+
+    /// Returns an iterator over the chunks inside of a `RIFF` or `LIST`
+    /// chunk.
+    #[inline]
+    pub fn children(&self) -> Option<Children<'src>> {
+        if self.is_list() {
+            Some(Children {
+                src: &self.data[4..],
+            })
+        } else {
+            None
+        }
+    }
+}
+
+/// An iterator over the chunks inside of a `RIFF` or `LIST` chunk, see
+/// [`Chunk::children`].
+///
+/// Once a chunk runs past the end of its parent, the error is yielded and
+/// the iterator ends.
+#[derive(Debug, Clone)]
+pub struct Children<'src> {
+    src: &'src [u8],
+}
+
+impl<'src> Children<'src> {
+    /// Returns the bytes that have not been read yet.
+    #[inline]
+    #[must_use]
+    pub const fn remaining(&self) -> &'src [u8] {
+        self.src
+    }
+}
+
+impl<'src> Iterator for Children<'src> {
+    type Item = Result<Chunk<'src>, RiffError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.src.is_empty() {
+            return None;
+        }
+
+        match split(self.src, usize::MAX, true) {
+            Ok((chunk, rest)) => {
+                self.src = rest;
+
+                Some(Ok(chunk))
+            }
+            Err(_) => {
+                self.src = &[];
+
+                Some(Err(RiffError::Truncated))
+            }
+        }
+    }
+}
+
+impl core::iter::FusedIterator for Children<'_> {}
+
+/// Type for errors that may occur while decoding RIFF chunks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum RiffError {
+    /// A chunk is larger than the maximum size.
+    TooLong,
+    /// A chunk runs past the end of the chunk containing it.
+    Truncated,
+}
+
+impl fmt::Display for RiffError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::TooLong => f.write_str("chunk too large"),
+            Self::Truncated => f.write_str("chunk runs past the end of its parent"),
+        }
+    }
+}
+
+impl core::error::Error for RiffError {}
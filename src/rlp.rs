@@ -0,0 +1,438 @@
+use core::fmt;
+
+use crate::decoder::{Decoder, Error as DError, Result as DResult};
+use crate::{Size, SizeHint};
+
+/// A [`Decoder`] for items in Ethereum's recursive length prefix encoding.
+///
+/// Decoded lists are validated as a whole, including every nested item,
+/// so they can then be walked with [`List::iter`] without any errors.
+///
+/// Only the canonical encoding is accepted: single bytes below `0x80`
+/// must be encoded as themselves, and lengths must use the short form
+/// when possible, without leading zeros.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Rlp {
+    /// The maximum length of the payload of an item.
+    ///
+    /// Longer items are a fatal error rather than incomplete.
+    pub max_len: usize,
+    /// The maximum depth of nested lists, where the outermost list has a
+    /// depth of one.
+    pub max_depth: usize,
+}
+
+impl Rlp {
+    /// Create a new decoder for RLP items.
+    #[inline]
+    #[must_use]
+    pub const fn new() -> Rlp {
+        Rlp {
+            max_len: 8 * 1024 * 1024,
+            max_depth: 64,
+        }
+    }
+
+    /// Set the maximum length of the payload of an item.
+    #[inline]
+    #[must_use]
+    pub const fn with_max_len(mut self, max_len: usize) -> Rlp {
+        self.max_len = max_len;
+        self
+    }
+
+    /// Set the maximum depth of nested lists.
+    #[inline]
+    #[must_use]
+    pub const fn with_max_depth(mut self, max_depth: usize) -> Rlp {
+        self.max_depth = max_depth;
+        self
+    }
+
+    /// Read the item at the start of `src`, returning it along with its
+    /// encoded length.
+    fn item<'s>(&self, src: &'s [u8], depth: usize) -> Result<(Item<'s>, usize), DError<RlpError>> {
+        let header = Header::read(src)?;
+
+        if header.len > self.max_len {
+            return Err(DError::Fatal {
+                error: RlpError::TooLong,
+            });
+        }
+
+        if header.list && depth >= self.max_depth {
+            return Err(DError::Fatal {
+                error: RlpError::TooDeep,
+            });
+        }
+
+        let end = header.start.checked_add(header.len).ok_or(DError::Fatal {
+            error: RlpError::TooLong,
+        })?;
+
+        let Some(payload) = src.get(header.start..end) else {
+            return Err(DError::Incomplete {
+                needed: Size::new(end - src.len()),
+            });
+        };
+
+        if !header.list {
+            return Ok((Item::Bytes(payload), end));
+        }
+
+        let mut rest = payload;
+
+        while !rest.is_empty() {
+            let (_, len) = self.item(rest, depth + 1).map_err(|error| match error {
+                DError::Incomplete { .. } => DError::Fatal {
+                    error: RlpError::Truncated,
+                },
+                error => error,
+            })?;
+
+            rest = &rest[len..];
+        }
+
+        Ok((Item::List(List { payload }), end))
+    }
+}
+
+impl Default for Rlp {
+    #[inline]
+    fn default() -> Self {
+        Rlp::new()
+    }
+}
+
+impl Decoder for Rlp {
+    type Item<'src> = Item<'src>;
+    type Error = RlpError;
+
+    #[inline]
+    fn hint(&self) -> Size {
+        Size::new(1)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> SizeHint {
+        SizeHint::new(self.hint(), Size::new(9) + Size::new(self.max_len))
+    }
+
+    fn decode<'s>(&mut self, src: &mut &'s [u8]) -> DResult<'s, Self> {
+        let (item, len) = self.item(src, 0)?;
+
+        *src = &src[len..];
+
+        Ok(item)
+    }
+}
+
+/// The header of an item.
+struct Header {
+    /// Whether the item is a list.
+    list: bool,
+    /// The offset of the payload.
+    start: usize,
+    /// The length of the payload.
+    len: usize,
+}
+
+impl Header {
+    fn read(src: &[u8]) -> Result<Header, DError<RlpError>> {
+        let non_canonical = DError::Fatal {
+            error: RlpError::NonCanonical,
+        };
+
+        let Some(&first) = src.first() else {
+            return Err(DError::Incomplete {
+                needed: Size::new(1),
+            });
+        };
+
+        let (list, short) = match first {
+            0x00..=0x7f => {
+                return Ok(Header {
+                    list: false,
+                    start: 0,
+                    len: 1,
+                })
+            }
+            0x80..=0xbf => (false, first - 0x80),
+            0xc0..=0xff => (true, first - 0xc0),
+        };
+
+        if short < 56 {
+            let len = usize::from(short);
+
+            // A single byte below 0x80 is its own encoding.
+            if !list && len == 1 && src.get(1).is_some_and(|&b| b < 0x80) {
+                return Err(non_canonical);
+            }
+
+            return Ok(Header {
+                list,
+                start: 1,
+                len,
+            });
+        }
+
+        let len_size = usize::from(short - 55);
+        let start = 1 + len_size;
+
+        let Some(field) = src.get(1..start) else {
+            return Err(DError::Incomplete {
+                needed: Size::new(start - src.len()),
+            });
+        };
+
+        if field[0] == 0 {
+            return Err(non_canonical);
+        }
+
+        let len = field.iter().fold(0u64, |n, &b| n << 8 | u64::from(b));
+
+        if len < 56 {
+            return Err(non_canonical);
+        }
+
+        let len = usize::try_from(len).map_err(|_| DError::Fatal {
+            error: RlpError::TooLong,
+        })?;
+
+        Ok(Header { list, start, len })
+    }
+}
+
+/// An RLP item, which is either a byte string or a list of items.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Item<'src> {
+    /// A byte string.
+    Bytes(&'src [u8]),
+    /// A list of items.
+    List(List<'src>),
+}
+
+impl<'src> Item<'src> {
+    /// Returns the byte string, if this is one.
+    #[inline]
+    #[must_use]
+    pub const fn as_bytes(&self) -> Option<&'src [u8]> {
+        match self {
+            Self::Bytes(bytes) => Some(bytes),
+            Self::List(_) => None,
+        }
+    }
+
+    /// Returns the list, if this is one.
+    #[inline]
+    #[must_use]
+    pub const fn as_list(&self) -> Option<List<'src>> {
+        match self {
+            Self::Bytes(_) => None,
+            Self::List(list) => Some(*list),
+        }
+    }
+}
+
+/// A list of RLP items, which has been validated.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct List<'src> {
+    payload: &'src [u8],
+}
+
+impl<'src> List<'src> {
+    /// Returns the encoded items of the list.
+    #[inline]
+    #[must_use]
+    pub const fn payload(&self) -> &'src [u8] {
+        self.payload
+    }
+
+    /// Returns whether the list has no items.
+    #[inline]
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.payload.is_empty()
+    }
+
+    /// Returns an iterator over the items of the list.
+    #[inline]
+    pub fn iter(&self) -> Iter<'src> {
+        Iter { src: self.payload }
+    }
+}
+
+impl fmt::Debug for List<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<'src> IntoIterator for List<'src> {
+    type Item = Item<'src>;
+    type IntoIter = Iter<'src>;
+
+    #[inline]
+    fn into_iter(self) -> Iter<'src> {
+        self.iter()
+    }
+}
+
+/// An iterator over the items of a [`List`].
+#[derive(Debug, Clone)]
+pub struct Iter<'src> {
+    src: &'src [u8],
+}
+
+impl<'src> Iterator for Iter<'src> {
+    type Item = Item<'src>;
+
+    fn next(&mut self) -> Option<Item<'src>> {
+        if self.src.is_empty() {
+            return None;
+        }
+
+        let header = Header::read(self.src).expect("list items are validated");
+        let (item, rest) = self.src[header.start..].split_at(header.len);
+
+        self.src = rest;
+
+        Some(if header.list {
+            Item::List(List { payload: item })
+        } else {
+            Item::Bytes(item)
+        })
+    }
+}
+
+impl core::iter::FusedIterator for Iter<'_> {}
+
+/// Type for errors that may occur while decoding RLP items.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum RlpError {
+    /// An item is not in the canonical encoding.
+    NonCanonical,
+    /// An item is longer than the maximum length, or than fits in memory.
+    TooLong,
+    /// Lists are nested deeper than the maximum depth.
+    TooDeep,
+    /// An item runs past the end of the list containing it.
+    Truncated,
+}
+
+impl fmt::Display for RlpError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NonCanonical => f.write_str("non-canonical encoding"),
+            Self::TooLong => f.write_str("item too long"),
+            Self::TooDeep => f.write_str("lists nested too deeply"),
+            Self::Truncated => f.write_str("item runs past the end of its list"),
+        }
+    }
+}
+
+impl core::error::Error for RlpError {}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use std::vec::Vec;
+
+    use super::*;
+
+    fn decode(bytes: &[u8]) -> DResult<'_, Rlp> {
+        let mut src = bytes;
+        let item = Rlp::new().decode(&mut src)?;
+
+        assert!(src.is_empty(), "trailing bytes: {src:?}");
+
+        Ok(item)
+    }
+
+    fn fatal<T>(error: RlpError) -> Result<T, DError<RlpError>> {
+        Err(DError::Fatal { error })
+    }
+
+    #[test]
+    fn bytes() {
+        let lorem = b"Lorem ipsum dolor sit amet, consectetur adipisicing elit";
+        let long = [&[0xb8, 0x38][..], lorem].concat();
+
+        let cases: [(&[u8], &[u8]); 5] = [
+            (b"", b"\x80"),
+            (b"\x0f", b"\x0f"),
+            (b"\x80", b"\x81\x80"),
+            (b"dog", b"\x83dog"),
+            (lorem, &long),
+        ];
+
+        for (payload, bytes) in cases {
+            assert_eq!(decode(bytes), Ok(Item::Bytes(payload)));
+        }
+    }
+
+    #[test]
+    fn lists() {
+        let item = decode(b"\xc8\x83cat\x83dog").unwrap();
+        let list = item.as_list().unwrap();
+
+        assert_eq!(item.as_bytes(), None);
+        assert_eq!(
+            list.iter().map(|item| item.as_bytes()).collect::<Vec<_>>(),
+            [Some(&b"cat"[..]), Some(b"dog")]
+        );
+
+        assert!(decode(b"\xc0").unwrap().as_list().unwrap().is_empty());
+
+        // The set theoretical representation of three.
+        let three = decode(b"\xc7\xc0\xc1\xc0\xc3\xc0\xc1\xc0").unwrap();
+        let items = three.as_list().unwrap().iter().collect::<Vec<_>>();
+
+        assert_eq!(items.len(), 3);
+        assert!(items[0].as_list().unwrap().is_empty());
+        assert_eq!(items[1].as_list().unwrap().payload(), b"\xc0");
+        assert_eq!(items[2].as_list().unwrap().payload(), b"\xc0\xc1\xc0");
+    }
+
+    #[test]
+    fn incomplete() {
+        let needed = |n| {
+            Err(DError::Incomplete {
+                needed: Size::new(n),
+            })
+        };
+
+        assert_eq!(Rlp::new().decode(&mut &b""[..]), needed(1));
+        assert_eq!(Rlp::new().decode(&mut &b"\x83d"[..]), needed(2));
+        assert_eq!(Rlp::new().decode(&mut &b"\xb9\x01"[..]), needed(1));
+        assert_eq!(Rlp::new().decode(&mut &b"\xc8\x83cat"[..]), needed(4));
+    }
+
+    #[test]
+    fn malformed() {
+        assert_eq!(decode(b"\x81\x05"), fatal(RlpError::NonCanonical));
+        assert_eq!(decode(b"\xb8\x01a"), fatal(RlpError::NonCanonical));
+        assert_eq!(decode(b"\xb9\x00\x38"), fatal(RlpError::NonCanonical));
+        assert_eq!(decode(b"\xc2\x83a"), fatal(RlpError::Truncated));
+
+        let mut rlp = Rlp::new().with_max_depth(1);
+
+        assert!(rlp.decode(&mut &b"\xc0"[..]).is_ok());
+        assert_eq!(rlp.decode(&mut &b"\xc1\xc0"[..]), fatal(RlpError::TooDeep));
+
+        let mut rlp = Rlp::new().with_max_len(2);
+
+        assert_eq!(rlp.decode(&mut &b"\x83dog"[..]), fatal(RlpError::TooLong));
+
+        // The payload would end past the end of the address space.
+        let mut rlp = Rlp::new().with_max_len(usize::MAX);
+
+        assert_eq!(
+            rlp.decode(&mut &b"\xbf\xff\xff\xff\xff\xff\xff\xff\xff"[..]),
+            fatal(RlpError::TooLong)
+        );
+    }
+}
@@ -0,0 +1,336 @@
+use core::fmt::{self, Write};
+
+use arrayvec::ArrayString;
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::decoder::{Decoder, Result as DResult};
+use crate::encoder::{Encoder, Error as EError, Result as EResult};
+use crate::endian::{ByteOrder, LittleEndian, NativeEndian};
+use crate::Size;
+
+mod de;
+mod ser;
+
+pub use de::Deserializer;
+pub use ser::Serializer;
+
+/// The width of the length prefix of strings, byte arrays, sequences and
+/// maps.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum LenWidth {
+    /// A [`prim@u8`] length prefix.
+    U8,
+    /// A [`prim@u16`] length prefix.
+    U16,
+    /// A [`prim@u32`] length prefix.
+    U32,
+    /// A [`prim@u64`] length prefix.
+    U64,
+}
+
+/// The encoding of integers wider than a byte, along with length prefixes
+/// and the indices of enum variants.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum IntEncoding {
+    /// Integers are written at their full width, and length prefixes
+    /// with the [`LenWidth`] of the [`Config`].
+    Fixed,
+    /// Integers are written as bincode's variable-length integers, where
+    /// values below 251 are a single byte, and otherwise the byte 251,
+    /// 252, 253 or 254 is followed by a [`prim@u16`], [`prim@u32`],
+    /// [`prim@u64`] or [`prim@u128`] respectively.
+    ///
+    /// Signed integers are zigzag encoded first, and length prefixes are
+    /// written as a [`prim@u64`].
+    Varint,
+    /// Integers are written as LEB128, 7 bits at a time with the least
+    /// significant group first, as used by postcard.
+    ///
+    /// Signed integers are zigzag encoded first, and length prefixes are
+    /// written as a [`prim@u64`].
+    Leb128,
+}
+
+/// The encoding of chars.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum CharEncoding {
+    /// Chars are written as a [`prim@u32`].
+    U32,
+    /// Chars are written as their UTF-8 bytes.
+    Utf8,
+    /// Chars are written as a string of their UTF-8 bytes, with a length
+    /// prefix.
+    Str,
+}
+
+/// The settings of the binary format.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Config<B: ByteOrder = NativeEndian> {
+    /// The byte order of primitives and length prefixes.
+    pub byte_order: B,
+    /// The width of length prefixes.
+    pub len_width: LenWidth,
+    /// The encoding of integers.
+    pub int_encoding: IntEncoding,
+    /// The encoding of chars.
+    pub char_encoding: CharEncoding,
+    /// The maximum length of strings, byte arrays, sequences and maps.
+    ///
+    /// Longer lengths are a fatal error rather than incomplete.
+    pub max_len: usize,
+}
+
+impl<B: ByteOrder> Config<B> {
+    /// Create a new [`Config`] with [`prim@u32`] length prefixes.
+    #[inline]
+    #[must_use]
+    pub const fn new(byte_order: B) -> Config<B> {
+        Config {
+            byte_order,
+            len_width: LenWidth::U32,
+            int_encoding: IntEncoding::Fixed,
+            char_encoding: CharEncoding::U32,
+            max_len: usize::MAX,
+        }
+    }
+
+    /// Set the width of length prefixes.
+    #[inline]
+    #[must_use]
+    pub const fn with_len_width(mut self, len_width: LenWidth) -> Config<B> {
+        self.len_width = len_width;
+        self
+    }
+
+    /// Set the encoding of integers.
+    #[inline]
+    #[must_use]
+    pub const fn with_int_encoding(mut self, int_encoding: IntEncoding) -> Config<B> {
+        self.int_encoding = int_encoding;
+        self
+    }
+
+    /// Set the encoding of chars.
+    #[inline]
+    #[must_use]
+    pub const fn with_char_encoding(mut self, char_encoding: CharEncoding) -> Config<B> {
+        self.char_encoding = char_encoding;
+        self
+    }
+
+    /// Set the maximum length of strings, byte arrays, sequences and maps.
+    #[inline]
+    #[must_use]
+    pub const fn with_max_len(mut self, max_len: usize) -> Config<B> {
+        self.max_len = max_len;
+        self
+    }
+}
+
+impl Config<LittleEndian> {
+    /// Create a new [`Config`] matching bincode's fixed-int encoding, as
+    /// used by `bincode::serialize` in bincode 1 and by
+    /// `bincode::config::legacy()` in bincode 2.
+    ///
+    /// Integers are little endian at their full width, lengths are a
+    /// [`prim@u64`], and chars are UTF-8. Use
+    /// [`IntEncoding::Varint`] to match `bincode::config::standard()`.
+    #[inline]
+    #[must_use]
+    pub const fn bincode() -> Config<LittleEndian> {
+        Config::new(LittleEndian)
+            .with_len_width(LenWidth::U64)
+            .with_char_encoding(CharEncoding::Utf8)
+    }
+
+    /// Create a new [`Config`] matching postcard's wire format.
+    ///
+    /// Integers are LEB128, and chars are strings.
+    #[inline]
+    #[must_use]
+    pub const fn postcard() -> Config<LittleEndian> {
+        Config::new(LittleEndian)
+            .with_int_encoding(IntEncoding::Leb128)
+            .with_char_encoding(CharEncoding::Str)
+    }
+}
+
+impl<B: ByteOrder + Default> Default for Config<B> {
+    #[inline]
+    fn default() -> Self {
+        Config::new(B::default())
+    }
+}
+
+/// A [`Decoder`] and [`Encoder`] for a type implementing [`serde`]'s
+/// traits, in a compact binary format.
+///
+/// Primitives are written in a specified byte order. Strings, byte arrays,
+/// sequences and maps are prefixed with their length, options with a tag
+/// byte, and enum variants with their index as a [`prim@u32`]. Structs and
+/// tuples are written as their fields in order, so the format is not
+/// self-describing.
+///
+/// With [`Config::bincode`] and [`Config::postcard`], the format is
+/// compatible with bincode and postcard respectively.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Serde<T, B: ByteOrder = NativeEndian> {
+    /// The settings of the binary format.
+    pub config: Config<B>,
+    _marker: core::marker::PhantomData<fn() -> T>,
+}
+
+impl<T, B: ByteOrder> Serde<T, B> {
+    /// Create a new codec for `T`.
+    #[inline]
+    #[must_use]
+    pub const fn new(config: Config<B>) -> Serde<T, B> {
+        Serde {
+            config,
+            _marker: core::marker::PhantomData,
+        }
+    }
+}
+
+impl<T, B: ByteOrder + Default> Default for Serde<T, B> {
+    #[inline]
+    fn default() -> Self {
+        Serde::new(Config::default())
+    }
+}
+
+impl<T: DeserializeOwned, B: ByteOrder> Decoder for Serde<T, B> {
+    type Item<'src> = T;
+    type Error = Error;
+
+    #[inline]
+    fn decode<'s>(&mut self, src: &mut &'s [u8]) -> DResult<'s, Self> {
+        let mut deserializer = Deserializer::new(src, self.config);
+        let item = T::deserialize(&mut deserializer)?;
+
+        *src = deserializer.remaining();
+
+        Ok(item)
+    }
+}
+
+impl<T: Serialize, B: ByteOrder> Encoder<T> for Serde<T, B> {
+    type Error = Error;
+
+    #[inline]
+    fn encode_hint(&self, item: &T) -> Size {
+        let mut serializer = Serializer::new(&mut [], self.config);
+
+        match item.serialize(&mut serializer) {
+            Ok(()) => serializer.finish().map_or_else(
+                |error| match error {
+                    EError::Full { needed } => needed,
+                    EError::Fatal { .. } => Size::Unknown,
+                },
+                Size::new,
+            ),
+            Err(_) => Size::Unknown,
+        }
+    }
+
+    #[inline]
+    fn encode(&mut self, item: &T, dst: &mut &mut [u8]) -> EResult<Self::Error> {
+        let mut serializer = Serializer::new(dst, self.config);
+
+        item.serialize(&mut serializer)?;
+
+        let len = serializer.finish()?;
+
+        *dst = &mut core::mem::take(dst)[len..];
+
+        Ok(())
+    }
+}
+
+/// The maximum length of the message of [`Error::Custom`].
+const MESSAGE_LEN: usize = 64;
+
+/// Type for errors that may occur in the binary format.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Error {
+    /// The bits of a bool are neither zero nor one.
+    InvalidBool(u8),
+    /// The bits of a char are not a Unicode scalar value.
+    InvalidChar(u32),
+    /// A string or char is not valid UTF-8.
+    InvalidUtf8,
+    /// The tag of an option is neither zero nor one.
+    InvalidTag(u8),
+    /// A length is too large.
+    TooLong,
+    /// A variable-length integer has an invalid marker, or does not fit
+    /// in its type.
+    InvalidVarint,
+    /// The format does not support an operation.
+    Unsupported(&'static str),
+    /// A message from the type being (de)serialized.
+    ///
+    /// Messages are truncated to fit without allocating.
+    Custom(ArrayString<MESSAGE_LEN>),
+}
+
+impl Error {
+    /// Create a new [`Error::Custom`], truncating `msg` if needed.
+    fn custom(msg: impl fmt::Display) -> Error {
+        struct Truncate<'a>(&'a mut ArrayString<MESSAGE_LEN>);
+
+        impl Write for Truncate<'_> {
+            fn write_str(&mut self, s: &str) -> fmt::Result {
+                for ch in s.chars() {
+                    self.0.try_push(ch).map_err(|_| fmt::Error)?;
+                }
+
+                Ok(())
+            }
+        }
+
+        let mut message = ArrayString::new();
+        let _ = write!(Truncate(&mut message), "{msg}");
+
+        Error::Custom(message)
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidBool(bits) => write!(f, "invalid bits for a bool: {bits}"),
+            Self::InvalidChar(bits) => write!(f, "invalid bits for a char: {bits:#x}"),
+            Self::InvalidUtf8 => f.write_str("invalid utf-8"),
+            Self::InvalidTag(tag) => write!(f, "invalid tag for an option: {tag}"),
+            Self::TooLong => f.write_str("length too large"),
+            Self::InvalidVarint => f.write_str("invalid variable-length integer"),
+            Self::Unsupported(what) => write!(f, "unsupported: {what}"),
+            Self::Custom(message) => f.write_str(message),
+        }
+    }
+}
+
+impl core::error::Error for Error {}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for Error {
+    fn format(&self, f: defmt::Formatter<'_>) {
+        match self {
+            Self::InvalidBool(bits) => defmt::write!(f, "invalid bits for a bool: {=u8}", bits),
+            Self::InvalidChar(bits) => {
+                defmt::write!(f, "invalid bits for a char: {=u32:#x}", bits)
+            }
+            Self::InvalidUtf8 => defmt::write!(f, "invalid utf-8"),
+            Self::InvalidTag(tag) => defmt::write!(f, "invalid tag for an option: {=u8}", tag),
+            Self::TooLong => defmt::write!(f, "length too large"),
+            Self::InvalidVarint => defmt::write!(f, "invalid variable-length integer"),
+            Self::Unsupported(what) => defmt::write!(f, "unsupported: {=str}", what),
+            Self::Custom(message) => defmt::write!(f, "{=str}", message.as_str()),
+        }
+    }
+}
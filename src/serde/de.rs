@@ -0,0 +1,456 @@
+use core::fmt;
+
+use serde::de::{self, value::U32Deserializer, DeserializeSeed, IntoDeserializer, Visitor};
+
+use super::{CharEncoding, Config, Error, IntEncoding, LenWidth};
+use crate::decoder::Error as DError;
+use crate::endian::{ByteOrder, NativeEndian};
+use crate::frame::Layout;
+use crate::Size;
+
+type Result<T> = core::result::Result<T, DError<Error>>;
+
+impl de::Error for DError<Error> {
+    #[inline]
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        DError::Fatal {
+            error: Error::custom(msg),
+        }
+    }
+}
+
+/// A [`serde`] deserializer reading the binary format from a byte
+/// slice.
+///
+/// Running out of bytes is reported as [`DError::Incomplete`], so a value
+/// can be deserialized again once more bytes have been read.
+#[derive(Debug, Clone)]
+pub struct Deserializer<'de, B: ByteOrder = NativeEndian> {
+    src: &'de [u8],
+    config: Config<B>,
+}
+
+impl<'de, B: ByteOrder> Deserializer<'de, B> {
+    /// Create a new [`Deserializer`] reading from `src`.
+    #[inline]
+    #[must_use]
+    pub const fn new(src: &'de [u8], config: Config<B>) -> Deserializer<'de, B> {
+        Deserializer { src, config }
+    }
+
+    /// Returns the bytes that have not been read yet.
+    #[inline]
+    #[must_use]
+    pub const fn remaining(&self) -> &'de [u8] {
+        self.src
+    }
+
+    fn take(&mut self, len: usize) -> Result<&'de [u8]> {
+        let Some((bytes, rest)) = self.src.split_at_checked(len) else {
+            return Err(DError::Incomplete {
+                needed: Size::new(len - self.src.len()),
+            });
+        };
+
+        self.src = rest;
+
+        Ok(bytes)
+    }
+
+    /// Read a primitive, whose layout is always valid.
+    fn read<T: Layout>(&mut self) -> Result<T> {
+        let bytes = self.take(T::SIZE)?;
+
+        Ok(T::read(bytes, self.config.byte_order.endian()).expect("primitives are always valid"))
+    }
+
+    fn read_bool(&mut self) -> Result<bool> {
+        match self.read::<u8>()? {
+            0 => Ok(false),
+            1 => Ok(true),
+            bits => Err(DError::Fatal {
+                error: Error::InvalidBool(bits),
+            }),
+        }
+    }
+
+    /// Read a bincode variable-length integer.
+    fn read_varint(&mut self) -> Result<u128> {
+        match self.read::<u8>()? {
+            byte @ 0..=250 => Ok(byte.into()),
+            251 => Ok(self.read::<u16>()?.into()),
+            252 => Ok(self.read::<u32>()?.into()),
+            253 => Ok(self.read::<u64>()?.into()),
+            254 => self.read::<u128>(),
+            _ => Err(DError::Fatal {
+                error: Error::InvalidVarint,
+            }),
+        }
+    }
+
+    /// Read a LEB128 integer of at most `size` bytes once decoded.
+    fn read_leb128(&mut self, size: usize) -> Result<u128> {
+        let max_len = (8 * size).div_ceil(7);
+        let mut value = 0u128;
+
+        for i in 0..max_len as u32 {
+            let byte = self.read::<u8>()?;
+            let bits = u128::from(byte & 0x7f);
+
+            if bits.leading_zeros() < 7 * i {
+                break;
+            }
+
+            value |= bits << (7 * i);
+
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+
+        Err(DError::Fatal {
+            error: Error::InvalidVarint,
+        })
+    }
+
+    fn read_unsigned<T: Layout + TryFrom<u128>>(&mut self) -> Result<T> {
+        let n = match self.config.int_encoding {
+            IntEncoding::Fixed => return self.read(),
+            IntEncoding::Varint => self.read_varint()?,
+            IntEncoding::Leb128 => self.read_leb128(T::SIZE)?,
+        };
+
+        T::try_from(n).map_err(|_| DError::Fatal {
+            error: Error::InvalidVarint,
+        })
+    }
+
+    fn read_signed<T: Layout + TryFrom<i128>>(&mut self) -> Result<T> {
+        let n = match self.config.int_encoding {
+            IntEncoding::Fixed => return self.read(),
+            IntEncoding::Varint => self.read_varint()?,
+            IntEncoding::Leb128 => self.read_leb128(T::SIZE)?,
+        };
+
+        T::try_from((n >> 1) as i128 ^ -((n & 1) as i128)).map_err(|_| DError::Fatal {
+            error: Error::InvalidVarint,
+        })
+    }
+
+    fn read_char(&mut self) -> Result<char> {
+        let bytes = match self.config.char_encoding {
+            CharEncoding::U32 => {
+                let bits = self.read::<u32>()?;
+
+                return char::from_u32(bits).ok_or(DError::Fatal {
+                    error: Error::InvalidChar(bits),
+                });
+            }
+            CharEncoding::Utf8 => {
+                let len = match self.src.first() {
+                    Some(0xf0..) => 4,
+                    Some(0xe0..) => 3,
+                    Some(0xc0..) => 2,
+                    _ => 1,
+                };
+
+                self.take(len)?
+            }
+            CharEncoding::Str => self.read_bytes()?,
+        };
+
+        let mut chars = core::str::from_utf8(bytes).unwrap_or_default().chars();
+
+        match (chars.next(), chars.next()) {
+            (Some(ch), None) => Ok(ch),
+            _ => Err(DError::Fatal {
+                error: Error::InvalidUtf8,
+            }),
+        }
+    }
+
+    fn read_len(&mut self) -> Result<usize> {
+        let len = match (self.config.int_encoding, self.config.len_width) {
+            (IntEncoding::Varint | IntEncoding::Leb128, _) => self.read_unsigned::<u64>()?,
+            (_, LenWidth::U8) => u64::from(self.read::<u8>()?),
+            (_, LenWidth::U16) => u64::from(self.read::<u16>()?),
+            (_, LenWidth::U32) => u64::from(self.read::<u32>()?),
+            (_, LenWidth::U64) => self.read::<u64>()?,
+        };
+
+        match usize::try_from(len) {
+            Ok(len) if len <= self.config.max_len => Ok(len),
+            _ => Err(DError::Fatal {
+                error: Error::TooLong,
+            }),
+        }
+    }
+
+    fn read_bytes(&mut self) -> Result<&'de [u8]> {
+        let len = self.read_len()?;
+
+        self.take(len)
+    }
+
+    fn read_str(&mut self) -> Result<&'de str> {
+        core::str::from_utf8(self.read_bytes()?).map_err(|_| DError::Fatal {
+            error: Error::InvalidUtf8,
+        })
+    }
+}
+
+macro_rules! primitives {
+    ($($deserialize:ident => $visit:ident($read:ident::<$ty:ty>),)*) => {
+        $(
+            #[inline]
+            fn $deserialize<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+                visitor.$visit(self.$read::<$ty>()?)
+            }
+        )*
+    };
+}
+
+impl<'de, B: ByteOrder> de::Deserializer<'de> for &mut Deserializer<'de, B> {
+    type Error = DError<Error>;
+
+    primitives! {
+        deserialize_u8 => visit_u8(read::<u8>),
+        deserialize_u16 => visit_u16(read_unsigned::<u16>),
+        deserialize_u32 => visit_u32(read_unsigned::<u32>),
+        deserialize_u64 => visit_u64(read_unsigned::<u64>),
+        deserialize_u128 => visit_u128(read_unsigned::<u128>),
+        deserialize_i8 => visit_i8(read::<i8>),
+        deserialize_i16 => visit_i16(read_signed::<i16>),
+        deserialize_i32 => visit_i32(read_signed::<i32>),
+        deserialize_i64 => visit_i64(read_signed::<i64>),
+        deserialize_i128 => visit_i128(read_signed::<i128>),
+        deserialize_f32 => visit_f32(read::<f32>),
+        deserialize_f64 => visit_f64(read::<f64>),
+        deserialize_identifier => visit_u32(read_unsigned::<u32>),
+    }
+
+    #[inline]
+    fn deserialize_any<V: Visitor<'de>>(self, _: V) -> Result<V::Value> {
+        Err(DError::Fatal {
+            error: Error::Unsupported("deserializing without a type"),
+        })
+    }
+
+    #[inline]
+    fn deserialize_ignored_any<V: Visitor<'de>>(self, _: V) -> Result<V::Value> {
+        Err(DError::Fatal {
+            error: Error::Unsupported("ignoring values"),
+        })
+    }
+
+    #[inline]
+    fn deserialize_bool<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        visitor.visit_bool(self.read_bool()?)
+    }
+
+    #[inline]
+    fn deserialize_char<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        visitor.visit_char(self.read_char()?)
+    }
+
+    #[inline]
+    fn deserialize_str<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        visitor.visit_borrowed_str(self.read_str()?)
+    }
+
+    #[inline]
+    fn deserialize_string<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        self.deserialize_str(visitor)
+    }
+
+    #[inline]
+    fn deserialize_bytes<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        visitor.visit_borrowed_bytes(self.read_bytes()?)
+    }
+
+    #[inline]
+    fn deserialize_byte_buf<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        self.deserialize_bytes(visitor)
+    }
+
+    #[inline]
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        match self.read::<u8>()? {
+            0 => visitor.visit_none(),
+            1 => visitor.visit_some(self),
+            tag => Err(DError::Fatal {
+                error: Error::InvalidTag(tag),
+            }),
+        }
+    }
+
+    #[inline]
+    fn deserialize_unit<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        visitor.visit_unit()
+    }
+
+    #[inline]
+    fn deserialize_unit_struct<V: Visitor<'de>>(
+        self,
+        _: &'static str,
+        visitor: V,
+    ) -> Result<V::Value> {
+        visitor.visit_unit()
+    }
+
+    #[inline]
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _: &'static str,
+        visitor: V,
+    ) -> Result<V::Value> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    #[inline]
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        let len = self.read_len()?;
+
+        visitor.visit_seq(Access { de: self, len })
+    }
+
+    #[inline]
+    fn deserialize_tuple<V: Visitor<'de>>(self, len: usize, visitor: V) -> Result<V::Value> {
+        visitor.visit_seq(Access { de: self, len })
+    }
+
+    #[inline]
+    fn deserialize_tuple_struct<V: Visitor<'de>>(
+        self,
+        _: &'static str,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value> {
+        self.deserialize_tuple(len, visitor)
+    }
+
+    #[inline]
+    fn deserialize_map<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        let len = self.read_len()?;
+
+        visitor.visit_map(Access { de: self, len })
+    }
+
+    #[inline]
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value> {
+        self.deserialize_tuple(fields.len(), visitor)
+    }
+
+    #[inline]
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _: &'static str,
+        _: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value> {
+        visitor.visit_enum(self)
+    }
+
+    #[inline]
+    fn is_human_readable(&self) -> bool {
+        false
+    }
+}
+
+/// Access to the elements of a sequence or map of a known length.
+struct Access<'a, 'de, B: ByteOrder> {
+    de: &'a mut Deserializer<'de, B>,
+    len: usize,
+}
+
+impl<'de, B: ByteOrder> de::SeqAccess<'de> for Access<'_, 'de, B> {
+    type Error = DError<Error>;
+
+    #[inline]
+    fn next_element_seed<T: DeserializeSeed<'de>>(&mut self, seed: T) -> Result<Option<T::Value>> {
+        if self.len == 0 {
+            return Ok(None);
+        }
+
+        self.len -= 1;
+
+        seed.deserialize(&mut *self.de).map(Some)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.len)
+    }
+}
+
+impl<'de, B: ByteOrder> de::MapAccess<'de> for Access<'_, 'de, B> {
+    type Error = DError<Error>;
+
+    #[inline]
+    fn next_key_seed<K: DeserializeSeed<'de>>(&mut self, seed: K) -> Result<Option<K::Value>> {
+        if self.len == 0 {
+            return Ok(None);
+        }
+
+        self.len -= 1;
+
+        seed.deserialize(&mut *self.de).map(Some)
+    }
+
+    #[inline]
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value> {
+        seed.deserialize(&mut *self.de)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.len)
+    }
+}
+
+impl<'de, B: ByteOrder> de::EnumAccess<'de> for &mut Deserializer<'de, B> {
+    type Error = DError<Error>;
+    type Variant = Self;
+
+    #[inline]
+    fn variant_seed<V: DeserializeSeed<'de>>(self, seed: V) -> Result<(V::Value, Self)> {
+        let index = self.read_unsigned::<u32>()?;
+        let index: U32Deserializer<DError<Error>> = index.into_deserializer();
+        let value = seed.deserialize(index)?;
+
+        Ok((value, self))
+    }
+}
+
+impl<'de, B: ByteOrder> de::VariantAccess<'de> for &mut Deserializer<'de, B> {
+    type Error = DError<Error>;
+
+    #[inline]
+    fn unit_variant(self) -> Result<()> {
+        Ok(())
+    }
+
+    #[inline]
+    fn newtype_variant_seed<T: DeserializeSeed<'de>>(self, seed: T) -> Result<T::Value> {
+        seed.deserialize(self)
+    }
+
+    #[inline]
+    fn tuple_variant<V: Visitor<'de>>(self, len: usize, visitor: V) -> Result<V::Value> {
+        de::Deserializer::deserialize_tuple(self, len, visitor)
+    }
+
+    #[inline]
+    fn struct_variant<V: Visitor<'de>>(
+        self,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value> {
+        de::Deserializer::deserialize_tuple(self, fields.len(), visitor)
+    }
+}
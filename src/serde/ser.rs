@@ -0,0 +1,450 @@
+use core::fmt::{self, Write};
+
+use serde::ser::{self, Serialize};
+
+use super::{CharEncoding, Config, Error, IntEncoding, LenWidth};
+use crate::encoder::Error as EError;
+use crate::endian::{ByteOrder, NativeEndian};
+use crate::frame::Layout;
+use crate::Size;
+
+type Result<T = ()> = core::result::Result<T, EError<Error>>;
+
+impl ser::Error for EError<Error> {
+    #[inline]
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        EError::Fatal {
+            error: Error::custom(msg),
+        }
+    }
+}
+
+/// A [`serde`] serializer writing the binary format into a byte slice.
+///
+/// Running out of space is only reported by [`Serializer::finish`], after
+/// the rest of the value has been measured, so the amount of space needed
+/// is exact.
+#[derive(Debug)]
+pub struct Serializer<'a, B: ByteOrder = NativeEndian> {
+    dst: &'a mut [u8],
+    len: usize,
+    config: Config<B>,
+}
+
+impl<'a, B: ByteOrder> Serializer<'a, B> {
+    /// Create a new [`Serializer`] writing into `dst`.
+    #[inline]
+    #[must_use]
+    pub const fn new(dst: &'a mut [u8], config: Config<B>) -> Serializer<'a, B> {
+        Serializer {
+            dst,
+            len: 0,
+            config,
+        }
+    }
+
+    /// Returns the amount of bytes written, or the amount of additional
+    /// bytes needed if `dst` is too small.
+    #[inline]
+    pub fn finish(self) -> Result<usize> {
+        match self.len.checked_sub(self.dst.len()) {
+            Some(needed @ 1..) => Err(EError::Full {
+                needed: Size::new(needed),
+            }),
+            _ => Ok(self.len),
+        }
+    }
+
+    /// Returns the bytes for the next `len` bytes of output, if they fit.
+    fn reserve(&mut self, len: usize) -> Option<&mut [u8]> {
+        let start = self.len;
+
+        self.len = self.len.saturating_add(len);
+        self.dst.get_mut(start..self.len)
+    }
+
+    fn write<T: Layout>(&mut self, value: T) -> Result {
+        let endian = self.config.byte_order.endian();
+
+        if let Some(bytes) = self.reserve(T::SIZE) {
+            value.write(bytes, endian);
+        }
+
+        Ok(())
+    }
+
+    /// Write a bincode variable-length integer.
+    fn write_varint(&mut self, value: u128) -> Result {
+        if let Ok(value @ 0..=250) = u8::try_from(value) {
+            self.write(value)
+        } else if let Ok(value) = u16::try_from(value) {
+            self.write(251u8)?;
+            self.write(value)
+        } else if let Ok(value) = u32::try_from(value) {
+            self.write(252u8)?;
+            self.write(value)
+        } else if let Ok(value) = u64::try_from(value) {
+            self.write(253u8)?;
+            self.write(value)
+        } else {
+            self.write(254u8)?;
+            self.write(value)
+        }
+    }
+
+    /// Write a LEB128 integer.
+    fn write_leb128(&mut self, mut value: u128) -> Result {
+        loop {
+            let byte = (value & 0x7f) as u8;
+
+            value >>= 7;
+
+            if value == 0 {
+                return self.write(byte);
+            }
+
+            self.write(byte | 0x80)?;
+        }
+    }
+
+    fn write_unsigned<T: Layout + Into<u128>>(&mut self, value: T) -> Result {
+        match self.config.int_encoding {
+            IntEncoding::Fixed => self.write(value),
+            IntEncoding::Varint => self.write_varint(value.into()),
+            IntEncoding::Leb128 => self.write_leb128(value.into()),
+        }
+    }
+
+    fn write_signed<T: Layout + Into<i128>>(&mut self, value: T) -> Result {
+        if self.config.int_encoding == IntEncoding::Fixed {
+            return self.write(value);
+        }
+
+        let n: i128 = value.into();
+        let n = ((n << 1) ^ (n >> 127)) as u128;
+
+        match self.config.int_encoding {
+            IntEncoding::Leb128 => self.write_leb128(n),
+            _ => self.write_varint(n),
+        }
+    }
+
+    fn write_char(&mut self, value: char) -> Result {
+        let mut buf = [0; 4];
+        let bytes = value.encode_utf8(&mut buf).as_bytes();
+
+        match self.config.char_encoding {
+            CharEncoding::U32 => self.write(value),
+            CharEncoding::Utf8 => {
+                if let Some(dst) = self.reserve(bytes.len()) {
+                    dst.copy_from_slice(bytes);
+                }
+
+                Ok(())
+            }
+            CharEncoding::Str => self.write_bytes(bytes),
+        }
+    }
+
+    fn write_len(&mut self, len: usize) -> Result {
+        let too_long = EError::Fatal {
+            error: Error::TooLong,
+        };
+
+        if len > self.config.max_len {
+            return Err(too_long);
+        }
+
+        match (self.config.int_encoding, self.config.len_width) {
+            (IntEncoding::Varint | IntEncoding::Leb128, _) => {
+                self.write_unsigned(u64::try_from(len).map_err(|_| too_long)?)
+            }
+            (_, LenWidth::U8) => self.write(u8::try_from(len).map_err(|_| too_long)?),
+            (_, LenWidth::U16) => self.write(u16::try_from(len).map_err(|_| too_long)?),
+            (_, LenWidth::U32) => self.write(u32::try_from(len).map_err(|_| too_long)?),
+            (_, LenWidth::U64) => self.write(u64::try_from(len).map_err(|_| too_long)?),
+        }
+    }
+
+    fn write_bytes(&mut self, bytes: &[u8]) -> Result {
+        self.write_len(bytes.len())?;
+
+        if let Some(dst) = self.reserve(bytes.len()) {
+            dst.copy_from_slice(bytes);
+        }
+
+        Ok(())
+    }
+
+    fn unknown_len(len: Option<usize>) -> Result<usize> {
+        len.ok_or(EError::Fatal {
+            error: Error::Unsupported("sequences and maps of unknown length"),
+        })
+    }
+}
+
+macro_rules! primitives {
+    ($($serialize:ident($ty:ty) => $write:ident,)*) => {
+        $(
+            #[inline]
+            fn $serialize(self, value: $ty) -> Result {
+                self.$write(value)
+            }
+        )*
+    };
+}
+
+impl<'a, B: ByteOrder> ser::Serializer for &mut Serializer<'a, B> {
+    type Ok = ();
+    type Error = EError<Error>;
+
+    type SerializeSeq = Self;
+    type SerializeTuple = Self;
+    type SerializeTupleStruct = Self;
+    type SerializeTupleVariant = Self;
+    type SerializeMap = Self;
+    type SerializeStruct = Self;
+    type SerializeStructVariant = Self;
+
+    primitives! {
+        serialize_u8(u8) => write,
+        serialize_u16(u16) => write_unsigned,
+        serialize_u32(u32) => write_unsigned,
+        serialize_u64(u64) => write_unsigned,
+        serialize_u128(u128) => write_unsigned,
+        serialize_i8(i8) => write,
+        serialize_i16(i16) => write_signed,
+        serialize_i32(i32) => write_signed,
+        serialize_i64(i64) => write_signed,
+        serialize_i128(i128) => write_signed,
+        serialize_f32(f32) => write,
+        serialize_f64(f64) => write,
+        serialize_bool(bool) => write,
+        serialize_char(char) => write_char,
+    }
+
+    #[inline]
+    fn serialize_str(self, value: &str) -> Result {
+        self.write_bytes(value.as_bytes())
+    }
+
+    #[inline]
+    fn collect_str<T: fmt::Display + ?Sized>(self, value: &T) -> Result {
+        struct Count(usize);
+
+        impl Write for Count {
+            fn write_str(&mut self, s: &str) -> fmt::Result {
+                self.0 += s.len();
+
+                Ok(())
+            }
+        }
+
+        struct Raw<'s, 'a, B: ByteOrder>(&'s mut Serializer<'a, B>);
+
+        impl<B: ByteOrder> Write for Raw<'_, '_, B> {
+            fn write_str(&mut self, s: &str) -> fmt::Result {
+                if let Some(dst) = self.0.reserve(s.len()) {
+                    dst.copy_from_slice(s.as_bytes());
+                }
+
+                Ok(())
+            }
+        }
+
+        let mut count = Count(0);
+        let _ = write!(count, "{value}");
+
+        self.write_len(count.0)?;
+
+        write!(Raw(self), "{value}").map_err(|_| EError::Fatal {
+            error: Error::custom("a Display implementation returned an error"),
+        })
+    }
+
+    #[inline]
+    fn serialize_bytes(self, value: &[u8]) -> Result {
+        self.write_bytes(value)
+    }
+
+    #[inline]
+    fn serialize_none(self) -> Result {
+        self.write(0u8)
+    }
+
+    #[inline]
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result {
+        self.write(1u8)?;
+        value.serialize(self)
+    }
+
+    #[inline]
+    fn serialize_unit(self) -> Result {
+        Ok(())
+    }
+
+    #[inline]
+    fn serialize_unit_struct(self, _: &'static str) -> Result {
+        Ok(())
+    }
+
+    #[inline]
+    fn serialize_unit_variant(self, _: &'static str, index: u32, _: &'static str) -> Result {
+        self.write_unsigned(index)
+    }
+
+    #[inline]
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(self, _: &'static str, value: &T) -> Result {
+        value.serialize(self)
+    }
+
+    #[inline]
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _: &'static str,
+        index: u32,
+        _: &'static str,
+        value: &T,
+    ) -> Result {
+        self.write_unsigned(index)?;
+        value.serialize(self)
+    }
+
+    #[inline]
+    fn serialize_seq(self, len: Option<usize>) -> Result<Self> {
+        self.write_len(Serializer::<B>::unknown_len(len)?)?;
+
+        Ok(self)
+    }
+
+    #[inline]
+    fn serialize_tuple(self, _: usize) -> Result<Self> {
+        Ok(self)
+    }
+
+    #[inline]
+    fn serialize_tuple_struct(self, _: &'static str, _: usize) -> Result<Self> {
+        Ok(self)
+    }
+
+    #[inline]
+    fn serialize_tuple_variant(
+        self,
+        _: &'static str,
+        index: u32,
+        _: &'static str,
+        _: usize,
+    ) -> Result<Self> {
+        self.write_unsigned(index)?;
+
+        Ok(self)
+    }
+
+    #[inline]
+    fn serialize_map(self, len: Option<usize>) -> Result<Self> {
+        self.write_len(Serializer::<B>::unknown_len(len)?)?;
+
+        Ok(self)
+    }
+
+    #[inline]
+    fn serialize_struct(self, _: &'static str, _: usize) -> Result<Self> {
+        Ok(self)
+    }
+
+    #[inline]
+    fn serialize_struct_variant(
+        self,
+        _: &'static str,
+        index: u32,
+        _: &'static str,
+        _: usize,
+    ) -> Result<Self> {
+        self.write_unsigned(index)?;
+
+        Ok(self)
+    }
+
+    #[inline]
+    fn is_human_readable(&self) -> bool {
+        false
+    }
+}
+
+macro_rules! compound {
+    ($($trait:ident::$method:ident),* $(,)?) => {
+        $(
+            impl<B: ByteOrder> ser::$trait for &mut Serializer<'_, B> {
+                type Ok = ();
+                type Error = EError<Error>;
+
+                #[inline]
+                fn $method<T: Serialize + ?Sized>(&mut self, value: &T) -> Result {
+                    value.serialize(&mut **self)
+                }
+
+                #[inline]
+                fn end(self) -> Result {
+                    Ok(())
+                }
+            }
+        )*
+    };
+}
+
+compound! {
+    SerializeSeq::serialize_element,
+    SerializeTuple::serialize_element,
+    SerializeTupleStruct::serialize_field,
+    SerializeTupleVariant::serialize_field,
+}
+
+impl<B: ByteOrder> ser::SerializeMap for &mut Serializer<'_, B> {
+    type Ok = ();
+    type Error = EError<Error>;
+
+    #[inline]
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result {
+        key.serialize(&mut **self)
+    }
+
+    #[inline]
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result {
+        value.serialize(&mut **self)
+    }
+
+    #[inline]
+    fn end(self) -> Result {
+        Ok(())
+    }
+}
+
+impl<B: ByteOrder> ser::SerializeStruct for &mut Serializer<'_, B> {
+    type Ok = ();
+    type Error = EError<Error>;
+
+    #[inline]
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, _: &'static str, value: &T) -> Result {
+        value.serialize(&mut **self)
+    }
+
+    #[inline]
+    fn end(self) -> Result {
+        Ok(())
+    }
+}
+
+impl<B: ByteOrder> ser::SerializeStructVariant for &mut Serializer<'_, B> {
+    type Ok = ();
+    type Error = EError<Error>;
+
+    #[inline]
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, _: &'static str, value: &T) -> Result {
+        value.serialize(&mut **self)
+    }
+
+    #[inline]
+    fn end(self) -> Result {
+        Ok(())
+    }
+}
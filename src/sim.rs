@@ -0,0 +1,356 @@
+use core::{
+    future::Future,
+    pin::{pin, Pin},
+    task::{Context, Poll, Waker},
+};
+
+use std::{
+    collections::VecDeque,
+    io::{self, ErrorKind},
+    vec::Vec,
+};
+
+/// A single scripted step of a [`SimIo`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Step {
+    /// Yield these bytes.
+    ///
+    /// For reads, the bytes are handed out across as many calls as the
+    /// caller's buffer size requires. For writes, this is ignored.
+    Data(Vec<u8>),
+    /// Accept at most this many bytes in a single write call.
+    ///
+    /// For reads, this is ignored.
+    Limit(usize),
+    /// Return [`Poll::Pending`] once, or [`ErrorKind::WouldBlock`]
+    /// for blocking IO.
+    ///
+    /// The waker is woken immediately so that executors poll again.
+    Pending,
+    /// Fail with an error of this kind once.
+    Error(ErrorKind),
+}
+
+/// A scripted IO object for deterministically testing framing drivers
+/// and codecs without a runtime or sockets.
+///
+/// Reads follow the read script, and once it is exhausted report the end
+/// of the stream. Writes follow the write script, and once it is exhausted
+/// accept everything. All written bytes are recorded.
+///
+/// Implements both [`std::io`] and [`futures_io`] traits.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SimIo {
+    reads: VecDeque<Step>,
+    writes: VecDeque<Step>,
+    written: Vec<u8>,
+    flushes: usize,
+    closed: bool,
+}
+
+impl SimIo {
+    /// Create a new [`SimIo`] with empty scripts.
+    #[inline]
+    #[must_use]
+    pub const fn new() -> SimIo {
+        SimIo {
+            reads: VecDeque::new(),
+            writes: VecDeque::new(),
+            written: Vec::new(),
+            flushes: 0,
+            closed: false,
+        }
+    }
+
+    /// Script a read that yields `bytes`.
+    #[inline]
+    #[must_use]
+    pub fn then_read(mut self, bytes: impl AsRef<[u8]>) -> SimIo {
+        self.reads.push_back(Step::Data(bytes.as_ref().into()));
+        self
+    }
+
+    /// Script `bytes` to be read one byte per call.
+    #[inline]
+    #[must_use]
+    pub fn then_read_bytewise(mut self, bytes: impl AsRef<[u8]>) -> SimIo {
+        self.reads
+            .extend(bytes.as_ref().iter().map(|&b| Step::Data([b].into())));
+        self
+    }
+
+    /// Script a read that is not ready.
+    #[inline]
+    #[must_use]
+    pub fn then_read_pending(mut self) -> SimIo {
+        self.reads.push_back(Step::Pending);
+        self
+    }
+
+    /// Script a read that fails.
+    #[inline]
+    #[must_use]
+    pub fn then_read_error(mut self, kind: ErrorKind) -> SimIo {
+        self.reads.push_back(Step::Error(kind));
+        self
+    }
+
+    /// Script a write that accepts at most `limit` bytes.
+    #[inline]
+    #[must_use]
+    pub fn then_write_limit(mut self, limit: usize) -> SimIo {
+        self.writes.push_back(Step::Limit(limit));
+        self
+    }
+
+    /// Script a write that is not ready.
+    #[inline]
+    #[must_use]
+    pub fn then_write_pending(mut self) -> SimIo {
+        self.writes.push_back(Step::Pending);
+        self
+    }
+
+    /// Script a write that fails.
+    #[inline]
+    #[must_use]
+    pub fn then_write_error(mut self, kind: ErrorKind) -> SimIo {
+        self.writes.push_back(Step::Error(kind));
+        self
+    }
+
+    /// Push an arbitrary step onto the read script.
+    #[inline]
+    pub fn push_read(&mut self, step: Step) {
+        self.reads.push_back(step);
+    }
+
+    /// Push an arbitrary step onto the write script.
+    #[inline]
+    pub fn push_write(&mut self, step: Step) {
+        self.writes.push_back(step);
+    }
+
+    /// Returns the bytes written so far.
+    #[inline]
+    #[must_use]
+    pub fn written(&self) -> &[u8] {
+        &self.written
+    }
+
+    /// Returns the amount of successful flushes.
+    #[inline]
+    #[must_use]
+    pub const fn flushes(&self) -> usize {
+        self.flushes
+    }
+
+    /// Returns whether the writing half was closed.
+    #[inline]
+    #[must_use]
+    pub const fn is_closed(&self) -> bool {
+        self.closed
+    }
+
+    /// Returns whether every scripted read has been consumed.
+    #[inline]
+    #[must_use]
+    pub fn is_read_done(&self) -> bool {
+        self.reads.is_empty()
+    }
+
+    fn poll_read_inner(
+        &mut self,
+        cx: Option<&mut Context<'_>>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        loop {
+            match self.reads.front_mut() {
+                None => return Poll::Ready(Ok(0)),
+                Some(Step::Data(bytes)) if bytes.is_empty() => {
+                    self.reads.pop_front();
+                }
+                Some(Step::Data(bytes)) => {
+                    let n = bytes.len().min(buf.len());
+
+                    buf[..n].copy_from_slice(&bytes[..n]);
+                    bytes.drain(..n);
+
+                    if bytes.is_empty() {
+                        self.reads.pop_front();
+                    }
+
+                    return Poll::Ready(Ok(n));
+                }
+                Some(Step::Limit(..)) => {
+                    self.reads.pop_front();
+                }
+                Some(Step::Pending) => {
+                    self.reads.pop_front();
+
+                    return pending(cx);
+                }
+                Some(&mut Step::Error(kind)) => {
+                    self.reads.pop_front();
+
+                    return Poll::Ready(Err(kind.into()));
+                }
+            }
+        }
+    }
+
+    fn poll_write_inner(
+        &mut self,
+        cx: Option<&mut Context<'_>>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        loop {
+            match self.writes.pop_front() {
+                None => {
+                    self.written.extend_from_slice(buf);
+
+                    return Poll::Ready(Ok(buf.len()));
+                }
+                Some(Step::Limit(limit)) => {
+                    let n = limit.min(buf.len());
+
+                    self.written.extend_from_slice(&buf[..n]);
+
+                    return Poll::Ready(Ok(n));
+                }
+                Some(Step::Data(..)) => {}
+                Some(Step::Pending) => return pending(cx),
+                Some(Step::Error(kind)) => return Poll::Ready(Err(kind.into())),
+            }
+        }
+    }
+
+    fn poll_flush_inner(&mut self, cx: Option<&mut Context<'_>>) -> Poll<io::Result<()>> {
+        match self.writes.front() {
+            Some(Step::Pending) => {
+                self.writes.pop_front();
+
+                pending(cx)
+            }
+            Some(&Step::Error(kind)) => {
+                self.writes.pop_front();
+
+                Poll::Ready(Err(kind.into()))
+            }
+            _ => {
+                self.flushes += 1;
+
+                Poll::Ready(Ok(()))
+            }
+        }
+    }
+}
+
+/// Wake the task and return [`Poll::Pending`], or
+/// report [`ErrorKind::WouldBlock`] when there is no task.
+#[inline]
+fn pending<T>(cx: Option<&mut Context<'_>>) -> Poll<io::Result<T>> {
+    match cx {
+        Some(cx) => {
+            cx.waker().wake_by_ref();
+
+            Poll::Pending
+        }
+        None => Poll::Ready(Err(ErrorKind::WouldBlock.into())),
+    }
+}
+
+#[inline]
+fn ready<T>(poll: Poll<io::Result<T>>) -> io::Result<T> {
+    match poll {
+        Poll::Ready(result) => result,
+        Poll::Pending => Err(ErrorKind::WouldBlock.into()),
+    }
+}
+
+impl io::Read for SimIo {
+    #[inline]
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        ready(self.poll_read_inner(None, buf))
+    }
+}
+
+impl io::Write for SimIo {
+    #[inline]
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        ready(self.poll_write_inner(None, buf))
+    }
+
+    #[inline]
+    fn flush(&mut self) -> io::Result<()> {
+        ready(self.poll_flush_inner(None))
+    }
+}
+
+impl futures_io::AsyncRead for SimIo {
+    #[inline]
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        self.get_mut().poll_read_inner(Some(cx), buf)
+    }
+}
+
+impl futures_io::AsyncWrite for SimIo {
+    #[inline]
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        self.get_mut().poll_write_inner(Some(cx), buf)
+    }
+
+    #[inline]
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.get_mut().poll_flush_inner(Some(cx))
+    }
+
+    #[inline]
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+
+        match this.poll_flush_inner(Some(cx)) {
+            Poll::Ready(Ok(())) => {
+                this.closed = true;
+
+                Poll::Ready(Ok(()))
+            }
+            poll => poll,
+        }
+    }
+}
+
+/// Poll a future exactly once with a no-op waker.
+///
+/// Dropping the future afterwards simulates cancellation at this
+/// point, which is useful for verifying cancel safety.
+#[inline]
+pub fn poll_once<F: Future + Unpin>(future: &mut F) -> Poll<F::Output> {
+    Pin::new(future).poll(&mut Context::from_waker(Waker::noop()))
+}
+
+/// Drive a future to completion on the current thread, returning its
+/// output along with how many times it returned [`Poll::Pending`].
+///
+/// This spins rather than parking, so every pending future must arrange
+/// to be woken, as [`SimIo`] does.
+pub fn block_on<F: Future>(future: F) -> (F::Output, usize) {
+    let mut future = pin!(future);
+    let mut cx = Context::from_waker(Waker::noop());
+    let mut pending = 0;
+
+    loop {
+        match future.as_mut().poll(&mut cx) {
+            Poll::Ready(output) => return (output, pending),
+            Poll::Pending => pending += 1,
+        }
+    }
+}
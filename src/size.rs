@@ -1,8 +1,12 @@
-use core::num::NonZeroUsize;
+use core::{iter::Sum, num::NonZeroUsize, ops::Add};
 
-/// Represents a **nonzero** amount of bytes
-/// that is required to perform some action.
+/// Represents an amount of bytes that is required to perform some action.
+///
+/// The amount is usually nonzero, but may be exactly [`Size::Zero`] for
+/// actions that do not need any bytes at all.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[must_use]
 pub enum Size {
     /// The amount of bytes is not known.
@@ -11,6 +15,8 @@ pub enum Size {
     /// minimum nonzero amount is.
     #[default]
     Unknown,
+    /// Exactly no bytes are required.
+    Zero,
     /// The amount of bytes required is exactly known.
     Known(NonZeroUsize),
 }
@@ -26,43 +32,54 @@ impl Size {
     ///
     /// # Returns
     ///
-    /// - `bytes == 0` becomes [`Size::Unknown`].
+    /// - `bytes == 0` becomes [`Size::Zero`].
     /// - `bytes > 0` becomes [`Size::Known`].
     #[inline]
     pub const fn new(bytes: usize) -> Size {
         match NonZeroUsize::new(bytes) {
             Some(bytes) => Size::Known(bytes),
-            None => Size::Unknown,
+            None => Size::Zero,
         }
     }
 
-    /// Gets the known amount, if it is known.
+    /// Gets the known nonzero amount, if it is known and nonzero.
     #[inline]
     pub const fn get(self) -> Option<NonZeroUsize> {
         match self {
-            Size::Unknown => None,
+            Size::Unknown | Size::Zero => None,
             Size::Known(bytes) => Some(bytes),
         }
     }
 
-    /// Gets the known amount, or return the provided default.
+    /// Gets the known amount, including zero, if it is known.
+    #[inline]
+    #[must_use]
+    pub const fn as_usize(self) -> Option<usize> {
+        match self {
+            Size::Unknown => None,
+            Size::Zero => Some(0),
+            Size::Known(bytes) => Some(bytes.get()),
+        }
+    }
+
+    /// Gets the known nonzero amount, or return the provided default.
     #[inline]
     #[must_use]
     pub const fn get_or(self, default: NonZeroUsize) -> NonZeroUsize {
         match self {
-            Size::Unknown => default,
+            Size::Unknown | Size::Zero => default,
             Size::Known(bytes) => bytes,
         }
     }
 
-    /// Gets the known amount or returns a default of `1`.
+    /// Gets the known nonzero amount or returns a default of `1`.
     #[inline]
     #[must_use]
     pub const fn get_or_one(self) -> NonZeroUsize {
         self.get_or(NonZeroUsize::MIN)
     }
 
-    /// Gets the known amount, or return a default computed from
+    /// Gets the known nonzero amount, or return a default computed from
     /// a closure.
     #[inline]
     #[must_use]
@@ -71,7 +88,7 @@ impl Size {
         F: FnOnce() -> NonZeroUsize,
     {
         match self {
-            Size::Unknown => f(),
+            Size::Unknown | Size::Zero => f(),
             Size::Known(bytes) => bytes,
         }
     }
@@ -80,7 +97,14 @@ impl Size {
     #[inline]
     #[must_use]
     pub const fn is_known(self) -> bool {
-        matches!(self, Self::Known(..))
+        matches!(self, Self::Zero | Self::Known(..))
+    }
+
+    /// Returns whether exactly no bytes are needed.
+    #[inline]
+    #[must_use]
+    pub const fn is_zero(self) -> bool {
+        matches!(self, Self::Zero)
     }
 
     /// Returns whether the amount needed is unknown.
@@ -90,7 +114,9 @@ impl Size {
         matches!(self, Self::Unknown)
     }
 
-    /// Maps the known needed amount by applying a closure to it.
+    /// Maps the known nonzero amount by applying a closure to it.
+    ///
+    /// [`Size::Unknown`] and [`Size::Zero`] are left as they are.
     #[inline]
     pub fn map<F>(self, f: F) -> Size
     where
@@ -98,7 +124,120 @@ impl Size {
     {
         match self {
             Size::Unknown => Size::Unknown,
+            Size::Zero => Size::Zero,
             Size::Known(bytes) => Size::new(f(bytes)),
         }
     }
+
+    /// Adds two amounts, returning `None` on overflow.
+    ///
+    /// If either amount is unknown, so is the sum.
+    #[inline]
+    #[must_use]
+    pub const fn checked_add(self, other: Size) -> Option<Size> {
+        match (self.as_usize(), other.as_usize()) {
+            (Some(a), Some(b)) => match a.checked_add(b) {
+                Some(bytes) => Some(Size::new(bytes)),
+                None => None,
+            },
+            _ => Some(Size::Unknown),
+        }
+    }
+
+    /// Adds two amounts, saturating at [`Size::MAX`].
+    ///
+    /// If either amount is unknown, so is the sum.
+    #[inline]
+    pub const fn saturating_add(self, other: Size) -> Size {
+        match self.checked_add(other) {
+            Some(size) => size,
+            None => Size::MAX,
+        }
+    }
+
+    /// Returns the larger of two amounts.
+    ///
+    /// An unknown amount is ignored in favor of a known one, as the result
+    /// is at least as large as the known amount either way.
+    #[inline]
+    pub const fn max(self, other: Size) -> Size {
+        match (self.as_usize(), other.as_usize()) {
+            (Some(a), Some(b)) if a < b => other,
+            (Some(_), _) => self,
+            (None, _) => other,
+        }
+    }
+
+    /// Returns the smaller of two amounts.
+    ///
+    /// If either amount is unknown, so is the result.
+    #[inline]
+    pub const fn min(self, other: Size) -> Size {
+        match (self.as_usize(), other.as_usize()) {
+            (Some(a), Some(b)) if a > b => other,
+            (Some(_), Some(_)) => self,
+            _ => Size::Unknown,
+        }
+    }
+}
+
+impl Add for Size {
+    type Output = Size;
+
+    /// Adds two amounts with [`Size::saturating_add`].
+    #[inline]
+    fn add(self, other: Size) -> Size {
+        self.saturating_add(other)
+    }
+}
+
+impl Sum for Size {
+    /// Sums amounts with [`Size::saturating_add`], starting from
+    /// [`Size::Zero`].
+    #[inline]
+    fn sum<I: Iterator<Item = Size>>(iter: I) -> Size {
+        iter.fold(Size::Zero, Size::saturating_add)
+    }
+}
+
+/// Represents the range of the amount of bytes that is required to perform
+/// some action.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct SizeHint {
+    /// The lower bound of the amount.
+    pub min: Size,
+    /// The upper bound of the amount.
+    pub max: Size,
+}
+
+impl SizeHint {
+    /// Create a new [`SizeHint`] from its bounds.
+    #[inline]
+    #[must_use]
+    pub const fn new(min: Size, max: Size) -> SizeHint {
+        SizeHint { min, max }
+    }
+
+    /// Create a new [`SizeHint`] for exactly `size`.
+    #[inline]
+    #[must_use]
+    pub const fn exact(size: Size) -> SizeHint {
+        SizeHint::new(size, size)
+    }
+
+    /// Create a new [`SizeHint`] for at least `min`, without an upper bound.
+    #[inline]
+    #[must_use]
+    pub const fn at_least(min: Size) -> SizeHint {
+        SizeHint::new(min, Size::Unknown)
+    }
+
+    /// Returns the amount if both bounds are known and equal.
+    #[inline]
+    #[must_use]
+    pub fn get_exact(self) -> Option<Size> {
+        (self.min.is_known() && self.min == self.max).then_some(self.min)
+    }
 }
@@ -0,0 +1,568 @@
+use core::{fmt, str::Utf8Error};
+
+use crate::binary::{Bytes, BytesError, Fixed};
+use crate::decoder::{Decoder, Error as DError, Result as DResult};
+use crate::encoder::{Encoder, Error as EError, Result as EResult};
+use crate::{Size, SizeHint};
+
+/// A [`Decoder`] that is capable of reading a UTF-8 [`prim@str`] whose
+/// length in bytes is read by another decoder.
+///
+/// The string borrows from the source. It is also an [`Encoder`] when the
+/// length can be encoded as a [`prim@u64`].
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Str<L> {
+    /// The decoder for the length of the string.
+    pub len: L,
+    /// The maximum length of the string in bytes.
+    ///
+    /// Longer strings are a fatal error rather than incomplete.
+    pub max_len: usize,
+}
+
+impl<L> Str<L> {
+    /// Create a new decoder for a [`prim@str`].
+    #[inline]
+    #[must_use]
+    pub const fn new(len: L) -> Str<L> {
+        Str {
+            len,
+            max_len: usize::MAX,
+        }
+    }
+
+    /// Set the maximum length of the string in bytes.
+    #[inline]
+    #[must_use]
+    pub const fn with_max_len(mut self, max_len: usize) -> Str<L> {
+        self.max_len = max_len;
+        self
+    }
+}
+
+impl Str<Fixed> {
+    /// Create a new decoder for a [`prim@str`] of exactly `len` bytes.
+    #[inline]
+    #[must_use]
+    pub const fn fixed(len: usize) -> Str<Fixed> {
+        Str::new(Fixed(len))
+    }
+}
+
+impl<L: Default> Default for Str<L> {
+    #[inline]
+    fn default() -> Self {
+        Str::new(L::default())
+    }
+}
+
+impl<L> Decoder for Str<L>
+where
+    L: Decoder,
+    for<'a> L::Item<'a>: TryInto<usize>,
+{
+    type Item<'src> = &'src str;
+    type Error = StrError<L::Error>;
+
+    #[inline]
+    fn hint(&self) -> Size {
+        self.len.hint()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> SizeHint {
+        let len = self.len.size_hint();
+
+        SizeHint::new(len.min, len.max + Size::new(self.max_len))
+    }
+
+    #[inline]
+    fn decode<'s>(&mut self, src: &mut &'s [u8]) -> DResult<'s, Self> {
+        let mut rest = *src;
+
+        let bytes = Bytes::new(&mut self.len)
+            .with_max_len(self.max_len)
+            .decode(&mut rest)
+            .map_err(|e| e.map(StrError::from))?;
+
+        match core::str::from_utf8(bytes) {
+            Ok(s) => {
+                *src = rest;
+                Ok(s)
+            }
+            Err(error) => Err(DError::Fatal {
+                error: StrError::Utf8(error),
+            }),
+        }
+    }
+}
+
+impl<L: Encoder<u64>> Encoder<str> for Str<L> {
+    type Error = StrError<L::Error>;
+
+    #[inline]
+    fn encode_hint(&self, item: &str) -> Size {
+        self.len.encode_hint(&(item.len() as u64)) + Size::new(item.len())
+    }
+
+    #[inline]
+    fn encode(&mut self, item: &str, dst: &mut &mut [u8]) -> EResult<Self::Error> {
+        Bytes::new(&mut self.len)
+            .with_max_len(self.max_len)
+            .encode(item.as_bytes(), dst)
+            .map_err(|e| e.map(StrError::from))
+    }
+}
+
+/// A [`Decoder`] that is capable of reading an owned UTF-8
+/// [`String`](alloc::string::String) whose length in bytes is read by
+/// another decoder.
+#[cfg(feature = "alloc")]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct String<L> {
+    /// The decoder for the length of the string.
+    pub len: L,
+    /// The maximum length of the string in bytes.
+    ///
+    /// Longer strings are a fatal error rather than incomplete.
+    pub max_len: usize,
+}
+
+#[cfg(feature = "alloc")]
+impl<L> String<L> {
+    /// Create a new decoder for a [`String`](alloc::string::String).
+    #[inline]
+    #[must_use]
+    pub const fn new(len: L) -> String<L> {
+        String {
+            len,
+            max_len: usize::MAX,
+        }
+    }
+
+    /// Set the maximum length of the string in bytes.
+    #[inline]
+    #[must_use]
+    pub const fn with_max_len(mut self, max_len: usize) -> String<L> {
+        self.max_len = max_len;
+        self
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<L: Default> Default for String<L> {
+    #[inline]
+    fn default() -> Self {
+        String::new(L::default())
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<L> Decoder for String<L>
+where
+    L: Decoder,
+    for<'a> L::Item<'a>: TryInto<usize>,
+{
+    type Item<'src> = alloc::string::String;
+    type Error = StrError<L::Error>;
+
+    #[inline]
+    fn hint(&self) -> Size {
+        self.len.hint()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> SizeHint {
+        let len = self.len.size_hint();
+
+        SizeHint::new(len.min, len.max + Size::new(self.max_len))
+    }
+
+    #[inline]
+    fn decode<'s>(&mut self, src: &mut &'s [u8]) -> DResult<'s, Self> {
+        Str::new(&mut self.len)
+            .with_max_len(self.max_len)
+            .decode(src)
+            .map(Into::into)
+    }
+}
+
+/// A [`Decoder`] that is capable of reading a UTF-8 [`prim@str`] whose
+/// length in bytes is read by another decoder, replacing invalid sequences
+/// with [`U+FFFD`](core::char::REPLACEMENT_CHARACTER).
+///
+/// The string borrows from the source unless it had to be repaired.
+#[cfg(feature = "alloc")]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Lossy<L> {
+    /// The decoder for the length of the string.
+    pub len: L,
+    /// The maximum length of the string in bytes.
+    ///
+    /// Longer strings are a fatal error rather than incomplete.
+    pub max_len: usize,
+}
+
+#[cfg(feature = "alloc")]
+impl<L> Lossy<L> {
+    /// Create a new lossy decoder for a [`prim@str`].
+    #[inline]
+    #[must_use]
+    pub const fn new(len: L) -> Lossy<L> {
+        Lossy {
+            len,
+            max_len: usize::MAX,
+        }
+    }
+
+    /// Set the maximum length of the string in bytes.
+    #[inline]
+    #[must_use]
+    pub const fn with_max_len(mut self, max_len: usize) -> Lossy<L> {
+        self.max_len = max_len;
+        self
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl Lossy<Fixed> {
+    /// Create a new lossy decoder for a [`prim@str`] of exactly `len` bytes.
+    #[inline]
+    #[must_use]
+    pub const fn fixed(len: usize) -> Lossy<Fixed> {
+        Lossy::new(Fixed(len))
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<L: Default> Default for Lossy<L> {
+    #[inline]
+    fn default() -> Self {
+        Lossy::new(L::default())
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<L> Decoder for Lossy<L>
+where
+    L: Decoder,
+    for<'a> L::Item<'a>: TryInto<usize>,
+{
+    type Item<'src> = alloc::borrow::Cow<'src, str>;
+    type Error = BytesError<L::Error>;
+
+    #[inline]
+    fn hint(&self) -> Size {
+        self.len.hint()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> SizeHint {
+        let len = self.len.size_hint();
+
+        SizeHint::new(len.min, len.max + Size::new(self.max_len))
+    }
+
+    #[inline]
+    fn decode<'s>(&mut self, src: &mut &'s [u8]) -> DResult<'s, Self> {
+        Bytes::new(&mut self.len)
+            .with_max_len(self.max_len)
+            .decode(src)
+            .map(alloc::string::String::from_utf8_lossy)
+    }
+}
+
+/// Type for errors that may occur while reading a string.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StrError<E> {
+    /// The length could not be read.
+    Length(E),
+    /// The length is too large.
+    TooLong,
+    /// The string is not valid UTF-8.
+    Utf8(Utf8Error),
+}
+
+impl<E> StrError<E> {
+    /// Returns the offset into the string of the invalid UTF-8, if any.
+    #[inline]
+    #[must_use]
+    pub fn utf8_offset(&self) -> Option<usize> {
+        match self {
+            Self::Utf8(error) => Some(error.valid_up_to()),
+            _ => None,
+        }
+    }
+}
+
+impl<E> From<BytesError<E>> for StrError<E> {
+    #[inline]
+    fn from(error: BytesError<E>) -> Self {
+        match error {
+            BytesError::Length(error) => Self::Length(error),
+            BytesError::TooLong => Self::TooLong,
+        }
+    }
+}
+
+impl<E: fmt::Display> fmt::Display for StrError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Length(error) => write!(f, "invalid length: {error}"),
+            Self::TooLong => f.write_str("length too large"),
+            Self::Utf8(error) => write!(f, "invalid utf-8 at byte {}", error.valid_up_to()),
+        }
+    }
+}
+
+impl<E: fmt::Display + fmt::Debug> core::error::Error for StrError<E> {}
+
+#[cfg(feature = "defmt")]
+impl<E: defmt::Format> defmt::Format for StrError<E> {
+    fn format(&self, f: defmt::Formatter<'_>) {
+        match self {
+            Self::Length(error) => defmt::write!(f, "invalid length: {}", error),
+            Self::TooLong => defmt::write!(f, "length too large"),
+            Self::Utf8(error) => {
+                defmt::write!(f, "invalid utf-8 at byte {=usize}", error.valid_up_to())
+            }
+        }
+    }
+}
+
+/// A [`Decoder`] and [`Encoder`] for a UTF-8 [`prim@str`] in a
+/// fixed-width field, padded at the end with a filler byte.
+///
+/// Decoding trims all trailing filler bytes, and encoding pads the
+/// string back out to the width of the field.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Padded {
+    /// The width of the field in bytes.
+    pub width: usize,
+    /// The filler byte.
+    pub pad: u8,
+}
+
+impl Padded {
+    /// Create a new decoder for a field of `width` bytes padded with `pad`.
+    #[inline]
+    #[must_use]
+    pub const fn new(width: usize, pad: u8) -> Padded {
+        Padded { width, pad }
+    }
+
+    /// Create a new decoder for a field of `width` bytes padded with NUL.
+    #[inline]
+    #[must_use]
+    pub const fn nul(width: usize) -> Padded {
+        Padded::new(width, b'\0')
+    }
+
+    /// Create a new decoder for a field of `width` bytes padded with spaces.
+    #[inline]
+    #[must_use]
+    pub const fn space(width: usize) -> Padded {
+        Padded::new(width, b' ')
+    }
+}
+
+impl Decoder for Padded {
+    type Item<'src> = &'src str;
+    type Error = Utf8Error;
+
+    #[inline]
+    fn hint(&self) -> Size {
+        Size::new(self.width)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> SizeHint {
+        SizeHint::exact(self.hint())
+    }
+
+    #[inline]
+    fn decode<'s>(&mut self, src: &mut &'s [u8]) -> DResult<'s, Self> {
+        let Some((bytes, rest)) = src.split_at_checked(self.width) else {
+            return Err(DError::Incomplete {
+                needed: Size::new(self.width - src.len()),
+            });
+        };
+
+        let len = bytes
+            .iter()
+            .rposition(|&byte| byte != self.pad)
+            .map_or(0, |i| i + 1);
+
+        match core::str::from_utf8(&bytes[..len]) {
+            Ok(s) => {
+                *src = rest;
+                Ok(s)
+            }
+            Err(error) => Err(DError::Fatal { error }),
+        }
+    }
+}
+
+impl Encoder<str> for Padded {
+    type Error = PaddedError;
+
+    #[inline]
+    fn encode_hint(&self, _: &str) -> Size {
+        Size::new(self.width)
+    }
+
+    #[inline]
+    fn encode(&mut self, item: &str, dst: &mut &mut [u8]) -> EResult<Self::Error> {
+        if item.len() > self.width {
+            return Err(EError::Fatal {
+                error: PaddedError(()),
+            });
+        }
+
+        if dst.len() < self.width {
+            return Err(EError::Full {
+                needed: Size::new(self.width - dst.len()),
+            });
+        }
+
+        let (bytes, rest) = core::mem::take(dst).split_at_mut(self.width);
+        let (head, tail) = bytes.split_at_mut(item.len());
+
+        head.copy_from_slice(item.as_bytes());
+        tail.fill(self.pad);
+
+        *dst = rest;
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct PaddedError(());
+
+impl fmt::Display for PaddedError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("string too long for field")
+    }
+}
+
+impl core::error::Error for PaddedError {}
+
+/// A [`Decoder`] for a stream of UTF-8 text, yielding the longest valid
+/// [`prim@str`] available in the source each time.
+///
+/// A partial sequence at the end of the source is left there, so that it
+/// is carried into the next call along with the bytes that complete it.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Utf8Stream {
+    position: u64,
+}
+
+impl Utf8Stream {
+    /// Create a new decoder for a stream of UTF-8 text.
+    #[inline]
+    #[must_use]
+    pub const fn new() -> Utf8Stream {
+        Utf8Stream { position: 0 }
+    }
+
+    /// Returns the amount of bytes decoded so far.
+    #[inline]
+    #[must_use]
+    pub const fn position(&self) -> u64 {
+        self.position
+    }
+
+    /// Returns the length of a sequence starting with `byte`.
+    #[inline]
+    const fn sequence_len(byte: u8) -> usize {
+        match byte {
+            0xC0..=0xDF => 2,
+            0xE0..=0xEF => 3,
+            _ => 4,
+        }
+    }
+
+    fn next<'s>(&mut self, src: &mut &'s [u8], eof: bool) -> DResult<'s, Self> {
+        let (text, error) = match core::str::from_utf8(src) {
+            Ok(text) => (text, None),
+            Err(error) => (
+                core::str::from_utf8(&src[..error.valid_up_to()]).unwrap_or_default(),
+                Some(error),
+            ),
+        };
+
+        if !text.is_empty() {
+            *src = &src[text.len()..];
+            self.position += text.len() as u64;
+
+            return Ok(text);
+        }
+
+        match (src.first(), error) {
+            (None, _) if eof => Err(DError::Eof),
+            (None, _) => Err(DError::Incomplete {
+                needed: Size::new(1),
+            }),
+            (Some(&byte), Some(error)) if error.error_len().is_none() && !eof => {
+                Err(DError::Incomplete {
+                    needed: Size::new(Self::sequence_len(byte) - src.len()),
+                })
+            }
+            _ => Err(DError::Fatal {
+                error: Utf8StreamError {
+                    offset: self.position,
+                },
+            }),
+        }
+    }
+}
+
+impl Decoder for Utf8Stream {
+    type Item<'src> = &'src str;
+    type Error = Utf8StreamError;
+
+    #[inline]
+    fn hint(&self) -> Size {
+        Size::new(1)
+    }
+
+    #[inline]
+    fn decode<'s>(&mut self, src: &mut &'s [u8]) -> DResult<'s, Self> {
+        self.next(src, false)
+    }
+
+    #[inline]
+    fn decode_eof<'s>(&mut self, src: &mut &'s [u8]) -> DResult<'s, Self> {
+        self.next(src, true)
+    }
+}
+
+/// Type for errors that may occur while reading a stream of UTF-8 text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Utf8StreamError {
+    offset: u64,
+}
+
+impl Utf8StreamError {
+    /// Returns the offset into the stream of the invalid UTF-8.
+    #[inline]
+    #[must_use]
+    pub const fn offset(&self) -> u64 {
+        self.offset
+    }
+}
+
+impl fmt::Display for Utf8StreamError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid utf-8 at byte {} of stream", self.offset)
+    }
+}
+
+impl core::error::Error for Utf8StreamError {}
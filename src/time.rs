@@ -0,0 +1,415 @@
+use core::{fmt, time::Duration};
+
+use crate::decoder::{Decoder, Error as DError, Result as DResult};
+use crate::encoder::{Encoder, Error as EError, Result as EResult};
+use crate::endian::{BigEndian, ByteOrder, Endian, LittleEndian};
+use crate::{Size, SizeHint};
+
+/// The unit of a timestamp.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Unit {
+    /// Seconds.
+    Seconds,
+    /// Milliseconds.
+    Millis,
+    /// Microseconds.
+    Micros,
+    /// Nanoseconds.
+    Nanos,
+}
+
+impl Unit {
+    /// Returns the amount of nanoseconds in one of this unit.
+    #[inline]
+    #[must_use]
+    pub const fn nanos(self) -> u64 {
+        match self {
+            Self::Seconds => 1_000_000_000,
+            Self::Millis => 1_000_000,
+            Self::Micros => 1_000,
+            Self::Nanos => 1,
+        }
+    }
+
+    /// Returns the duration of `value` of this unit.
+    #[inline]
+    #[must_use]
+    pub const fn duration(self, value: u64) -> Duration {
+        match self {
+            Self::Seconds => Duration::from_secs(value),
+            Self::Millis => Duration::from_millis(value),
+            Self::Micros => Duration::from_micros(value),
+            Self::Nanos => Duration::from_nanos(value),
+        }
+    }
+
+    /// Returns the amount of this unit in `duration`, rounded down, if it
+    /// fits in a [`prim@u64`].
+    #[inline]
+    #[must_use]
+    pub const fn count(self, duration: Duration) -> Option<u64> {
+        let count = duration.as_nanos() / self.nanos() as u128;
+
+        if count > u64::MAX as u128 {
+            None
+        } else {
+            Some(count as u64)
+        }
+    }
+}
+
+/// A point in time, as the time elapsed since the Unix epoch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct UnixTime(pub Duration);
+
+impl UnixTime {
+    /// The Unix epoch, `1970-01-01T00:00:00Z`.
+    pub const EPOCH: UnixTime = UnixTime(Duration::ZERO);
+
+    /// Create a new [`UnixTime`] of `since_epoch` after the Unix epoch.
+    #[inline]
+    #[must_use]
+    pub const fn new(since_epoch: Duration) -> UnixTime {
+        UnixTime(since_epoch)
+    }
+
+    /// Returns the time elapsed since the Unix epoch.
+    #[inline]
+    #[must_use]
+    pub const fn since_epoch(&self) -> Duration {
+        self.0
+    }
+}
+
+#[cfg(feature = "std")]
+impl TryFrom<UnixTime> for std::time::SystemTime {
+    type Error = SystemTimeError;
+
+    /// Fails for times the system clock cannot represent.
+    #[inline]
+    fn try_from(time: UnixTime) -> Result<Self, Self::Error> {
+        std::time::UNIX_EPOCH
+            .checked_add(time.0)
+            .ok_or(SystemTimeError(()))
+    }
+}
+
+#[cfg(feature = "std")]
+impl TryFrom<std::time::SystemTime> for UnixTime {
+    type Error = std::time::SystemTimeError;
+
+    /// Fails for times before the Unix epoch.
+    #[inline]
+    fn try_from(time: std::time::SystemTime) -> Result<Self, Self::Error> {
+        time.duration_since(std::time::UNIX_EPOCH).map(UnixTime)
+    }
+}
+
+macro_rules! define {
+    ($(
+        $(#[$attr:meta])*
+        $vis:vis struct $name:ident<$ty:ident> {}
+    )*) => {
+        $(
+            $(#[$attr])*
+            #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+            #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+            $vis struct $name<B: ByteOrder = BigEndian> {
+                /// The byte order of the timestamp.
+                pub byte_order: B,
+                /// The unit of the timestamp.
+                pub unit: Unit,
+            }
+
+            impl<B: ByteOrder> $name<B> {
+                const SIZE: usize = ::core::mem::size_of::<$ty>();
+
+                /// Create a new decoder for a timestamp in `unit`s.
+                #[inline]
+                #[must_use]
+                pub const fn new(byte_order: B, unit: Unit) -> $name<B> {
+                    $name { byte_order, unit }
+                }
+            }
+
+            impl<B: ByteOrder> Decoder for $name<B> {
+                type Item<'src> = UnixTime;
+                type Error = ::core::convert::Infallible;
+
+                #[inline]
+                fn hint(&self) -> Size {
+                    Size::new(Self::SIZE)
+                }
+
+                #[inline]
+                fn size_hint(&self) -> SizeHint {
+                    SizeHint::exact(self.hint())
+                }
+
+                #[inline]
+                fn decode<'s>(&mut self, src: &mut &'s [u8]) -> DResult<'s, Self> {
+                    let Some((&bytes, rest)) = src.split_first_chunk() else {
+                        return Err(DError::Incomplete {
+                            needed: Size::new(Self::SIZE - src.len()),
+                        });
+                    };
+
+                    let value = match self.byte_order.endian() {
+                        Endian::Little => $ty::from_le_bytes(bytes),
+                        Endian::Big => $ty::from_be_bytes(bytes),
+                    };
+
+                    *src = rest;
+
+                    Ok(UnixTime(self.unit.duration(value.into())))
+                }
+            }
+
+            impl<B: ByteOrder> Encoder<UnixTime> for $name<B> {
+                type Error = TimestampError;
+
+                #[inline]
+                fn encode_hint(&self, _: &UnixTime) -> Size {
+                    Size::new(Self::SIZE)
+                }
+
+                fn encode(&mut self, item: &UnixTime, dst: &mut &mut [u8]) -> EResult<Self::Error> {
+                    let Some(value) = self.unit.count(item.0).and_then(|n| $ty::try_from(n).ok())
+                    else {
+                        return Err(EError::Fatal {
+                            error: TimestampError(()),
+                        });
+                    };
+
+                    if dst.len() < Self::SIZE {
+                        return Err(EError::Full {
+                            needed: Size::new(Self::SIZE - dst.len()),
+                        });
+                    }
+
+                    let (bytes, rest) = core::mem::take(dst).split_at_mut(Self::SIZE);
+
+                    bytes.copy_from_slice(&match self.byte_order.endian() {
+                        Endian::Little => value.to_le_bytes(),
+                        Endian::Big => value.to_be_bytes(),
+                    });
+
+                    *dst = rest;
+
+                    Ok(())
+                }
+            }
+        )*
+    };
+}
+
+define! {
+    /// A [`Decoder`] and [`Encoder`] for a 32-bit unsigned Unix timestamp.
+    ///
+    /// Encoding rounds down to the unit, and times that do not fit are a
+    /// fatal error.
+    pub struct Unix32<u32> {}
+
+    /// A [`Decoder`] and [`Encoder`] for a 64-bit unsigned Unix timestamp.
+    ///
+    /// Encoding rounds down to the unit, and times that do not fit are a
+    /// fatal error.
+    pub struct Unix64<u64> {}
+}
+
+/// A Windows `FILETIME`, as the amount of 100 nanosecond intervals since
+/// `1601-01-01T00:00:00Z`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FileTime(pub u64);
+
+impl FileTime {
+    /// The Unix epoch, `1970-01-01T00:00:00Z`.
+    pub const UNIX_EPOCH: FileTime = FileTime(116_444_736_000_000_000);
+
+    /// The amount of nanoseconds in an interval.
+    const INTERVAL: u64 = 100;
+
+    /// Returns the time elapsed since `1601-01-01T00:00:00Z`.
+    #[inline]
+    #[must_use]
+    pub const fn since_1601(&self) -> Duration {
+        let nanos = self.0 % 10_000_000 * Self::INTERVAL;
+
+        Duration::new(self.0 / 10_000_000, nanos as u32)
+    }
+
+    /// Returns the amount of nanoseconds since the Unix epoch, which is
+    /// negative for times before it.
+    #[inline]
+    #[must_use]
+    pub const fn unix_nanos(&self) -> i128 {
+        (self.0 as i128 - Self::UNIX_EPOCH.0 as i128) * Self::INTERVAL as i128
+    }
+
+    /// Returns the time as a [`UnixTime`], unless it is before the Unix
+    /// epoch.
+    #[inline]
+    #[must_use]
+    pub const fn to_unix(&self) -> Option<UnixTime> {
+        match self.0.checked_sub(Self::UNIX_EPOCH.0) {
+            Some(intervals) => Some(UnixTime(FileTime(intervals).since_1601())),
+            None => None,
+        }
+    }
+
+    /// Returns a [`UnixTime`] as a [`FileTime`], rounded down to an
+    /// interval, if it fits.
+    #[inline]
+    #[must_use]
+    pub const fn from_unix(time: UnixTime) -> Option<FileTime> {
+        let intervals = time.0.as_nanos() / Self::INTERVAL as u128 + Self::UNIX_EPOCH.0 as u128;
+
+        if intervals > u64::MAX as u128 {
+            None
+        } else {
+            Some(FileTime(intervals as u64))
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl TryFrom<FileTime> for std::time::SystemTime {
+    type Error = SystemTimeError;
+
+    /// Fails for times the system clock cannot represent.
+    #[inline]
+    fn try_from(time: FileTime) -> Result<Self, Self::Error> {
+        let since_1601 = time.since_1601();
+        let epoch = FileTime::UNIX_EPOCH.since_1601();
+
+        match since_1601.checked_sub(epoch) {
+            Some(since_epoch) => std::time::UNIX_EPOCH.checked_add(since_epoch),
+            None => std::time::UNIX_EPOCH.checked_sub(epoch - since_1601),
+        }
+        .ok_or(SystemTimeError(()))
+    }
+}
+
+/// A [`Decoder`] and [`Encoder`] for a Windows [`FileTime`], as a 64-bit
+/// integer.
+///
+/// `FILETIME`s are little endian in NTFS, PE files and the registry,
+/// which is the default.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FileTimes<B: ByteOrder = LittleEndian> {
+    /// The byte order of the time.
+    pub byte_order: B,
+}
+
+impl<B: ByteOrder> FileTimes<B> {
+    /// Create a new decoder for a [`FileTime`].
+    #[inline]
+    #[must_use]
+    pub const fn new(byte_order: B) -> FileTimes<B> {
+        FileTimes { byte_order }
+    }
+}
+
+impl Default for FileTimes {
+    #[inline]
+    fn default() -> Self {
+        FileTimes::new(LittleEndian)
+    }
+}
+
+impl<B: ByteOrder> Decoder for FileTimes<B> {
+    type Item<'src> = FileTime;
+    type Error = ::core::convert::Infallible;
+
+    #[inline]
+    fn hint(&self) -> Size {
+        Size::new(8)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> SizeHint {
+        SizeHint::exact(self.hint())
+    }
+
+    #[inline]
+    fn decode<'s>(&mut self, src: &mut &'s [u8]) -> DResult<'s, Self> {
+        let Some((&bytes, rest)) = src.split_first_chunk() else {
+            return Err(DError::Incomplete {
+                needed: Size::new(8 - src.len()),
+            });
+        };
+
+        let value = match self.byte_order.endian() {
+            Endian::Little => u64::from_le_bytes(bytes),
+            Endian::Big => u64::from_be_bytes(bytes),
+        };
+
+        *src = rest;
+
+        Ok(FileTime(value))
+    }
+}
+
+impl<B: ByteOrder> Encoder<FileTime> for FileTimes<B> {
+    type Error = ::core::convert::Infallible;
+
+    #[inline]
+    fn encode_hint(&self, _: &FileTime) -> Size {
+        Size::new(8)
+    }
+
+    #[inline]
+    fn encode(&mut self, item: &FileTime, dst: &mut &mut [u8]) -> EResult<Self::Error> {
+        if dst.len() < 8 {
+            return Err(EError::Full {
+                needed: Size::new(8 - dst.len()),
+            });
+        }
+
+        let (bytes, rest) = core::mem::take(dst).split_at_mut(8);
+
+        bytes.copy_from_slice(&match self.byte_order.endian() {
+            Endian::Little => item.0.to_le_bytes(),
+            Endian::Big => item.0.to_be_bytes(),
+        });
+
+        *dst = rest;
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct TimestampError(());
+
+impl fmt::Display for TimestampError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("time too large for the timestamp")
+    }
+}
+
+impl core::error::Error for TimestampError {}
+
+/// Type for errors that may occur while converting a [`UnixTime`] or a
+/// [`FileTime`] into a [`SystemTime`](std::time::SystemTime).
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct SystemTimeError(());
+
+#[cfg(feature = "std")]
+impl fmt::Display for SystemTimeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("time out of range for the system clock")
+    }
+}
+
+#[cfg(feature = "std")]
+impl core::error::Error for SystemTimeError {}
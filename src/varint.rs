@@ -0,0 +1,1135 @@
+use core::fmt;
+
+use crate::decoder::{Decoder, Error as DError, Result as DResult};
+use crate::encoder::{Encoder, Error as EError, Result as EResult};
+use crate::{Size, SizeHint};
+
+/// A [`Decoder`] and [`Encoder`] for MQTT's variable byte integer.
+///
+/// Values are encoded in 1 to 4 bytes, 7 bits at a time with the least
+/// significant group first, where the high bit of each byte signals that
+/// another byte follows.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Mqtt;
+
+impl Mqtt {
+    /// The largest value representable by a variable byte integer.
+    pub const MAX: u32 = 268_435_455;
+
+    /// The maximum amount of bytes in a variable byte integer.
+    const MAX_LEN: usize = 4;
+
+    /// Create a new decoder for a variable byte integer.
+    #[inline]
+    #[must_use]
+    pub const fn new() -> Mqtt {
+        Mqtt
+    }
+
+    /// Returns the amount of bytes required to encode `value`.
+    #[inline]
+    #[must_use]
+    pub const fn encoded_len(value: u32) -> usize {
+        match value {
+            0..=0x7F => 1,
+            0x80..=0x3FFF => 2,
+            0x4000..=0x1F_FFFF => 3,
+            _ => 4,
+        }
+    }
+}
+
+impl Decoder for Mqtt {
+    type Item<'src> = u32;
+    type Error = MqttError;
+
+    #[inline]
+    fn hint(&self) -> Size {
+        Size::new(1)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> SizeHint {
+        SizeHint::new(self.hint(), Size::new(Self::MAX_LEN))
+    }
+
+    fn decode<'s>(&mut self, src: &mut &'s [u8]) -> DResult<'s, Self> {
+        let mut value = 0u32;
+
+        for (i, &byte) in src.iter().enumerate() {
+            if i == Self::MAX_LEN {
+                break;
+            }
+
+            value |= u32::from(byte & 0x7F) << (7 * i);
+
+            if byte & 0x80 == 0 {
+                // The encoding must use the minimum amount of bytes.
+                if i > 0 && byte == 0 {
+                    return Err(DError::Fatal {
+                        error: MqttError(()),
+                    });
+                }
+
+                *src = &src[i + 1..];
+
+                return Ok(value);
+            }
+        }
+
+        if src.len() >= Self::MAX_LEN {
+            Err(DError::Fatal {
+                error: MqttError(()),
+            })
+        } else {
+            Err(DError::Incomplete {
+                needed: Size::new(1),
+            })
+        }
+    }
+}
+
+impl Encoder<u32> for Mqtt {
+    type Error = MqttError;
+
+    #[inline]
+    fn encode_hint(&self, item: &u32) -> Size {
+        Size::new(Self::encoded_len(*item))
+    }
+
+    fn encode(&mut self, item: &u32, dst: &mut &mut [u8]) -> EResult<Self::Error> {
+        let mut value = *item;
+
+        if value > Self::MAX {
+            return Err(EError::Fatal {
+                error: MqttError(()),
+            });
+        }
+
+        let len = Self::encoded_len(value);
+
+        if dst.len() < len {
+            return Err(EError::Full {
+                needed: Size::new(len - dst.len()),
+            });
+        }
+
+        let (bytes, rest) = core::mem::take(dst).split_at_mut(len);
+
+        for byte in bytes.iter_mut() {
+            *byte = (value & 0x7F) as u8;
+            value >>= 7;
+
+            if value != 0 {
+                *byte |= 0x80;
+            }
+        }
+
+        *dst = rest;
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct MqttError(());
+
+impl fmt::Display for MqttError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("malformed variable byte integer")
+    }
+}
+
+impl core::error::Error for MqttError {}
+
+/// A [`Decoder`] and [`Encoder`] for variable-length quantities, as used
+/// by MIDI.
+///
+/// Values are encoded 7 bits at a time with the most significant group
+/// first, where the high bit of each byte signals that another byte follows.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Vlq {
+    /// The maximum amount of bytes in a quantity.
+    ///
+    /// This is clamped to the `10` bytes required for a [`prim@u64`].
+    pub max_len: usize,
+}
+
+impl Vlq {
+    /// The maximum amount of bytes required for a [`prim@u64`].
+    const MAX_LEN: usize = 10;
+
+    /// A decoder limited to the 4 byte quantities used by MIDI.
+    pub const MIDI: Vlq = Vlq::new(4);
+
+    /// Create a new decoder for a variable-length quantity of at most
+    /// `max_len` bytes.
+    #[inline]
+    #[must_use]
+    pub const fn new(max_len: usize) -> Vlq {
+        Vlq { max_len }
+    }
+
+    /// Returns the amount of bytes required to encode `value`.
+    #[inline]
+    #[must_use]
+    pub const fn encoded_len(value: u64) -> usize {
+        match value {
+            0 => 1,
+            _ => (u64::BITS - value.leading_zeros()).div_ceil(7) as usize,
+        }
+    }
+
+    #[inline]
+    const fn max_len(&self) -> usize {
+        if self.max_len < Self::MAX_LEN {
+            self.max_len
+        } else {
+            Self::MAX_LEN
+        }
+    }
+}
+
+impl Default for Vlq {
+    #[inline]
+    fn default() -> Self {
+        Vlq::new(Self::MAX_LEN)
+    }
+}
+
+impl Decoder for Vlq {
+    type Item<'src> = u64;
+    type Error = VlqError;
+
+    #[inline]
+    fn hint(&self) -> Size {
+        Size::new(1)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> SizeHint {
+        SizeHint::new(self.hint(), Size::new(self.max_len().max(1)))
+    }
+
+    fn decode<'s>(&mut self, src: &mut &'s [u8]) -> DResult<'s, Self> {
+        let max_len = self.max_len();
+        let mut value = 0u64;
+
+        for (i, &byte) in src.iter().enumerate().take(max_len) {
+            if value >> (u64::BITS - 7) != 0 {
+                return Err(DError::Fatal {
+                    error: VlqError(()),
+                });
+            }
+
+            value = (value << 7) | u64::from(byte & 0x7F);
+
+            if byte & 0x80 == 0 {
+                *src = &src[i + 1..];
+
+                return Ok(value);
+            }
+        }
+
+        if src.len() >= max_len {
+            Err(DError::Fatal {
+                error: VlqError(()),
+            })
+        } else {
+            Err(DError::Incomplete {
+                needed: Size::new(1),
+            })
+        }
+    }
+}
+
+impl Encoder<u64> for Vlq {
+    type Error = VlqError;
+
+    #[inline]
+    fn encode_hint(&self, item: &u64) -> Size {
+        Size::new(Self::encoded_len(*item))
+    }
+
+    fn encode(&mut self, item: &u64, dst: &mut &mut [u8]) -> EResult<Self::Error> {
+        let len = Self::encoded_len(*item);
+
+        if len > self.max_len() {
+            return Err(EError::Fatal {
+                error: VlqError(()),
+            });
+        }
+
+        if dst.len() < len {
+            return Err(EError::Full {
+                needed: Size::new(len - dst.len()),
+            });
+        }
+
+        let (bytes, rest) = core::mem::take(dst).split_at_mut(len);
+
+        for (i, byte) in bytes.iter_mut().enumerate() {
+            let shift = 7 * (len - 1 - i);
+
+            *byte = ((*item >> shift) & 0x7F) as u8;
+
+            if shift != 0 {
+                *byte |= 0x80;
+            }
+        }
+
+        *dst = rest;
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct VlqError(());
+
+impl fmt::Display for VlqError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("malformed variable-length quantity")
+    }
+}
+
+impl core::error::Error for VlqError {}
+
+/// A [`Decoder`] and [`Encoder`] for QUIC's variable-length integer.
+///
+/// The two high bits of the first byte select a 1, 2, 4, or 8 byte
+/// big endian encoding of the remaining bits.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Quic;
+
+impl Quic {
+    /// The largest value representable by a variable-length integer.
+    pub const MAX: u64 = (1 << 62) - 1;
+
+    /// Create a new decoder for a variable-length integer.
+    #[inline]
+    #[must_use]
+    pub const fn new() -> Quic {
+        Quic
+    }
+
+    /// Returns the amount of bytes required to encode `value`.
+    ///
+    /// Values above [`Quic::MAX`] are not representable.
+    #[inline]
+    #[must_use]
+    pub const fn encoded_len(value: u64) -> usize {
+        match value {
+            0..=0x3F => 1,
+            0x40..=0x3FFF => 2,
+            0x4000..=0x3FFF_FFFF => 4,
+            _ => 8,
+        }
+    }
+}
+
+impl Decoder for Quic {
+    type Item<'src> = u64;
+    type Error = ::core::convert::Infallible;
+
+    #[inline]
+    fn hint(&self) -> Size {
+        Size::new(1)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> SizeHint {
+        SizeHint::new(self.hint(), Size::new(8))
+    }
+
+    #[inline]
+    fn decode<'s>(&mut self, src: &mut &'s [u8]) -> DResult<'s, Self> {
+        let Some(&first) = src.first() else {
+            return Err(DError::Incomplete {
+                needed: Size::new(1),
+            });
+        };
+
+        let len = 1 << (first >> 6);
+
+        let Some((bytes, rest)) = src.split_at_checked(len) else {
+            return Err(DError::Incomplete {
+                needed: Size::new(len - src.len()),
+            });
+        };
+
+        let value = bytes[1..]
+            .iter()
+            .fold(u64::from(first & 0x3F), |value, &byte| {
+                (value << 8) | u64::from(byte)
+            });
+
+        *src = rest;
+
+        Ok(value)
+    }
+}
+
+impl Encoder<u64> for Quic {
+    type Error = QuicError;
+
+    #[inline]
+    fn encode_hint(&self, item: &u64) -> Size {
+        Size::new(Self::encoded_len(*item))
+    }
+
+    fn encode(&mut self, item: &u64, dst: &mut &mut [u8]) -> EResult<Self::Error> {
+        if *item > Self::MAX {
+            return Err(EError::Fatal {
+                error: QuicError(()),
+            });
+        }
+
+        let len = Self::encoded_len(*item);
+
+        if dst.len() < len {
+            return Err(EError::Full {
+                needed: Size::new(len - dst.len()),
+            });
+        }
+
+        let (bytes, rest) = core::mem::take(dst).split_at_mut(len);
+
+        bytes.copy_from_slice(&item.to_be_bytes()[8 - len..]);
+        bytes[0] |= (len.trailing_zeros() as u8) << 6;
+
+        *dst = rest;
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct QuicError(());
+
+impl fmt::Display for QuicError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("value too large for a variable-length integer")
+    }
+}
+
+impl core::error::Error for QuicError {}
+
+/// A [`Decoder`] and [`Encoder`] for unsigned LEB128 integers, as used by
+/// postcard, protobuf and WebAssembly.
+///
+/// Values are encoded in 1 to 10 bytes, 7 bits at a time with the least
+/// significant group first, where the high bit of each byte signals that
+/// another byte follows.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Leb128;
+
+impl Leb128 {
+    /// The maximum amount of bytes required for a [`prim@u64`].
+    const MAX_LEN: usize = 10;
+
+    /// Create a new decoder for a LEB128 integer.
+    #[inline]
+    #[must_use]
+    pub const fn new() -> Leb128 {
+        Leb128
+    }
+
+    /// Returns the amount of bytes required to encode `value`.
+    #[inline]
+    #[must_use]
+    pub const fn encoded_len(value: u64) -> usize {
+        match value {
+            0 => 1,
+            _ => (u64::BITS - value.leading_zeros()).div_ceil(7) as usize,
+        }
+    }
+}
+
+impl Decoder for Leb128 {
+    type Item<'src> = u64;
+    type Error = Leb128Error;
+
+    #[inline]
+    fn hint(&self) -> Size {
+        Size::new(1)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> SizeHint {
+        SizeHint::new(self.hint(), Size::new(Self::MAX_LEN))
+    }
+
+    fn decode<'s>(&mut self, src: &mut &'s [u8]) -> DResult<'s, Self> {
+        let mut value = 0u64;
+
+        for (i, &byte) in src.iter().enumerate().take(Self::MAX_LEN) {
+            let bits = u64::from(byte & 0x7F);
+
+            // The last byte only holds the highest bit of a u64.
+            if i == Self::MAX_LEN - 1 && bits > 1 {
+                return Err(DError::Fatal {
+                    error: Leb128Error(()),
+                });
+            }
+
+            value |= bits << (7 * i);
+
+            if byte & 0x80 == 0 {
+                *src = &src[i + 1..];
+
+                return Ok(value);
+            }
+        }
+
+        if src.len() >= Self::MAX_LEN {
+            Err(DError::Fatal {
+                error: Leb128Error(()),
+            })
+        } else {
+            Err(DError::Incomplete {
+                needed: Size::new(1),
+            })
+        }
+    }
+}
+
+impl Encoder<u64> for Leb128 {
+    type Error = ::core::convert::Infallible;
+
+    #[inline]
+    fn encode_hint(&self, item: &u64) -> Size {
+        Size::new(Self::encoded_len(*item))
+    }
+
+    fn encode(&mut self, item: &u64, dst: &mut &mut [u8]) -> EResult<Self::Error> {
+        let mut value = *item;
+        let len = Self::encoded_len(value);
+
+        if dst.len() < len {
+            return Err(EError::Full {
+                needed: Size::new(len - dst.len()),
+            });
+        }
+
+        let (bytes, rest) = core::mem::take(dst).split_at_mut(len);
+
+        for byte in bytes.iter_mut() {
+            *byte = (value & 0x7F) as u8;
+            value >>= 7;
+
+            if value != 0 {
+                *byte |= 0x80;
+            }
+        }
+
+        *dst = rest;
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Leb128Error(());
+
+impl fmt::Display for Leb128Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("malformed LEB128 integer")
+    }
+}
+
+impl core::error::Error for Leb128Error {}
+
+/// A [`Decoder`] and [`Encoder`] for signed integers, zigzag encoded into
+/// an unsigned variable-length integer, as used by postcard and protobuf.
+///
+/// Zigzag encoding maps `0, -1, 1, -2, ...` to `0, 1, 2, 3, ...`, so
+/// values of a small magnitude have a short encoding.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ZigZag<D = Leb128> {
+    /// The codec for the unsigned integer.
+    pub inner: D,
+}
+
+impl<D> ZigZag<D> {
+    /// Create a new decoder for zigzag encoded integers.
+    #[inline]
+    #[must_use]
+    pub const fn new(inner: D) -> ZigZag<D> {
+        ZigZag { inner }
+    }
+}
+
+impl<D> Decoder for ZigZag<D>
+where
+    D: for<'a> Decoder<Item<'a> = u64>,
+{
+    type Item<'src> = i64;
+    type Error = D::Error;
+
+    #[inline]
+    fn hint(&self) -> Size {
+        self.inner.hint()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> SizeHint {
+        self.inner.size_hint()
+    }
+
+    #[inline]
+    fn decode<'s>(&mut self, src: &mut &'s [u8]) -> DResult<'s, Self> {
+        let value = self.inner.decode(src)?;
+
+        Ok((value >> 1) as i64 ^ -((value & 1) as i64))
+    }
+}
+
+impl<D: Encoder<u64>> Encoder<i64> for ZigZag<D> {
+    type Error = D::Error;
+
+    #[inline]
+    fn encode_hint(&self, item: &i64) -> Size {
+        self.inner
+            .encode_hint(&(((item << 1) ^ (item >> 63)) as u64))
+    }
+
+    #[inline]
+    fn encode(&mut self, item: &i64, dst: &mut &mut [u8]) -> EResult<Self::Error> {
+        self.inner
+            .encode(&(((item << 1) ^ (item >> 63)) as u64), dst)
+    }
+}
+
+/// A [`Decoder`] and [`Encoder`] for EBML element IDs, as used by
+/// Matroska and WebM.
+///
+/// IDs are 1 to 4 bytes, where the amount of leading zero bits of the
+/// first byte is the amount of bytes that follow. The length marker is
+/// kept as part of the ID, as is conventional, so the EBML header is
+/// `0x1A45DFA3`.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct EbmlId;
+
+impl EbmlId {
+    /// The maximum amount of bytes in an element ID.
+    const MAX_LEN: usize = 4;
+
+    /// Create a new decoder for an element ID.
+    #[inline]
+    #[must_use]
+    pub const fn new() -> EbmlId {
+        EbmlId
+    }
+
+    /// Returns the amount of bytes in the encoding of `id`, if it is a
+    /// valid element ID.
+    #[inline]
+    #[must_use]
+    pub const fn encoded_len(id: u32) -> Option<usize> {
+        let len = (u32::BITS - id.leading_zeros()).div_ceil(8) as usize;
+
+        match len {
+            1..=Self::MAX_LEN if id >> (7 * len) == 1 => Some(len),
+            _ => None,
+        }
+    }
+}
+
+impl Decoder for EbmlId {
+    type Item<'src> = u32;
+    type Error = EbmlError;
+
+    #[inline]
+    fn hint(&self) -> Size {
+        Size::new(1)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> SizeHint {
+        SizeHint::new(self.hint(), Size::new(Self::MAX_LEN))
+    }
+
+    fn decode<'s>(&mut self, src: &mut &'s [u8]) -> DResult<'s, Self> {
+        let Some(&first) = src.first() else {
+            return Err(DError::Incomplete {
+                needed: Size::new(1),
+            });
+        };
+
+        let len = first.leading_zeros() as usize + 1;
+
+        if len > Self::MAX_LEN {
+            return Err(DError::Fatal {
+                error: EbmlError(()),
+            });
+        }
+
+        let Some((bytes, rest)) = src.split_at_checked(len) else {
+            return Err(DError::Incomplete {
+                needed: Size::new(len - src.len()),
+            });
+        };
+
+        *src = rest;
+
+        Ok(bytes.iter().fold(0, |id, &b| id << 8 | u32::from(b)))
+    }
+}
+
+impl Encoder<u32> for EbmlId {
+    type Error = EbmlError;
+
+    #[inline]
+    fn encode_hint(&self, item: &u32) -> Size {
+        Self::encoded_len(*item).map_or(Size::Unknown, Size::new)
+    }
+
+    fn encode(&mut self, item: &u32, dst: &mut &mut [u8]) -> EResult<Self::Error> {
+        let Some(len) = Self::encoded_len(*item) else {
+            return Err(EError::Fatal {
+                error: EbmlError(()),
+            });
+        };
+
+        if dst.len() < len {
+            return Err(EError::Full {
+                needed: Size::new(len - dst.len()),
+            });
+        }
+
+        let (bytes, rest) = core::mem::take(dst).split_at_mut(len);
+
+        bytes.copy_from_slice(&item.to_be_bytes()[4 - len..]);
+
+        *dst = rest;
+
+        Ok(())
+    }
+}
+
+/// A [`Decoder`] and [`Encoder`] for EBML element data sizes, as used by
+/// Matroska and WebM.
+///
+/// Sizes are 1 to 8 bytes, where the amount of leading zero bits of the
+/// first byte is the amount of bytes that follow, and the rest of the bits
+/// are the size. A size of all one bits is an unknown size, which is
+/// yielded as `None`, such as for live streams.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct EbmlSize;
+
+impl EbmlSize {
+    /// The largest known size representable by a data size.
+    pub const MAX: u64 = (1 << 56) - 2;
+
+    /// The maximum amount of bytes in a data size.
+    const MAX_LEN: usize = 8;
+
+    /// Create a new decoder for a data size.
+    #[inline]
+    #[must_use]
+    pub const fn new() -> EbmlSize {
+        EbmlSize
+    }
+
+    /// Returns the amount of bytes required to encode `size`, which is one
+    /// for an unknown size.
+    #[inline]
+    #[must_use]
+    pub const fn encoded_len(size: Option<u64>) -> usize {
+        match size {
+            // The value of all one bits is reserved for unknown sizes.
+            Some(size) => (u64::BITS - size.saturating_add(1).leading_zeros()).div_ceil(7) as usize,
+            None => 1,
+        }
+    }
+}
+
+impl Decoder for EbmlSize {
+    type Item<'src> = Option<u64>;
+    type Error = EbmlError;
+
+    #[inline]
+    fn hint(&self) -> Size {
+        Size::new(1)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> SizeHint {
+        SizeHint::new(self.hint(), Size::new(Self::MAX_LEN))
+    }
+
+    fn decode<'s>(&mut self, src: &mut &'s [u8]) -> DResult<'s, Self> {
+        let Some(&first) = src.first() else {
+            return Err(DError::Incomplete {
+                needed: Size::new(1),
+            });
+        };
+
+        if first == 0 {
+            return Err(DError::Fatal {
+                error: EbmlError(()),
+            });
+        }
+
+        let len = first.leading_zeros() as usize + 1;
+
+        let Some((bytes, rest)) = src.split_at_checked(len) else {
+            return Err(DError::Incomplete {
+                needed: Size::new(len - src.len()),
+            });
+        };
+
+        let mask = u64::MAX >> (64 - 7 * len);
+        let size = bytes.iter().fold(0, |n, &b| n << 8 | u64::from(b)) & mask;
+
+        *src = rest;
+
+        Ok((size != mask).then_some(size))
+    }
+}
+
+impl Encoder<Option<u64>> for EbmlSize {
+    type Error = EbmlError;
+
+    #[inline]
+    fn encode_hint(&self, item: &Option<u64>) -> Size {
+        Size::new(Self::encoded_len(*item))
+    }
+
+    fn encode(&mut self, item: &Option<u64>, dst: &mut &mut [u8]) -> EResult<Self::Error> {
+        if item.is_some_and(|size| size > Self::MAX) {
+            return Err(EError::Fatal {
+                error: EbmlError(()),
+            });
+        }
+
+        let len = Self::encoded_len(*item);
+
+        if dst.len() < len {
+            return Err(EError::Full {
+                needed: Size::new(len - dst.len()),
+            });
+        }
+
+        let (bytes, rest) = core::mem::take(dst).split_at_mut(len);
+        let size = item.unwrap_or(u64::MAX >> (64 - 7 * len)) | 1 << (7 * len);
+
+        bytes.copy_from_slice(&size.to_be_bytes()[8 - len..]);
+
+        *dst = rest;
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct EbmlError(());
+
+impl fmt::Display for EbmlError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("malformed EBML variable-size integer")
+    }
+}
+
+impl core::error::Error for EbmlError {}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use std::vec::Vec;
+
+    use super::*;
+
+    fn encode<T: ?Sized, E: Encoder<T>>(mut encoder: E, item: &T) -> Vec<u8> {
+        let mut buf = [0; 16];
+        let mut dst = &mut buf[..];
+
+        encoder.encode(item, &mut dst).unwrap();
+
+        let len = 16 - dst.len();
+
+        buf[..len].to_vec()
+    }
+
+    fn decode<D>(mut decoder: D, bytes: &[u8]) -> DResult<'_, D>
+    where
+        D: Decoder,
+    {
+        let mut src = bytes;
+        let item = decoder.decode(&mut src)?;
+
+        assert!(src.is_empty(), "trailing bytes: {src:?}");
+
+        Ok(item)
+    }
+
+    fn incomplete<T, E>(needed: usize) -> Result<T, DError<E>> {
+        Err(DError::Incomplete {
+            needed: Size::new(needed),
+        })
+    }
+
+    #[test]
+    fn mqtt_round_trip() {
+        let cases: [(u32, &[u8]); 8] = [
+            (0, &[0x00]),
+            (127, &[0x7f]),
+            (128, &[0x80, 0x01]),
+            (16_383, &[0xff, 0x7f]),
+            (16_384, &[0x80, 0x80, 0x01]),
+            (2_097_151, &[0xff, 0xff, 0x7f]),
+            (2_097_152, &[0x80, 0x80, 0x80, 0x01]),
+            (Mqtt::MAX, &[0xff, 0xff, 0xff, 0x7f]),
+        ];
+
+        for (value, bytes) in cases {
+            assert_eq!(encode(Mqtt, &value), bytes);
+            assert_eq!(decode(Mqtt, bytes), Ok(value));
+            assert_eq!(Mqtt::encoded_len(value), bytes.len());
+        }
+    }
+
+    #[test]
+    fn mqtt_rejects_malformed() {
+        let fatal = Err(DError::Fatal {
+            error: MqttError(()),
+        });
+
+        assert_eq!(Mqtt.decode(&mut &[0xff, 0xff, 0xff, 0xff, 0x7f][..]), fatal);
+        assert_eq!(Mqtt.decode(&mut &[0x80, 0x00][..]), fatal);
+        assert_eq!(Mqtt.decode(&mut &[0x80, 0x80][..]), incomplete(1));
+        assert_eq!(Mqtt.decode(&mut &[][..]), incomplete(1));
+        assert_eq!(
+            Mqtt.encode(&(Mqtt::MAX + 1), &mut &mut [0; 4][..]),
+            Err(EError::Fatal {
+                error: MqttError(())
+            })
+        );
+        assert_eq!(
+            Mqtt.encode(&16_384, &mut &mut [0; 1][..]),
+            Err(EError::Full {
+                needed: Size::new(2)
+            })
+        );
+    }
+
+    #[test]
+    fn vlq_round_trip() {
+        let cases: [(u64, &[u8]); 8] = [
+            (0, &[0x00]),
+            (0x40, &[0x40]),
+            (0x7f, &[0x7f]),
+            (0x80, &[0x81, 0x00]),
+            (0x2000, &[0xc0, 0x00]),
+            (0x3fff, &[0xff, 0x7f]),
+            (0x4000, &[0x81, 0x80, 0x00]),
+            (0x0fff_ffff, &[0xff, 0xff, 0xff, 0x7f]),
+        ];
+
+        for (value, bytes) in cases {
+            assert_eq!(encode(Vlq::MIDI, &value), bytes);
+            assert_eq!(decode(Vlq::MIDI, bytes), Ok(value));
+        }
+
+        assert_eq!(
+            decode(Vlq::default(), &encode(Vlq::default(), &u64::MAX)),
+            Ok(u64::MAX)
+        );
+    }
+
+    #[test]
+    fn vlq_rejects_malformed() {
+        let fatal = Err(DError::Fatal {
+            error: VlqError(()),
+        });
+
+        assert_eq!(
+            Vlq::new(4).decode(&mut &[0x81, 0x80, 0x80, 0x80, 0x00][..]),
+            fatal
+        );
+        assert_eq!(Vlq::default().decode(&mut &[0xff; 11][..]), fatal);
+        assert_eq!(Vlq::new(4).decode(&mut &[0x81][..]), incomplete(1));
+        assert_eq!(
+            Vlq::new(4).encode(&0x1000_0000, &mut &mut [0; 8][..]),
+            Err(EError::Fatal {
+                error: VlqError(())
+            })
+        );
+    }
+
+    #[test]
+    fn quic_round_trip() {
+        // The examples of RFC 9000, appendix A.1.
+        let cases: [(u64, &[u8]); 4] = [
+            (
+                151_288_809_941_952_652,
+                &[0xc2, 0x19, 0x7c, 0x5e, 0xff, 0x14, 0xe8, 0x8c],
+            ),
+            (494_878_333, &[0x9d, 0x7f, 0x3e, 0x7d]),
+            (15_293, &[0x7b, 0xbd]),
+            (37, &[0x25]),
+        ];
+
+        for (value, bytes) in cases {
+            assert_eq!(encode(Quic, &value), bytes);
+            assert_eq!(decode(Quic, bytes), Ok(value));
+        }
+
+        assert_eq!(decode(Quic, &[0x40, 0x25]), Ok(37));
+        assert_eq!(decode(Quic, &encode(Quic, &Quic::MAX)), Ok(Quic::MAX));
+    }
+
+    #[test]
+    fn quic_edge_cases() {
+        assert_eq!(Quic.decode(&mut &[][..]), incomplete(1));
+        assert_eq!(Quic.decode(&mut &[0x9d, 0x7f][..]), incomplete(2));
+        assert_eq!(
+            Quic.encode(&(Quic::MAX + 1), &mut &mut [0; 8][..]),
+            Err(EError::Fatal {
+                error: QuicError(())
+            })
+        );
+    }
+
+    #[test]
+    fn leb128_round_trip() {
+        let max = [0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x01];
+        let cases: [(u64, &[u8]); 5] = [
+            (0, &[0x00]),
+            (127, &[0x7f]),
+            (128, &[0x80, 0x01]),
+            (624_485, &[0xe5, 0x8e, 0x26]),
+            (u64::MAX, &max),
+        ];
+
+        for (value, bytes) in cases {
+            assert_eq!(encode(Leb128, &value), bytes);
+            assert_eq!(decode(Leb128, bytes), Ok(value));
+            assert_eq!(Leb128::encoded_len(value), bytes.len());
+        }
+    }
+
+    #[test]
+    fn leb128_rejects_overflow() {
+        let fatal = Err(DError::Fatal {
+            error: Leb128Error(()),
+        });
+        let mut overflow = [0xff; 10];
+
+        overflow[9] = 0x02;
+
+        assert_eq!(Leb128.decode(&mut &overflow[..]), fatal);
+        assert_eq!(Leb128.decode(&mut &[0xff; 11][..]), fatal);
+        assert_eq!(Leb128.decode(&mut &[0x80; 9][..]), incomplete(1));
+    }
+
+    #[test]
+    fn zigzag_round_trip() {
+        let cases: [(i64, &[u8]); 6] = [
+            (0, &[0x00]),
+            (-1, &[0x01]),
+            (1, &[0x02]),
+            (-2, &[0x03]),
+            (-64, &[0x7f]),
+            (64, &[0x80, 0x01]),
+        ];
+
+        for (value, bytes) in cases {
+            assert_eq!(encode(ZigZag::<Leb128>::default(), &value), bytes);
+            assert_eq!(decode(ZigZag::<Leb128>::default(), bytes), Ok(value));
+        }
+
+        for value in [i64::MIN, i64::MAX] {
+            let bytes = encode(ZigZag::<Leb128>::default(), &value);
+
+            assert_eq!(decode(ZigZag::<Leb128>::default(), &bytes), Ok(value));
+        }
+    }
+
+    #[test]
+    fn ebml_id_round_trip() {
+        let cases: [(u32, &[u8]); 4] = [
+            (0x81, &[0x81]),
+            (0x4286, &[0x42, 0x86]),
+            (0x2a_d7b1, &[0x2a, 0xd7, 0xb1]),
+            (0x1a45_dfa3, &[0x1a, 0x45, 0xdf, 0xa3]),
+        ];
+
+        for (id, bytes) in cases {
+            assert_eq!(encode(EbmlId, &id), bytes);
+            assert_eq!(decode(EbmlId, bytes), Ok(id));
+        }
+    }
+
+    #[test]
+    fn ebml_id_rejects_malformed() {
+        assert_eq!(
+            EbmlId.decode(&mut &[0x08, 0, 0, 0, 0][..]),
+            Err(DError::Fatal {
+                error: EbmlError(())
+            })
+        );
+        assert_eq!(EbmlId.decode(&mut &[0x1a, 0x45][..]), incomplete(2));
+        assert_eq!(EbmlId::encoded_len(0x12), None);
+        assert_eq!(
+            EbmlId.encode(&0x12, &mut &mut [0; 4][..]),
+            Err(EError::Fatal {
+                error: EbmlError(())
+            })
+        );
+    }
+
+    #[test]
+    fn ebml_size_round_trip() {
+        let cases: [(Option<u64>, &[u8]); 5] = [
+            (Some(0), &[0x80]),
+            (Some(126), &[0xfe]),
+            (Some(127), &[0x40, 0x7f]),
+            (None, &[0xff]),
+            (
+                Some(EbmlSize::MAX),
+                &[0x01, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xfe],
+            ),
+        ];
+
+        for (size, bytes) in cases {
+            assert_eq!(encode(EbmlSize, &size), bytes);
+            assert_eq!(decode(EbmlSize, bytes), Ok(size));
+        }
+
+        assert_eq!(
+            decode(EbmlSize, &[0x01, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff]),
+            Ok(None)
+        );
+    }
+
+    #[test]
+    fn ebml_size_rejects_malformed() {
+        let fatal = Err(DError::Fatal {
+            error: EbmlError(()),
+        });
+
+        assert_eq!(EbmlSize.decode(&mut &[0x00][..]), fatal);
+        assert_eq!(EbmlSize.decode(&mut &[0x40][..]), incomplete(1));
+        assert_eq!(
+            EbmlSize.encode(&Some(EbmlSize::MAX + 1), &mut &mut [0; 8][..]),
+            Err(EError::Fatal {
+                error: EbmlError(())
+            })
+        );
+    }
+}